@@ -0,0 +1,61 @@
+#!/usr/bin/env python3
+"""Stub HWI-compatible signer for exercising bitcoin-tui's --signer support.
+
+Speaks the subset of the HWI command line that bitcoind's -signer and bitcoin-tui use:
+
+    stub-signer.py enumerate
+    echo "signtx <psbt>" | stub-signer.py --stdin --fingerprint 00000001 --chain regtest
+
+No keys are involved. `signtx` answers with the PSBT read from the file named by
+STUB_SIGNER_PSBT (e.g. one signed beforehand with walletprocesspsbt), or echoes the
+input PSBT unchanged if the variable is unset. STUB_SIGNER_FINGERPRINT overrides the
+reported fingerprint and STUB_SIGNER_FAIL makes every command return an HWI error.
+"""
+
+import json
+import os
+import sys
+
+FINGERPRINT = os.environ.get("STUB_SIGNER_FINGERPRINT", "00000001")
+
+
+def fail(message):
+    print(json.dumps({"error": message, "code": -1}))
+    sys.exit(0)
+
+
+def main(argv):
+    if os.environ.get("STUB_SIGNER_FAIL"):
+        fail(os.environ["STUB_SIGNER_FAIL"])
+
+    if argv[:1] == ["enumerate"]:
+        print(json.dumps([{
+            "type": "stub",
+            "model": "stub_signer",
+            "path": "stub:0",
+            "fingerprint": FINGERPRINT,
+            "needs_pin_sent": False,
+            "needs_passphrase_sent": False,
+        }]))
+        return
+
+    if "--stdin" not in argv:
+        fail("unsupported command: " + " ".join(argv))
+    if "--fingerprint" in argv:
+        requested = argv[argv.index("--fingerprint") + 1]
+        if requested != FINGERPRINT:
+            fail("no device with fingerprint " + requested)
+
+    command, _, psbt = sys.stdin.read().strip().partition(" ")
+    if command != "signtx" or not psbt:
+        fail("expected 'signtx <psbt>' on stdin")
+
+    signed_path = os.environ.get("STUB_SIGNER_PSBT")
+    if signed_path:
+        with open(signed_path) as f:
+            psbt = f.read().strip()
+    print(json.dumps({"psbt": psbt}))
+
+
+if __name__ == "__main__":
+    main(sys.argv[1:])
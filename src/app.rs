@@ -1,13 +1,32 @@
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::Instant;
 
 use crossterm::event::KeyEvent;
 use ratatui::widgets::ListState;
 
+use crate::arg_form::{ArgForm, FieldKind, RowKind};
+use crate::call_history::{CallHistory, CallRecord, FavoriteCall, HistoryRow, has_secret_params};
+use crate::file_picker::{FileMode, FilePicker, PickerAction};
+use crate::jq;
+use crate::json_tree::{Export, JsonTree, TreeAction};
+use crate::method_class::{MethodClass, classify};
+use crate::node_methods::{self, NodeMethods};
+use crate::peer_history::{PeerEvent, PeerHistoryStore};
+use crate::peers_export::{self, ExportFormat};
 use crate::peers_query::{self, PeerQuery};
+use crate::psbt::{Encoding, Node as PsbtNode, Psbt};
+use crate::psbt_create::{CreateForm, FormRow};
+use crate::psbt_process::{ProcessForm, ProcessMode, ProcessRow};
+use crate::psbt_qr::QrView;
+use crate::psbt_summary::PsbtSummary;
+use crate::rpc_macro::{self, Macro, Step, StepReport};
 use crate::rpc_types::*;
-use crate::wallet_schema::{RpcMethod, load_non_wallet_methods, load_wallet_methods};
+use crate::storage;
+use crate::text_area::TextArea;
+use crate::wallet_schema::{
+    RpcMethod, Schema, load_non_wallet_methods, load_wallet_methods, load_wallet_psbt_methods,
+};
 
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
 pub enum Tab {
@@ -83,10 +102,14 @@ pub enum InputMode {
     TxSearch,
     ArgInput,
     WalletPicker,
-    PsbtSaveName,
+    SaveName,
     MethodSearch,
     DetailSearch,
+    DetailFilter,
     PeersQuery,
+    PsbtForm,
+    PsbtEditor,
+    ArgForm,
 }
 
 pub struct PollResult {
@@ -103,15 +126,20 @@ pub enum SearchResult {
     Mempool {
         txid: String,
         entry: MempoolEntry,
-        decoded: Option<String>,
+        decoded: Option<serde_json::Value>,
     },
     Confirmed {
         txid: String,
         tx: RawTransaction,
-        decoded: Option<String>,
+        decoded: Option<serde_json::Value>,
     },
 }
 
+pub struct AddrmanResult {
+    pub info: Result<BTreeMap<String, AddrmanCounts>, String>,
+    pub addresses: Result<Vec<NodeAddress>, String>,
+}
+
 pub struct ZmqEntry {
     pub topic: String,
     pub hash: String,
@@ -119,16 +147,23 @@ pub struct ZmqEntry {
 
 pub enum Event {
     Key(KeyEvent),
+    /// Bracketed paste from the terminal.
+    Paste(String),
     Tick,
     PollComplete(Box<PollResult>),
     RecentBlocksComplete(Vec<BlockStats>),
     ChainTipsEnriched(Vec<ChainTip>),
     SearchComplete(u64, Box<Result<SearchResult, String>>),
-    WalletRpcComplete(u64, Box<Result<String, String>>),
-    RpcComplete(u64, Box<Result<String, String>>),
+    WalletRpcComplete(u64, Box<CallOutcome>),
+    RpcComplete(u64, Box<CallOutcome>),
+    NodeMethodsComplete(Box<Result<NodeMethods, String>>),
+    MacroStep(u64, Box<StepReport>),
+    MacroDone(u64),
     WalletListComplete(Box<Result<Vec<String>, String>>),
     PsbtRpcComplete(u64, Box<Result<PsbtRpcResult, String>>),
-    ZmqBlockComplete(Box<Result<String, String>>),
+    PsbtSummaryComplete(u64, Box<Result<PsbtSummary, String>>),
+    ZmqBlockComplete(Box<Result<serde_json::Value, String>>),
+    AddrmanComplete(u64, Box<AddrmanResult>),
     ZmqMessage(Box<ZmqEntry>),
     ZmqError(String),
 }
@@ -138,14 +173,77 @@ pub enum PsbtRpcAction {
     Decode,
     Analyze,
     WalletProcess,
+    /// `descriptorprocesspsbt` with descriptors picked in the process form.
+    DescriptorProcess,
     Finalize,
     UtxoUpdate,
+    Create,
+    Combine,
+    Join,
+    /// finalizepsbt with extraction, then testmempoolaccept on the resulting tx.
+    TestAccept,
+    Broadcast,
+    EnumerateSigners,
+    /// Signs through the `--signer` command instead of the node.
+    ExternalSign,
+    /// `walletprocesspsbt` from the signer list without `--signer`: the node signs with the
+    /// wallet's own external signer, so the wallet is checked to have one first.
+    WalletSign,
+}
+
+impl PsbtRpcAction {
+    /// The RPC whose result the action shows, for looking up its result schema.
+    pub fn rpc_method(self) -> Option<&'static str> {
+        match self {
+            PsbtRpcAction::Decode => Some("decodepsbt"),
+            PsbtRpcAction::Analyze => Some("analyzepsbt"),
+            PsbtRpcAction::WalletProcess | PsbtRpcAction::WalletSign => Some("walletprocesspsbt"),
+            PsbtRpcAction::DescriptorProcess => Some("descriptorprocesspsbt"),
+            PsbtRpcAction::Finalize => Some("finalizepsbt"),
+            PsbtRpcAction::UtxoUpdate => Some("utxoupdatepsbt"),
+            PsbtRpcAction::Create => Some("walletcreatefundedpsbt"),
+            PsbtRpcAction::Combine => Some("combinepsbt"),
+            PsbtRpcAction::Join => Some("joinpsbts"),
+            PsbtRpcAction::TestAccept => Some("testmempoolaccept"),
+            PsbtRpcAction::Broadcast => Some("sendrawtransaction"),
+            PsbtRpcAction::EnumerateSigners => Some("enumeratesigners"),
+            PsbtRpcAction::ExternalSign => None,
+        }
+    }
+}
+
+/// A finished call from the RPC or Wallet tab, with what the history records.
+pub struct CallOutcome {
+    pub method: String,
+    /// `None` when the arguments didn't parse, so nothing reached the node.
+    pub params: Option<serde_json::Value>,
+    pub wallet: Option<String>,
+    /// Unix time the call was sent.
+    pub time: u64,
+    pub duration_ms: u64,
+    pub result: Result<serde_json::Value, String>,
 }
 
 pub struct PsbtRpcResult {
     pub action: PsbtRpcAction,
-    pub output_json: String,
+    pub output: serde_json::Value,
     pub updated_psbt: Option<String>,
+    pub broadcast_check: Option<BroadcastCheck>,
+    pub broadcast_txid: Option<String>,
+    pub signers: Option<Vec<ExternalSigner>>,
+}
+
+/// Outcome of `testmempoolaccept` for an extracted transaction, awaiting confirmation.
+pub struct BroadcastCheck {
+    pub hex: String,
+    pub txid: String,
+    pub allowed: bool,
+    pub reject_reason: Option<String>,
+    pub vsize: Option<u64>,
+    /// Base fee in BTC.
+    pub fee: Option<f64>,
+    /// Effective fee rate in BTC/kvB.
+    pub effective_feerate: Option<f64>,
 }
 
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
@@ -161,17 +259,37 @@ pub struct MethodBrowser {
     pub list_state: ListState,
     pub pane: BrowserPane,
     pub arg_input: String,
-    pub result: Option<String>,
+    /// The last result, annotated from the method's result schema, or the filter's output.
+    pub result: Option<JsonTree>,
+    /// The unfiltered result, so the filter can change without calling the node again.
+    pub raw_result: Option<serde_json::Value>,
+    /// jq expression applied to `raw_result` (`|`).
+    pub filter: String,
+    pub filter_error: Option<String>,
+    /// How many values the filter produced; `None` while no filter applies.
+    pub filter_outputs: Option<usize>,
+    /// Shows the method help instead of the result (`?`).
+    pub show_help: bool,
     pub error: Option<String>,
     pub calling: bool,
-    pub result_scroll: u16,
+    pub detail_scroll: u16,
     pub editing_args: bool,
     pub method_search: String,
     pub filtered_indices: Vec<usize>,
     pub filtered_selected: usize,
     pub detail_search: String,
-    pub detail_matches: Vec<u16>,
-    pub detail_match_index: usize,
+    /// Methods with a starred call, listed first when searching.
+    pub starred: BTreeSet<String>,
+    /// Which node the method list was checked against, shown in its title.
+    pub node_note: Option<String>,
+    /// A call waiting for the user to confirm it.
+    pub confirm: Option<CallConfirm>,
+    /// Where a result shown from the call history came from.
+    pub result_note: Option<String>,
+    /// Per-parameter argument form, opened with Enter on a method that takes params.
+    pub form: Option<ArgForm>,
+    /// Params built by the form; used instead of `arg_input` for the next call.
+    pub call_params: Option<serde_json::Value>,
     pub request_seq: u64,
     pub in_flight_request: Option<u64>,
 }
@@ -191,21 +309,173 @@ impl MethodBrowser {
             pane: BrowserPane::default(),
             arg_input: String::new(),
             result: None,
+            raw_result: None,
+            filter: String::new(),
+            filter_error: None,
+            filter_outputs: None,
+            show_help: false,
             error: None,
             calling: false,
-            result_scroll: 0,
+            detail_scroll: 0,
             editing_args: false,
             method_search: String::new(),
             filtered_indices,
             filtered_selected: 0,
             detail_search: String::new(),
-            detail_matches: Vec::new(),
-            detail_match_index: 0,
+            starred: BTreeSet::new(),
+            node_note: None,
+            confirm: None,
+            result_note: None,
+            form: None,
+            call_params: None,
             request_seq: 0,
             in_flight_request: None,
         }
     }
 
+    /// Shows a call's outcome. A successful call closes the argument form so the result
+    /// is visible; a failed one keeps it open with the error for another try.
+    pub fn finish_call(&mut self, result: Result<serde_json::Value, String>) {
+        self.calling = false;
+        self.in_flight_request = None;
+        match result {
+            Ok(value) => {
+                self.error = None;
+                self.raw_result = Some(value);
+                self.result = None;
+                self.result_note = None;
+                self.apply_filter();
+                self.show_help = false;
+                self.form = None;
+            }
+            Err(e) => {
+                if let Some(form) = &mut self.form {
+                    form.error = Some(e.clone());
+                }
+                // The filter stays for the next call of the same method.
+                self.result = None;
+                self.raw_result = None;
+                self.result_note = None;
+                self.detail_search.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+
+    pub fn clear_result(&mut self) {
+        self.result = None;
+        self.raw_result = None;
+        self.result_note = None;
+        self.detail_search.clear();
+        self.filter.clear();
+        self.filter_error = None;
+        self.filter_outputs = None;
+    }
+
+    /// Re-runs the filter on the unfiltered result. A filter that doesn't parse or fails
+    /// keeps the last output on screen, so typing one out doesn't flicker.
+    pub fn apply_filter(&mut self) {
+        let Some(raw) = &self.raw_result else {
+            return;
+        };
+        if self.filter.trim().is_empty() {
+            let schema = self.methods[self.selected].result.as_ref();
+            self.result = Some(JsonTree::new(raw.clone(), schema));
+            self.filter_error = None;
+            self.filter_outputs = None;
+        } else {
+            match jq::apply(&self.filter, raw) {
+                Ok(mut outputs) => {
+                    self.filter_outputs = Some(outputs.len());
+                    let value = if outputs.len() == 1 {
+                        outputs.remove(0)
+                    } else {
+                        serde_json::Value::Array(outputs)
+                    };
+                    self.result = Some(JsonTree::new(value, None));
+                    self.filter_error = None;
+                }
+                Err(e) => self.filter_error = Some(e),
+            }
+        }
+        if !self.detail_search.is_empty() {
+            self.update_detail_matches();
+        }
+    }
+
+    /// Whether the Detail pane shows the result rather than the method help.
+    pub fn showing_result(&self) -> bool {
+        self.result.is_some() && !self.show_help
+    }
+
+    /// Number of result nodes matching the detail search.
+    pub fn match_count(&self) -> usize {
+        self.result.as_ref().map_or(0, |tree| tree.matches().len())
+    }
+
+    pub fn clear_search(&mut self) {
+        self.detail_search.clear();
+        if let Some(tree) = &mut self.result {
+            tree.clear_search();
+        }
+    }
+
+    /// Starts the call, or asks for confirmation first when the method can change
+    /// anything. With `--read-only` the client refuses those calls, so there's nothing
+    /// to confirm.
+    pub fn request_call(&mut self, read_only: bool) {
+        let class = classify(&self.methods[self.selected].name);
+        if read_only || class == MethodClass::ReadOnly {
+            self.calling = true;
+        } else {
+            self.confirm = Some(CallConfirm {
+                class,
+                typed: String::new(),
+            });
+        }
+    }
+
+    /// Builds params from the argument form and starts the call, or shows why it can't.
+    pub fn submit_form(&mut self, read_only: bool) {
+        let Some(form) = &mut self.form else {
+            return;
+        };
+        if self.calling {
+            return;
+        }
+        match form.build() {
+            Ok(params) => {
+                form.error = None;
+                self.call_params = Some(params);
+                self.request_call(read_only);
+            }
+            Err(e) => form.error = Some(e),
+        }
+    }
+
+    /// Replaces the method list, keeping the selected method selected.
+    pub fn set_methods(&mut self, methods: Vec<RpcMethod>) {
+        let current = self.methods.get(self.selected).map(|m| m.name.clone());
+        self.methods = methods;
+        self.selected = current
+            .and_then(|name| self.methods.iter().position(|m| m.name == name))
+            .unwrap_or(0);
+        self.list_state.select(Some(self.selected));
+        self.update_method_filter();
+    }
+
+    /// Selects the method at `index`, dropping what belonged to the previous one.
+    pub fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.list_state.select(Some(index));
+        self.clear_result();
+        self.error = None;
+        self.arg_input.clear();
+        self.form = None;
+        self.confirm = None;
+        self.detail_scroll = 0;
+    }
+
     pub fn update_method_filter(&mut self) {
         let query = self.method_search.to_lowercase();
         if query.is_empty() {
@@ -219,6 +489,10 @@ impl MethodBrowser {
                 .map(|(i, _)| i)
                 .collect();
         }
+        let methods = &self.methods;
+        let starred = &self.starred;
+        self.filtered_indices
+            .sort_by_key(|&i| !starred.contains(&methods[i].name));
         let len = self.filtered_indices.len();
         if len == 0 {
             self.filtered_selected = 0;
@@ -228,22 +502,193 @@ impl MethodBrowser {
     }
 
     pub fn update_detail_matches(&mut self) {
-        let query = self.detail_search.to_lowercase();
-        self.detail_matches.clear();
-        self.detail_match_index = 0;
+        if let Some(tree) = &mut self.result {
+            tree.search(&self.detail_search);
+            if !tree.matches().is_empty() {
+                self.show_help = false;
+            }
+        }
+    }
+}
 
-        if let Some(result) = &self.result {
-            for (i, line) in result.lines().enumerate() {
-                if line.to_lowercase().contains(&query) {
-                    self.detail_matches.push(i as u16);
-                }
+pub const ADDRMAN_NETWORKS: [&str; 6] = ["all", "ipv4", "ipv6", "onion", "i2p", "cjdns"];
+pub const ADDRMAN_SERVICES: [&str; 6] = [
+    "any",
+    "NETWORK",
+    "NETWORK_LIMITED",
+    "WITNESS",
+    "COMPACT_FILTERS",
+    "P2P_V2",
+];
+
+pub struct CallConfirm {
+    pub class: MethodClass,
+    /// The method name as typed so far, for classes that need it typed.
+    pub typed: String,
+}
+
+impl CallConfirm {
+    /// `Some(true)` once confirmed (`y`, or `name` typed and Enter), `Some(false)` when
+    /// cancelled, `None` while still asking.
+    pub fn handle_key(&mut self, key: KeyEvent, name: &str) -> Option<bool> {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        if key.code == KeyCode::Esc {
+            return Some(false);
+        }
+        if !self.class.needs_typed_confirmation() {
+            return match key.code {
+                KeyCode::Char('y') | KeyCode::Enter => Some(true),
+                KeyCode::Char('n') => Some(false),
+                _ => None,
+            };
+        }
+        match key.code {
+            KeyCode::Enter if self.typed == name => return Some(true),
+            KeyCode::Backspace => {
+                self.typed.pop();
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.typed.clear();
             }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.typed.push(c);
+            }
+            _ => {}
         }
+        None
+    }
+}
+
+pub struct MacroEntry {
+    pub path: PathBuf,
+    pub name: String,
+    /// The first comment line, or why the file doesn't parse.
+    pub description: Result<String, String>,
+}
+
+/// A macro run shown in the palette.
+pub struct MacroRun {
+    pub id: u64,
+    pub path: PathBuf,
+    pub name: String,
+    /// Each step as written, so pending ones can be listed.
+    pub steps: Vec<String>,
+    pub reports: Vec<StepReport>,
+    pub done: bool,
+    /// Set while the macro waits to be confirmed.
+    pub confirm: Option<CallConfirm>,
+    pub pending: Option<Macro>,
+    /// Lines scrolled up from the end of the output.
+    pub scroll_back: u16,
+}
+
+/// The macro palette (`Ctrl+p`, or `m` on the tab bar) and the run it started.
+#[derive(Default)]
+pub struct MacroView {
+    pub open: bool,
+    pub query: String,
+    pub selected: usize,
+    pub entries: Vec<MacroEntry>,
+    pub run: Option<MacroRun>,
+    pub status: Option<String>,
+    /// A run to start; taken by main.rs.
+    pub start: Option<(u64, Vec<Step>)>,
+    /// Stop the running macro; taken by main.rs.
+    pub cancel: bool,
+    pub run_seq: u64,
+}
+
+impl MacroView {
+    /// Entries whose name or description contains the query.
+    pub fn filtered(&self) -> Vec<&MacroEntry> {
+        let query = self.query.to_lowercase();
+        self.entries
+            .iter()
+            .filter(|e| {
+                e.name.to_lowercase().contains(&query)
+                    || e.description
+                        .as_ref()
+                        .is_ok_and(|d| d.to_lowercase().contains(&query))
+            })
+            .collect()
+    }
+
+    pub fn running(&self) -> bool {
+        self.run
+            .as_ref()
+            .is_some_and(|run| run.pending.is_none() && !run.done)
+    }
+}
+
+/// Checking the RPC and Wallet method lists against the connected node's `help`.
+#[derive(Default)]
+pub struct MethodCheck {
+    /// Node version the method lists reflect, or are being fetched for.
+    pub version: Option<u64>,
+    /// Version and subversion whose `help` should be fetched; taken by main.rs.
+    pub fetch: Option<(u64, String)>,
+}
+
+/// The call history popup of the RPC and Wallet tabs (`H`).
+#[derive(Default)]
+pub struct HistoryView {
+    pub open: bool,
+    pub selected: usize,
+    pub status: Option<String>,
+}
 
-        if let Some(&first) = self.detail_matches.first() {
-            self.result_scroll = first;
+/// Saving a value from a result tree (`s`/`S`); one picker serves the RPC, Wallet,
+/// Transactions and ZMQ block trees.
+pub struct ResultExport {
+    pub picker: FilePicker,
+    pub pending: Option<Export>,
+}
+
+#[derive(Default)]
+pub struct AddrmanView {
+    pub open: bool,
+    pub fetching: bool,
+    pub loading: bool,
+    pub info: Option<BTreeMap<String, AddrmanCounts>>,
+    pub info_error: Option<String>,
+    pub addresses: Vec<NodeAddress>,
+    pub error: Option<String>,
+    pub network_filter: usize,
+    pub service_filter: usize,
+    pub visible: Vec<usize>,
+    pub selected: usize,
+    pub request_seq: u64,
+    pub in_flight_request: Option<u64>,
+}
+
+impl AddrmanView {
+    pub fn network(&self) -> Option<&'static str> {
+        match ADDRMAN_NETWORKS[self.network_filter] {
+            "all" => None,
+            net => Some(net),
         }
     }
+
+    pub fn refresh_visible(&mut self) {
+        let required = crate::format::service_flag_bit(ADDRMAN_SERVICES[self.service_filter]);
+        self.visible = self
+            .addresses
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| required.is_none_or(|bit| a.services & bit != 0))
+            .map(|(i, _)| i)
+            .collect();
+        self.visible
+            .sort_by_key(|&i| std::cmp::Reverse(self.addresses[i].time));
+        self.selected = self.selected.min(self.visible.len().saturating_sub(1));
+    }
+
+    fn request_refresh(&mut self) {
+        self.fetching = true;
+        self.loading = true;
+        self.error = None;
+    }
 }
 
 #[derive(Default)]
@@ -253,6 +698,8 @@ pub struct TransactionsTab {
     pub error: Option<String>,
     pub searching: bool,
     pub result_scroll: u16,
+    /// The result's `decoderawtransaction` output.
+    pub decoded: Option<JsonTree>,
     pub request_seq: u64,
     pub in_flight_request: Option<u64>,
 }
@@ -264,10 +711,9 @@ pub struct ZmqTab {
     pub enabled: bool,
     pub error: Option<String>,
     pub block_lookup: Option<String>,
-    pub block_popup: Option<String>,
+    pub block_popup: Option<JsonTree>,
     pub block_popup_error: Option<String>,
     pub block_popup_loading: bool,
-    pub block_popup_scroll: u16,
     pub tx_rate: VecDeque<u64>,
     pub tx_rate_epoch: Option<Instant>,
 }
@@ -280,62 +726,354 @@ pub struct WalletTab {
     pub fetching_wallets: bool,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-pub enum PsbtFileMode {
-    Load,
-    Save,
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum PsbtView {
+    #[default]
+    Summary,
+    Fields,
+    Raw,
 }
 
-pub struct PsbtFileEntry {
+/// One PSBT held by the PSBT tab, with its parse result cached.
+pub struct LoadedPsbt {
+    pub name: String,
+    pub psbt: String,
+    /// Encoding of the file it was loaded from; the default for saving it back.
+    pub encoding: Encoding,
+    pub parsed: Result<Psbt, String>,
+    pub tree: Vec<PsbtNode>,
+    /// Fetched from the node when the summary view first shows this PSBT.
+    pub summary: Option<Result<PsbtSummary, String>>,
+}
+
+impl LoadedPsbt {
+    pub fn new(name: String, psbt: String) -> Self {
+        let parsed = crate::psbt::parse(&psbt);
+        let tree = match &parsed {
+            Ok(parsed) => parsed.tree(),
+            Err(_) => Vec::new(),
+        };
+        LoadedPsbt {
+            name,
+            psbt,
+            encoding: Encoding::default(),
+            parsed,
+            tree,
+            summary: None,
+        }
+    }
+}
+
+/// The paste/edit area opened with `i` (new PSBT) or `E` (edit the active one).
+#[derive(Default)]
+pub struct PsbtEditor {
+    pub text: TextArea,
+    /// Replace the active PSBT on load instead of adding a new one.
+    pub replace: bool,
+    /// Result of parsing the current text, refreshed on every edit.
+    pub validation: Option<Result<String, String>>,
+}
+
+impl PsbtEditor {
+    /// Accepts base64 or hex; whitespace from line-wrapped chat messages is ignored.
+    pub fn parse(&self) -> Result<(String, Encoding), String> {
+        let compact: String = self.text.text().split_whitespace().collect();
+        if compact.is_empty() {
+            return Err("paste or type a base64 or hex PSBT".to_string());
+        }
+        let (psbt, encoding) = crate::psbt::read_file(compact.as_bytes())?;
+        crate::psbt::parse(&psbt)?;
+        Ok((psbt, encoding))
+    }
+
+    pub fn revalidate(&mut self) {
+        let compact: String = self.text.text().split_whitespace().collect();
+        self.validation = (!compact.is_empty()).then(|| {
+            let (psbt, encoding) = crate::psbt::read_file(compact.as_bytes())?;
+            let parsed = crate::psbt::parse(&psbt)?;
+            let issues = match parsed.issues.len() {
+                0 => String::new(),
+                n => format!(", {} issue(s)", n),
+            };
+            Ok(format!(
+                "valid {} PSBT v{}: {} inputs, {} outputs{}",
+                encoding.label(),
+                parsed.version,
+                parsed.inputs.len(),
+                parsed.outputs.len(),
+                issues
+            ))
+        });
+    }
+}
+
+/// A file name stem from a result's `key` field (a txid or block hash), else `fallback`.
+fn value_stem(value: &serde_json::Value, key: &str, fallback: &str) -> String {
+    match value.get(key) {
+        Some(serde_json::Value::String(s)) => s.clone(),
+        Some(serde_json::Value::Number(n)) => format!("{}-{}", fallback, n),
+        _ => fallback.to_string(),
+    }
+}
+
+/// Bitcoin Core's default `maxfeerate` (0.10 BTC/kvB) in sat/vB.
+pub const DEFAULT_MAX_FEE_RATE: f64 = 10_000.0;
+
+/// Converts sat/vB to the BTC/kvB unit Core's `maxfeerate` arguments take.
+pub fn sat_vb_to_btc_kvb(rate: f64) -> f64 {
+    rate / 100_000.0
+}
+
+/// Command-line settings used by PSBT actions.
+#[derive(Clone)]
+pub struct PsbtConfig {
+    /// Upper fee rate bound (sat/vB) passed to testmempoolaccept and sendrawtransaction.
+    pub max_fee_rate: f64,
+    /// HWI-compatible command for signing without the node, like bitcoind's `-signer`.
+    pub signer: Option<String>,
+    /// Chain name passed to the signer: `main`, `test`, `testnet4`, `signet` or `regtest`.
+    pub chain: String,
+}
+
+impl Default for PsbtConfig {
+    fn default() -> Self {
+        PsbtConfig {
+            max_fee_rate: DEFAULT_MAX_FEE_RATE,
+            signer: None,
+            chain: "main".to_string(),
+        }
+    }
+}
+
+/// A device reported by `enumeratesigners` or the signer command's `enumerate`.
+pub struct ExternalSigner {
+    pub fingerprint: String,
     pub name: String,
-    pub path: PathBuf,
-    pub is_dir: bool,
 }
 
 pub struct PsbtTab {
-    pub psbt: String,
+    pub psbts: Vec<LoadedPsbt>,
+    pub active: usize,
+    /// Indices marked with `m` for combine/join/diff.
+    pub marked: BTreeSet<usize>,
+    pub view: PsbtView,
+    pub tree_expanded: HashSet<String>,
+    pub tree_selected: usize,
     pub output: Option<String>,
+    /// The last action's result, below any `output` text.
+    pub output_tree: Option<JsonTree>,
+    /// Keys go to `output_tree` (`o`).
+    pub output_focus: bool,
     pub error: Option<String>,
     pub scroll: u16,
     pub rpc_in_flight: Option<PsbtRpcAction>,
     pub running_action: Option<PsbtRpcAction>,
-    pub picker_open: bool,
-    pub picker_mode: PsbtFileMode,
-    pub picker_dir: PathBuf,
-    pub picker_entries: Vec<PsbtFileEntry>,
-    pub picker_selected: usize,
-    pub save_name: String,
+    pub picker: FilePicker,
+    pub save_encoding: Encoding,
+    pub qr: QrView,
+    pub create: CreateForm,
+    pub process: ProcessForm,
+    pub editor: PsbtEditor,
+    /// Params for actions that don't operate on the active PSBT (create, combine, join,
+    /// broadcast), or that take options from a form (process).
+    pub action_params: Option<serde_json::Value>,
+    pub broadcast: Option<BroadcastCheck>,
+    pub config: PsbtConfig,
+    pub signers: Vec<ExternalSigner>,
+    pub signers_open: bool,
+    pub signer_selected: usize,
+    /// The PSBT a summary request is queued or running for, so it lands on the right entry.
+    pub summary_for: Option<String>,
+    /// Summaries (decodepsbt + analyzepsbt + wallet lookups) run beside the user's
+    /// actions rather than in `in_flight_request`, so they never hold those up.
+    pub summary_request: Option<u64>,
+    /// Index and text of the PSBT the running action was sent for, so an updated PSBT
+    /// replaces that entry even if the user switched to or edited another meanwhile.
+    pub sent_for: Option<(usize, String)>,
     pub request_seq: u64,
     pub in_flight_request: Option<u64>,
 }
 
 impl Default for PsbtTab {
     fn default() -> Self {
-        let picker_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
         PsbtTab {
-            psbt: String::new(),
+            psbts: Vec::new(),
+            active: 0,
+            marked: BTreeSet::new(),
+            view: PsbtView::default(),
+            tree_expanded: HashSet::new(),
+            tree_selected: 0,
             output: None,
+            output_tree: None,
+            output_focus: false,
             error: None,
             scroll: 0,
             rpc_in_flight: None,
             running_action: None,
-            picker_open: false,
-            picker_mode: PsbtFileMode::Load,
-            picker_dir,
-            picker_entries: Vec::new(),
-            picker_selected: 0,
-            save_name: "psbt.txt".to_string(),
+            picker: FilePicker {
+                multi_select: true,
+                highlight_ext: Some("psbt"),
+                ..FilePicker::new("psbt.txt")
+            },
+            save_encoding: Encoding::default(),
+            qr: QrView::default(),
+            create: CreateForm::default(),
+            process: ProcessForm::default(),
+            editor: PsbtEditor::default(),
+            action_params: None,
+            broadcast: None,
+            config: PsbtConfig::default(),
+            signers: Vec::new(),
+            signers_open: false,
+            signer_selected: 0,
             request_seq: 0,
+            summary_for: None,
+            summary_request: None,
+            sent_for: None,
             in_flight_request: None,
         }
     }
 }
 
+impl PsbtTab {
+    /// Replaces the output panel with a message, dropping the last result tree.
+    pub fn set_output(&mut self, text: Option<String>) {
+        self.output = text;
+        self.output_tree = None;
+        self.output_focus = false;
+    }
+
+    pub fn current(&self) -> Option<&LoadedPsbt> {
+        self.psbts.get(self.active)
+    }
+
+    pub fn current_text(&self) -> &str {
+        self.current().map(|p| p.psbt.as_str()).unwrap_or("")
+    }
+
+    pub fn tree(&self) -> &[PsbtNode] {
+        self.current().map(|p| p.tree.as_slice()).unwrap_or(&[])
+    }
+
+    /// Requests a summary of the active PSBT if the summary view is showing and it
+    /// hasn't been fetched yet. Waits while another summary is running.
+    pub fn queue_summary(&mut self) {
+        if self.view != PsbtView::Summary || self.summary_for.is_some() {
+            return;
+        }
+        if let Some(current) = self.current()
+            && current.summary.is_none()
+            && current.parsed.is_ok()
+        {
+            self.summary_for = Some(current.psbt.clone());
+        }
+    }
+
+    /// Stores a summary on every loaded copy of the PSBT it was computed for.
+    fn set_summary(&mut self, summary: Result<PsbtSummary, String>) {
+        let Some(psbt) = self.summary_for.take() else {
+            return;
+        };
+        for loaded in self.psbts.iter_mut().filter(|p| p.psbt == psbt) {
+            loaded.summary = Some(summary.clone());
+        }
+    }
+
+    pub fn add(&mut self, name: String, psbt: String) {
+        self.push(LoadedPsbt::new(name, psbt));
+    }
+
+    pub fn push(&mut self, loaded: LoadedPsbt) {
+        self.psbts.push(loaded);
+        self.select(self.psbts.len() - 1);
+    }
+
+    /// Replaces the active PSBT, re-parses it and returns the previous version. Expanded
+    /// tree paths are kept so an updated PSBT (e.g. after walletprocesspsbt) stays open
+    /// where the user was.
+    pub fn replace_current(&mut self, psbt: String) -> Option<LoadedPsbt> {
+        if self.active >= self.psbts.len() {
+            self.add("psbt".to_string(), psbt);
+            return None;
+        }
+        Some(self.replace(self.active, psbt))
+    }
+
+    fn replace(&mut self, index: usize, psbt: String) -> LoadedPsbt {
+        let current = &mut self.psbts[index];
+        let replacement = LoadedPsbt {
+            encoding: current.encoding,
+            ..LoadedPsbt::new(current.name.clone(), psbt)
+        };
+        let previous = std::mem::replace(current, replacement);
+        if index == self.active {
+            let rows = crate::psbt::flatten(self.tree(), &self.tree_expanded).len();
+            self.tree_selected = self.tree_selected.min(rows.saturating_sub(1));
+        }
+        previous
+    }
+
+    /// Stores an action's updated PSBT on the entry it was sent for (`sent_for`) and
+    /// returns that entry's index with its previous version. If the entry was removed or
+    /// edited while the action ran, the result is added as a new PSBT instead.
+    pub fn replace_sent(&mut self, psbt: String) -> (usize, Option<LoadedPsbt>) {
+        let target = self.sent_for.take().and_then(|(index, sent)| {
+            let same = |p: &LoadedPsbt| p.psbt.trim() == sent;
+            if self.psbts.get(index).is_some_and(same) {
+                Some(index)
+            } else {
+                self.psbts.iter().position(same)
+            }
+        });
+        match target {
+            Some(index) => (index, Some(self.replace(index, psbt))),
+            None => {
+                self.add("updated".to_string(), psbt);
+                (self.psbts.len() - 1, None)
+            }
+        }
+    }
+
+    /// Switches the active PSBT and resets the tree to its top level.
+    pub fn select(&mut self, index: usize) {
+        self.active = index.min(self.psbts.len().saturating_sub(1));
+        self.tree_expanded = (0..self.tree().len()).map(|i| i.to_string()).collect();
+        self.tree_selected = 0;
+    }
+
+    pub fn remove_current(&mut self) {
+        if self.active >= self.psbts.len() {
+            return;
+        }
+        self.psbts.remove(self.active);
+        let removed = self.active;
+        self.marked = self
+            .marked
+            .iter()
+            .filter(|&&i| i != removed)
+            .map(|&i| if i > removed { i - 1 } else { i })
+            .collect();
+        self.select(self.active.min(self.psbts.len().saturating_sub(1)));
+    }
+
+    /// The PSBTs combine/join work on: the marked ones, or all of them if none are marked.
+    pub fn batch(&self) -> Vec<String> {
+        self.psbts
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.marked.is_empty() || self.marked.contains(i))
+            .map(|(_, p)| p.psbt.trim().to_string())
+            .collect()
+    }
+}
+
 pub struct App {
     pub tab: Tab,
     pub focus: Focus,
     pub input_mode: InputMode,
     pub should_quit: bool,
+    /// Text waiting to be copied to the system clipboard (OSC 52) by the event loop.
+    pub clipboard: Option<String>,
 
     pub blockchain: Option<BlockchainInfo>,
     pub network: Option<NetworkInfo>,
@@ -346,8 +1084,11 @@ pub struct App {
     pub peers: Option<Vec<PeerInfo>>,
     pub peers_show_user_agent: bool,
     pub peers_selected: usize,
-    pub peers_popup: Option<String>,
+    pub peers_popup: Option<i64>,
     pub peers_popup_scroll: u16,
+    pub peers_popup_raw: bool,
+    /// The popup peer's getpeerinfo entry as a tree, while the raw view is shown.
+    pub peers_popup_tree: Option<JsonTree>,
     pub peers_query_help_open: bool,
     pub peers_query_help_scroll: u16,
     pub peers_query: PeerQuery,
@@ -357,12 +1098,21 @@ pub struct App {
     pub peers_query_completions: Vec<String>,
     pub peers_query_completion_index: usize,
     pub peers_visible_indices: Vec<usize>,
+    pub peer_history: PeerHistoryStore,
+    pub peers_events: VecDeque<PeerEvent>,
+    pub peers_events_focused: bool,
+    pub peers_events_scroll: usize,
+    pub addrman: AddrmanView,
+    pub peers_export_picker: FilePicker,
+    pub peers_export_format: ExportFormat,
+    pub peers_export_status: Option<Result<String, String>>,
     pub recent_blocks: Vec<BlockStats>,
     pub last_tip: Option<String>,
 
     pub rpc_error: Option<String>,
     pub last_update: Option<Instant>,
     pub refreshing: bool,
+    pub profile_dir: Option<PathBuf>,
 
     pub transactions: TransactionsTab,
     pub transactions_return_target: Option<(Tab, Focus)>,
@@ -370,6 +1120,15 @@ pub struct App {
     pub zmq: ZmqTab,
     pub wallet: WalletTab,
     pub rpc: MethodBrowser,
+    /// Result schemas for the PSBT tab's wallet calls.
+    pub psbt_methods: Vec<RpcMethod>,
+    pub call_history: CallHistory,
+    pub history: HistoryView,
+    pub method_check: MethodCheck,
+    /// Started with `--read-only`; mirrors the RPC client's setting.
+    pub read_only: bool,
+    pub macros: MacroView,
+    pub result_export: ResultExport,
 }
 
 impl Default for App {
@@ -379,6 +1138,7 @@ impl Default for App {
             focus: Focus::default(),
             input_mode: InputMode::default(),
             should_quit: false,
+            clipboard: None,
             blockchain: None,
             network: None,
             mempool: None,
@@ -390,6 +1150,8 @@ impl Default for App {
             peers_selected: 0,
             peers_popup: None,
             peers_popup_scroll: 0,
+            peers_popup_raw: false,
+            peers_popup_tree: None,
             peers_query_help_open: false,
             peers_query_help_scroll: 0,
             peers_query: PeerQuery::default(),
@@ -399,11 +1161,20 @@ impl Default for App {
             peers_query_completions: Vec::new(),
             peers_query_completion_index: 0,
             peers_visible_indices: Vec::new(),
+            peer_history: PeerHistoryStore::default(),
+            peers_events: VecDeque::new(),
+            peers_events_focused: false,
+            peers_events_scroll: 0,
+            addrman: AddrmanView::default(),
+            peers_export_picker: FilePicker::new("peers.csv"),
+            peers_export_format: ExportFormat::default(),
+            peers_export_status: None,
             recent_blocks: Vec::new(),
             last_tip: None,
             rpc_error: None,
             last_update: None,
             refreshing: false,
+            profile_dir: None,
             transactions: TransactionsTab::default(),
             transactions_return_target: None,
             psbt: PsbtTab::default(),
@@ -416,6 +1187,16 @@ impl Default for App {
                 fetching_wallets: false,
             },
             rpc: MethodBrowser::new(load_non_wallet_methods()),
+            psbt_methods: load_wallet_psbt_methods(),
+            call_history: CallHistory::default(),
+            history: HistoryView::default(),
+            method_check: MethodCheck::default(),
+            read_only: false,
+            macros: MacroView::default(),
+            result_export: ResultExport {
+                picker: FilePicker::new("result.json"),
+                pending: None,
+            },
         }
     }
 }
@@ -429,9 +1210,33 @@ impl App {
         }
     }
 
+    /// The result schema of `method` from the bundled spec, for result trees outside the
+    /// method browsers.
+    fn result_schema(&self, method: &str) -> Option<&Schema> {
+        self.rpc
+            .methods
+            .iter()
+            .chain(&self.wallet.browser.methods)
+            .chain(&self.psbt_methods)
+            .find(|m| m.name == method)?
+            .result
+            .as_ref()
+    }
+
+    fn active_file_picker(&mut self) -> &mut FilePicker {
+        if self.result_export.picker.open {
+            return &mut self.result_export.picker;
+        }
+        match self.tab {
+            Tab::Peers => &mut self.peers_export_picker,
+            _ => &mut self.psbt.picker,
+        }
+    }
+
     pub fn update(&mut self, event: Event) {
         match event {
             Event::Key(key) => self.handle_key(key),
+            Event::Paste(text) => self.handle_paste(text),
             Event::Tick => self.advance_tx_rate(),
             Event::PollComplete(result) => self.handle_poll(*result),
             Event::RecentBlocksComplete(blocks) => {
@@ -448,12 +1253,18 @@ impl App {
                 self.transactions.in_flight_request = None;
                 match *result {
                     Ok(sr) => {
+                        let (SearchResult::Mempool { decoded, .. }
+                        | SearchResult::Confirmed { decoded, .. }) = &sr;
+                        let schema = self.result_schema("decoderawtransaction");
+                        self.transactions.decoded =
+                            decoded.clone().map(|value| JsonTree::new(value, schema));
                         self.transactions.error = None;
                         self.transactions.result = Some(sr);
                         self.transactions.result_scroll = 0;
                     }
                     Err(e) => {
                         self.transactions.result = None;
+                        self.transactions.decoded = None;
                         self.transactions.error = Some(e);
                     }
                 }
@@ -482,39 +1293,96 @@ impl App {
                 }
                 self.psbt.rpc_in_flight = None;
                 self.psbt.in_flight_request = None;
-                self.psbt.running_action = None;
+                let running = self.psbt.running_action.take();
                 match *result {
                     Ok(res) => {
+                        let schema = res.action.rpc_method().and_then(|m| self.result_schema(m));
+                        let tree = JsonTree::new(res.output, schema);
+                        self.psbt.set_output(None);
+                        self.psbt.output_tree = Some(tree);
                         self.psbt.error = None;
-                        self.psbt.output = Some(res.output_json);
                         self.psbt.scroll = 0;
-                        if let Some(psbt) = res.updated_psbt {
-                            self.psbt.psbt = psbt;
+                        let new_name = match res.action {
+                            PsbtRpcAction::Create => Some("created"),
+                            PsbtRpcAction::Combine => Some("combined"),
+                            PsbtRpcAction::Join => Some("joined"),
+                            _ => None,
+                        };
+                        if matches!(res.action, PsbtRpcAction::Create) {
+                            self.psbt.create.open = false;
+                            self.psbt.view = PsbtView::Summary;
+                        }
+                        if let Some(check) = res.broadcast_check {
+                            self.psbt.broadcast = Some(check);
+                        }
+                        if let Some(signers) = res.signers {
+                            self.psbt.signers = signers;
+                            self.psbt.signer_selected = 0;
+                            self.psbt.signers_open = true;
+                        }
+                        if let Some(txid) = res.broadcast_txid {
+                            self.psbt.broadcast = None;
+                            self.open_transaction(txid, Tab::Psbt);
+                        }
+                        match (res.updated_psbt, new_name) {
+                            (Some(psbt), Some(name)) => {
+                                self.psbt.marked.clear();
+                                self.psbt.add(name.to_string(), psbt);
+                            }
+                            (Some(psbt), None) => {
+                                let (index, previous) = self.psbt.replace_sent(psbt);
+                                let signing = matches!(
+                                    res.action,
+                                    PsbtRpcAction::WalletProcess
+                                        | PsbtRpcAction::DescriptorProcess
+                                        | PsbtRpcAction::ExternalSign
+                                        | PsbtRpcAction::WalletSign
+                                );
+                                if signing {
+                                    self.psbt.process.open = false;
+                                }
+                                if signing
+                                    && let Some(Ok(before)) = previous.as_ref().map(|p| &p.parsed)
+                                    && let Some(Ok(after)) =
+                                        self.psbt.psbts.get(index).map(|p| &p.parsed)
+                                {
+                                    let mut report = vec!["Signatures:".to_string()];
+                                    report.extend(crate::psbt::signature_changes(before, after));
+                                    self.psbt.output = Some(report.join("\n"));
+                                    self.psbt.signers_open = false;
+                                }
+                            }
+                            (None, _) => {}
                         }
-                        let _ = res.action;
                     }
                     Err(e) => {
+                        match running {
+                            Some(PsbtRpcAction::Create) => self.psbt.create.error = Some(e.clone()),
+                            Some(
+                                PsbtRpcAction::WalletProcess | PsbtRpcAction::DescriptorProcess,
+                            ) if self.psbt.process.open => {
+                                self.psbt.process.error = Some(e.clone());
+                            }
+                            _ => {}
+                        }
                         self.psbt.error = Some(e);
                     }
                 }
             }
-            Event::WalletRpcComplete(request_id, result) => {
-                if self.wallet.browser.in_flight_request != Some(request_id) {
+            Event::PsbtSummaryComplete(request_id, summary) => {
+                if self.psbt.summary_request != Some(request_id) {
                     return;
                 }
-                self.wallet.browser.calling = false;
-                self.wallet.browser.in_flight_request = None;
-                match *result {
-                    Ok(json) => {
-                        self.wallet.browser.error = None;
-                        self.wallet.browser.result = Some(json);
-                        self.wallet.browser.result_scroll = 0;
-                    }
-                    Err(e) => {
-                        self.wallet.browser.result = None;
-                        self.wallet.browser.error = Some(e);
-                    }
+                self.psbt.summary_request = None;
+                // Summaries fill the summary view; the output panel keeps the last action.
+                self.psbt.set_summary(*summary);
+            }
+            Event::WalletRpcComplete(request_id, outcome) => {
+                self.record_call(&outcome);
+                if self.wallet.browser.in_flight_request != Some(request_id) {
+                    return;
                 }
+                self.wallet.browser.finish_call(outcome.result);
             }
             Event::ZmqMessage(entry) => {
                 const MAX_ENTRIES: usize = 2000;
@@ -539,36 +1407,84 @@ impl App {
             Event::ZmqBlockComplete(result) => {
                 self.zmq.block_popup_loading = false;
                 match *result {
-                    Ok(json) => {
-                        self.zmq.block_popup = Some(json);
+                    Ok(block) => {
+                        let tree = JsonTree::new(block, self.result_schema("getblock"));
+                        self.zmq.block_popup = Some(tree);
                         self.zmq.block_popup_error = None;
-                        self.zmq.block_popup_scroll = 0;
                     }
                     Err(e) => {
                         self.zmq.block_popup = None;
                         self.zmq.block_popup_error = Some(e);
-                        self.zmq.block_popup_scroll = 0;
                     }
                 }
             }
-            Event::RpcComplete(request_id, result) => {
-                if self.rpc.in_flight_request != Some(request_id) {
+            Event::AddrmanComplete(request_id, result) => {
+                if self.addrman.in_flight_request != Some(request_id) {
                     return;
                 }
-                self.rpc.calling = false;
-                self.rpc.in_flight_request = None;
-                match *result {
-                    Ok(json) => {
-                        self.rpc.error = None;
-                        self.rpc.result = Some(json);
-                        self.rpc.result_scroll = 0;
+                self.addrman.in_flight_request = None;
+                self.addrman.loading = false;
+                match result.info {
+                    Ok(info) => {
+                        self.addrman.info = Some(info);
+                        self.addrman.info_error = None;
+                    }
+                    Err(e) => self.addrman.info_error = Some(e),
+                }
+                match result.addresses {
+                    Ok(addresses) => {
+                        self.addrman.addresses = addresses;
+                        self.addrman.error = None;
                     }
                     Err(e) => {
-                        self.rpc.result = None;
-                        self.rpc.error = Some(e);
+                        self.addrman.addresses.clear();
+                        self.addrman.error = Some(e);
                     }
                 }
+                self.addrman.refresh_visible();
+            }
+            Event::RpcComplete(request_id, outcome) => {
+                self.record_call(&outcome);
+                if self.rpc.in_flight_request != Some(request_id) {
+                    return;
+                }
+                self.rpc.finish_call(outcome.result);
+            }
+            Event::MacroStep(id, report) => {
+                if let Some(run) = &mut self.macros.run
+                    && run.id == id
+                {
+                    run.reports.push(*report);
+                    run.scroll_back = 0;
+                }
             }
+            Event::MacroDone(id) => {
+                if let Some(run) = &mut self.macros.run
+                    && run.id == id
+                {
+                    run.done = true;
+                }
+            }
+            Event::NodeMethodsComplete(result) => match *result {
+                Ok(node) if self.method_check.version == Some(node.version) => {
+                    if let Some(dir) = &self.profile_dir
+                        && let Err(e) = storage::write_json(
+                            &dir.join(Self::method_cache_file(node.version)),
+                            &node,
+                        )
+                    {
+                        tracing::warn!(error = %e, "failed to cache node methods");
+                    }
+                    self.apply_node_methods(&node);
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "failed to read the node's method list");
+                    for browser in [&mut self.rpc, &mut self.wallet.browser] {
+                        browser.node_note = Some("help failed, R retries".to_string());
+                    }
+                }
+            },
         }
     }
 
@@ -629,7 +1545,10 @@ impl App {
             }
         }
         match result.network {
-            Ok(info) => self.network = Some(info),
+            Ok(info) => {
+                self.network = Some(info);
+                self.check_node_methods(false);
+            }
             Err(e) if !had_error => {
                 had_error = true;
                 self.rpc_error = Some(e);
@@ -670,6 +1589,10 @@ impl App {
         }
         match result.peers {
             Ok(info) => {
+                let events =
+                    self.peer_history
+                        .record(&info, Instant::now(), crate::format::unix_now());
+                self.push_peer_events(events);
                 self.peers = Some(info);
                 self.refresh_peers_view();
             }
@@ -681,34 +1604,360 @@ impl App {
         let _ = had_error;
     }
 
-    fn enter_tab(&mut self, tab: Tab) {
-        self.tab = tab;
-        self.focus = Focus::Content;
-        self.transactions_return_target = None;
-        self.input_mode = InputMode::Normal;
-        if tab == Tab::Transactions {
-            self.input_mode = InputMode::TxSearch;
-            self.transactions.search_input.clear();
-        }
+    const MAX_PEER_EVENTS: usize = 500;
+    const PEER_EVENTS_FILE: &str = "peer_events.jsonl";
+
+    pub fn load_peer_events(&mut self) {
+        let Some(dir) = &self.profile_dir else {
+            return;
+        };
+        let events: Vec<PeerEvent> =
+            storage::read_jsonl_tail(&dir.join(Self::PEER_EVENTS_FILE), Self::MAX_PEER_EVENTS);
+        self.peers_events = events.into();
     }
 
-    fn handle_key(&mut self, key: KeyEvent) {
-        use crossterm::event::{KeyCode, KeyModifiers};
+    fn method_cache_file(version: u64) -> String {
+        format!("rpc_methods-{}.json", version)
+    }
 
-        match self.input_mode {
-            InputMode::Normal => match self.focus {
-                Focus::TabBar => match key.code {
-                    KeyCode::Right | KeyCode::Char('l') => self.tab = self.tab.next(),
-                    KeyCode::Left | KeyCode::Char('h') => self.tab = self.tab.prev(),
-                    KeyCode::Enter => self.enter_tab(self.tab),
-                    KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-                    KeyCode::Char('d') => self.tab = Tab::Dashboard,
+    /// Checks the method lists against the node once per node version, from the cache
+    /// when this version was seen before; `refresh` asks the node again regardless.
+    pub fn check_node_methods(&mut self, refresh: bool) {
+        let Some(info) = &self.network else {
+            return;
+        };
+        if !refresh && self.method_check.version == Some(info.version) {
+            return;
+        }
+        let (version, subversion) = (info.version, info.subversion.clone());
+        self.method_check.version = Some(version);
+        if !refresh
+            && let Some(dir) = &self.profile_dir
+            && let Some(cached) =
+                storage::read_json::<NodeMethods>(&dir.join(Self::method_cache_file(version)))
+            && cached.subversion == subversion
+        {
+            self.apply_node_methods(&cached);
+            return;
+        }
+        self.method_check.fetch = Some((version, subversion));
+        for browser in [&mut self.rpc, &mut self.wallet.browser] {
+            browser.node_note = Some("checking node…".to_string());
+        }
+    }
+
+    fn apply_node_methods(&mut self, node: &NodeMethods) {
+        self.rpc.set_methods(node_methods::reconcile(
+            load_non_wallet_methods(),
+            node,
+            |category| category != "wallet",
+        ));
+        self.wallet.browser.set_methods(node_methods::reconcile(
+            load_wallet_methods(),
+            node,
+            |category| category == "wallet",
+        ));
+        let note = node.subversion.trim_matches('/').to_string();
+        for browser in [&mut self.rpc, &mut self.wallet.browser] {
+            browser.node_note = Some(note.clone());
+        }
+        self.sync_starred();
+    }
+
+    const CALL_HISTORY_FILE: &str = "rpc_history.jsonl";
+    const MAX_CALL_HISTORY: usize = 200;
+    const FAVORITES_FILE: &str = "rpc_favorites.jsonl";
+    const MAX_FAVORITES: usize = 500;
+
+    /// Restores the RPC/Wallet call history and starred calls.
+    pub fn load_call_history(&mut self) {
+        let Some(dir) = &self.profile_dir else {
+            return;
+        };
+        self.call_history.calls =
+            storage::read_jsonl_tail(&dir.join(Self::CALL_HISTORY_FILE), Self::MAX_CALL_HISTORY);
+        self.call_history.favorites =
+            storage::read_jsonl_tail(&dir.join(Self::FAVORITES_FILE), Self::MAX_FAVORITES);
+        self.sync_starred();
+    }
+
+    fn record_call(&mut self, outcome: &CallOutcome) {
+        let Some(params) = &outcome.params else {
+            return;
+        };
+        let record = CallRecord::new(
+            outcome.method.clone(),
+            params.clone(),
+            outcome.wallet.clone(),
+            outcome.time,
+            outcome.duration_ms,
+            &outcome.result,
+        );
+        if let Some(dir) = &self.profile_dir
+            && let Some(persisted) = record.persisted()
+            && let Err(e) = storage::append_jsonl(
+                &dir.join(Self::CALL_HISTORY_FILE),
+                std::slice::from_ref(&persisted),
+            )
+        {
+            tracing::warn!(error = %e, "failed to persist call history");
+        }
+        self.call_history.calls.push(record);
+        let excess = self
+            .call_history
+            .calls
+            .len()
+            .saturating_sub(Self::MAX_CALL_HISTORY);
+        self.call_history.calls.drain(..excess);
+    }
+
+    fn save_favorites(&self) {
+        let Some(dir) = &self.profile_dir else {
+            return;
+        };
+        let favorites: Vec<&FavoriteCall> = self
+            .call_history
+            .favorites
+            .iter()
+            .filter(|f| !has_secret_params(&f.method))
+            .collect();
+        if let Err(e) = storage::write_jsonl(&dir.join(Self::FAVORITES_FILE), &favorites) {
+            tracing::warn!(error = %e, "failed to persist favorite calls");
+        }
+    }
+
+    /// Marks the methods with starred calls in both method browsers.
+    fn sync_starred(&mut self) {
+        for browser in [&mut self.rpc, &mut self.wallet.browser] {
+            browser.starred = browser
+                .methods
+                .iter()
+                .filter(|m| self.call_history.is_favorite_method(&m.name))
+                .map(|m| m.name.clone())
+                .collect();
+            browser.update_method_filter();
+        }
+    }
+
+    fn current_browser(&self) -> &MethodBrowser {
+        match self.tab {
+            Tab::Wallet => &self.wallet.browser,
+            _ => &self.rpc,
+        }
+    }
+
+    /// History popup rows for the current tab's methods.
+    pub fn history_rows(&self) -> Vec<HistoryRow> {
+        let methods = &self.current_browser().methods;
+        self.call_history
+            .rows(|name| methods.iter().any(|m| m.name == name))
+    }
+
+    fn handle_history(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let rows = self.history_rows();
+        let max = rows.len().saturating_sub(1);
+        let selected = self.history.selected.min(max);
+        let row = rows.get(selected).copied();
+        let call = row.map(|row| match row {
+            HistoryRow::Favorite(i) => self.call_history.favorites[i].clone(),
+            HistoryRow::Call(i) => self.call_history.calls[i].favorite(),
+        });
+        self.history.status = None;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('H') => self.history.open = false,
+            KeyCode::Down | KeyCode::Char('j') => self.history.selected = (selected + 1).min(max),
+            KeyCode::Up | KeyCode::Char('k') => self.history.selected = selected.saturating_sub(1),
+            KeyCode::Char('g') => self.history.selected = 0,
+            KeyCode::Char('G') => self.history.selected = max,
+            KeyCode::Char('*') | KeyCode::Char('s') => {
+                if let Some(call) = call {
+                    if has_secret_params(&call.method) && !self.call_history.is_favorite(&call) {
+                        self.history.status = Some(format!(
+                            "starred for this session only: {} arguments aren't saved",
+                            call.method
+                        ));
+                    }
+                    self.call_history.toggle_favorite(call);
+                    self.save_favorites();
+                    self.sync_starred();
+                }
+            }
+            KeyCode::Enter | KeyCode::Char('e') => {
+                let Some(call) = call else {
+                    return;
+                };
+                if !self.open_history_call(&call) {
+                    return;
+                }
+                let read_only = self.read_only;
+                let b = self.active_browser();
+                if key.code == KeyCode::Enter {
+                    b.call_params = Some(call.params);
+                    b.request_call(read_only);
+                } else {
+                    b.arg_input = crate::call_history::args_text(&call.params);
+                    b.editing_args = true;
+                    b.show_help = true;
+                    self.input_mode = InputMode::ArgInput;
+                }
+            }
+            KeyCode::Char('v') => {
+                let record = match row {
+                    Some(HistoryRow::Call(i)) => Some(&self.call_history.calls[i]),
+                    Some(HistoryRow::Favorite(i)) => self
+                        .call_history
+                        .last_result(&self.call_history.favorites[i]),
+                    None => None,
+                };
+                let Some(record) = record.cloned() else {
+                    self.history.status = Some("no stored result for this call".to_string());
+                    return;
+                };
+                let Some(result) = record.result.clone() else {
+                    self.history.status = Some(match record.error {
+                        Some(e) => format!("failed: {}", e),
+                        None => "result not kept (over 64 KiB)".to_string(),
+                    });
+                    return;
+                };
+                if !self.open_history_call(&record.favorite()) {
+                    return;
+                }
+                let b = self.active_browser();
+                b.raw_result = Some(result);
+                b.apply_filter();
+                b.show_help = false;
+                b.result_note = Some(format!(
+                    "from history, {}",
+                    crate::format::fmt_relative_time(record.time)
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    /// Selects the call's method (and wallet) and closes the popup; false if the method
+    /// isn't in this tab any more.
+    fn open_history_call(&mut self, call: &FavoriteCall) -> bool {
+        let b = self.active_browser();
+        let Some(index) = b.methods.iter().position(|m| m.name == call.method) else {
+            self.history.status = Some(format!("{} is not available", call.method));
+            return false;
+        };
+        b.select(index);
+        b.pane = BrowserPane::Detail;
+        if self.tab == Tab::Wallet {
+            self.wallet.wallet_name = call.wallet.clone().unwrap_or_default();
+        }
+        self.history.open = false;
+        true
+    }
+
+    const PSBT_DESCRIPTORS_FILE: &str = "psbt_descriptors.jsonl";
+    const MAX_PSBT_DESCRIPTORS: usize = 200;
+
+    /// Restores the descriptors saved from the PSBT process form.
+    pub fn load_psbt_descriptors(&mut self) {
+        let Some(dir) = &self.profile_dir else {
+            return;
+        };
+        let saved: Vec<String> = storage::read_jsonl_tail(
+            &dir.join(Self::PSBT_DESCRIPTORS_FILE),
+            Self::MAX_PSBT_DESCRIPTORS,
+        );
+        self.psbt.process.descriptors = saved
+            .into_iter()
+            .map(|descriptor| crate::psbt_process::SavedDescriptor {
+                descriptor,
+                selected: false,
+            })
+            .collect();
+    }
+
+    fn save_psbt_descriptors(&self) {
+        let Some(dir) = &self.profile_dir else {
+            return;
+        };
+        let saved: Vec<&str> = self
+            .psbt
+            .process
+            .descriptors
+            .iter()
+            .map(|d| d.descriptor.as_str())
+            .collect();
+        if let Err(e) = storage::write_jsonl(&dir.join(Self::PSBT_DESCRIPTORS_FILE), &saved) {
+            tracing::warn!(error = %e, "failed to persist PSBT descriptors");
+        }
+    }
+
+    fn push_peer_events(&mut self, events: Vec<PeerEvent>) {
+        if events.is_empty() {
+            return;
+        }
+        if let Some(dir) = &self.profile_dir
+            && let Err(e) = storage::append_jsonl(&dir.join(Self::PEER_EVENTS_FILE), &events)
+        {
+            tracing::warn!(error = %e, "failed to persist peer events");
+        }
+        // Keep the view anchored on the same event while the user is scrolled back.
+        if self.peers_events_scroll > 0 {
+            self.peers_events_scroll += events.len();
+        }
+        self.peers_events.extend(events);
+        while self.peers_events.len() > Self::MAX_PEER_EVENTS {
+            self.peers_events.pop_front();
+        }
+        self.peers_events_scroll = self
+            .peers_events_scroll
+            .min(self.peers_events.len().saturating_sub(1));
+    }
+
+    fn enter_tab(&mut self, tab: Tab) {
+        self.tab = tab;
+        self.focus = Focus::Content;
+        self.transactions_return_target = None;
+        self.input_mode = InputMode::Normal;
+        if tab == Tab::Transactions {
+            self.input_mode = InputMode::TxSearch;
+            self.transactions.search_input.clear();
+        }
+    }
+
+    fn handle_key(&mut self, key: KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        if self.input_mode == InputMode::Normal {
+            if self.macros.open {
+                self.handle_macros(key);
+                return;
+            }
+            if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                self.open_macros();
+                return;
+            }
+            if self.result_export.picker.open {
+                self.handle_result_export(key);
+                return;
+            }
+        }
+
+        match self.input_mode {
+            InputMode::Normal => match self.focus {
+                Focus::TabBar => match key.code {
+                    KeyCode::Right | KeyCode::Char('l') => self.tab = self.tab.next(),
+                    KeyCode::Left | KeyCode::Char('h') => self.tab = self.tab.prev(),
+                    KeyCode::Enter => self.enter_tab(self.tab),
+                    KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+                    KeyCode::Char('d') => self.tab = Tab::Dashboard,
                     KeyCode::Char('p') => self.enter_tab(Tab::Peers),
                     KeyCode::Char('b') => self.enter_tab(Tab::Psbt),
                     KeyCode::Char('r') => self.enter_tab(Tab::Rpc),
                     KeyCode::Char('w') => self.enter_tab(Tab::Wallet),
                     KeyCode::Char('t') => self.enter_tab(Tab::Transactions),
                     KeyCode::Char('z') => self.enter_tab(Tab::Zmq),
+                    KeyCode::Char('m') => self.open_macros(),
                     _ => {}
                 },
                 Focus::Content => match self.tab {
@@ -728,19 +1977,15 @@ impl App {
                 KeyCode::Esc => {
                     self.input_mode = InputMode::Normal;
                 }
-                KeyCode::Enter => {
-                    if !self.transactions.search_input.is_empty() {
-                        self.transactions.searching = true;
-                        self.input_mode = InputMode::Normal;
-                    }
+                KeyCode::Enter if !self.transactions.search_input.is_empty() => {
+                    self.transactions.searching = true;
+                    self.input_mode = InputMode::Normal;
                 }
                 KeyCode::Backspace => {
                     self.transactions.search_input.pop();
                 }
-                KeyCode::Char(c) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.transactions.search_input.push(c);
-                    }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.transactions.search_input.push(c);
                 }
                 _ => {}
             },
@@ -752,17 +1997,17 @@ impl App {
                     b.arg_input.clear();
                 }
                 KeyCode::Enter => {
-                    self.active_browser().calling = true;
-                    self.active_browser().editing_args = false;
+                    let read_only = self.read_only;
+                    let b = self.active_browser();
+                    b.editing_args = false;
+                    b.request_call(read_only);
                     self.input_mode = InputMode::Normal;
                 }
                 KeyCode::Backspace => {
                     self.active_browser().arg_input.pop();
                 }
-                KeyCode::Char(c) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.active_browser().arg_input.push(c);
-                    }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.active_browser().arg_input.push(c);
                 }
                 _ => {}
             },
@@ -791,19 +2036,86 @@ impl App {
                 }
                 _ => {}
             },
-            InputMode::PsbtSaveName => match key.code {
+            InputMode::SaveName => match key.code {
                 KeyCode::Esc => self.input_mode = InputMode::Normal,
                 KeyCode::Enter => self.input_mode = InputMode::Normal,
                 KeyCode::Backspace => {
-                    self.psbt.save_name.pop();
+                    self.active_file_picker().save_name.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.active_file_picker().save_name.push(c);
+                }
+                _ => {}
+            },
+            InputMode::PsbtForm => match key.code {
+                KeyCode::Esc | KeyCode::Enter => self.input_mode = InputMode::Normal,
+                KeyCode::Tab => {
+                    self.input_mode = InputMode::Normal;
+                    if self.psbt.process.open {
+                        let max = self.psbt.process.rows().len() - 1;
+                        self.psbt.process.selected = (self.psbt.process.selected + 1).min(max);
+                    } else {
+                        let max = self.psbt.create.rows().len() - 1;
+                        self.psbt.create.selected = (self.psbt.create.selected + 1).min(max);
+                    }
+                }
+                KeyCode::Backspace => {
+                    if let Some(text) = self.psbt_form_text() {
+                        text.pop();
+                    }
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some(text) = self.psbt_form_text() {
+                        text.push(c);
+                    }
+                }
+                _ => {}
+            },
+            InputMode::ArgForm => match key.code {
+                KeyCode::Esc | KeyCode::Enter => {
+                    self.input_mode = InputMode::Normal;
+                    if let Some(form) = &mut self.active_browser().form {
+                        form.editing_key = false;
+                    }
+                }
+                KeyCode::Tab => {
+                    self.input_mode = InputMode::Normal;
+                    if let Some(form) = &mut self.active_browser().form {
+                        form.editing_key = false;
+                        let max = form.rows().len().saturating_sub(1);
+                        form.selected = (form.selected + 1).min(max);
+                    }
+                }
+                KeyCode::Backspace => {
+                    if let Some(form) = &mut self.active_browser().form
+                        && let Some(text) = form.text_mut()
+                    {
+                        text.pop();
+                    }
                 }
-                KeyCode::Char(c) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.psbt.save_name.push(c);
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    if let Some(form) = &mut self.active_browser().form
+                        && let Some(text) = form.text_mut()
+                    {
+                        text.push(c);
+                        if let Some(row) = form.selected_row() {
+                            form.include(&row.path);
+                        }
                     }
                 }
                 _ => {}
             },
+            InputMode::PsbtEditor => match key.code {
+                KeyCode::Esc => self.input_mode = InputMode::Normal,
+                KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.load_psbt_from_editor();
+                }
+                _ => {
+                    if self.psbt.editor.text.handle_key(key) {
+                        self.psbt.editor.revalidate();
+                    }
+                }
+            },
             InputMode::MethodSearch => match key.code {
                 KeyCode::Esc => {
                     let b = self.active_browser();
@@ -837,20 +2149,16 @@ impl App {
                     b.method_search.pop();
                     b.update_method_filter();
                 }
-                KeyCode::Char(c) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        let b = self.active_browser();
-                        b.method_search.push(c);
-                        b.update_method_filter();
-                    }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    let b = self.active_browser();
+                    b.method_search.push(c);
+                    b.update_method_filter();
                 }
                 _ => {}
             },
             InputMode::DetailSearch => match key.code {
                 KeyCode::Esc => {
-                    let b = self.active_browser();
-                    b.detail_search.clear();
-                    b.detail_matches.clear();
+                    self.active_browser().clear_search();
                     self.input_mode = InputMode::Normal;
                 }
                 KeyCode::Enter => {
@@ -862,13 +2170,35 @@ impl App {
                 KeyCode::Backspace => {
                     self.active_browser().detail_search.pop();
                 }
-                KeyCode::Char(c) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.active_browser().detail_search.push(c);
-                    }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.active_browser().detail_search.push(c);
                 }
                 _ => {}
             },
+            InputMode::DetailFilter => {
+                let b = self.active_browser();
+                match key.code {
+                    KeyCode::Esc => {
+                        b.filter.clear();
+                        b.apply_filter();
+                        self.input_mode = InputMode::Normal;
+                    }
+                    KeyCode::Enter => self.input_mode = InputMode::Normal,
+                    KeyCode::Backspace => {
+                        b.filter.pop();
+                        b.apply_filter();
+                    }
+                    KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        b.filter.clear();
+                        b.apply_filter();
+                    }
+                    KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                        b.filter.push(c);
+                        b.apply_filter();
+                    }
+                    _ => {}
+                }
+            }
             InputMode::PeersQuery => match key.code {
                 KeyCode::Esc => {
                     self.input_mode = InputMode::Normal;
@@ -878,7 +2208,12 @@ impl App {
                 KeyCode::Enter => {
                     let cmd = self.peers_query_input.trim().to_string();
                     if !cmd.is_empty() {
-                        match peers_query::apply_command(&mut self.peers_query, &cmd) {
+                        self.peers_export_status = None;
+                        match peers_query::apply_command(
+                            &mut self.peers_query,
+                            &cmd,
+                            self.peers.as_deref().unwrap_or_default(),
+                        ) {
                             Ok(()) => {
                                 self.peers_query_error = None;
                                 self.refresh_peers_view();
@@ -899,11 +2234,9 @@ impl App {
                 KeyCode::Tab => {
                     self.apply_peers_query_completion();
                 }
-                KeyCode::Char(c) => {
-                    if !key.modifiers.contains(KeyModifiers::CONTROL) {
-                        self.peers_query_input.push(c);
-                        self.clear_peers_query_completion();
-                    }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.peers_query_input.push(c);
+                    self.clear_peers_query_completion();
                 }
                 _ => {}
             },
@@ -913,6 +2246,27 @@ impl App {
     fn handle_transactions_content(&mut self, key: KeyEvent) {
         use crossterm::event::{KeyCode, KeyModifiers};
 
+        // With a decoded transaction, the tree takes the movement keys.
+        if let Some(tree) = &mut self.transactions.decoded {
+            match tree.handle_key(key) {
+                TreeAction::Ignored => {}
+                TreeAction::None => return,
+                TreeAction::Copy(text) => {
+                    self.clipboard = Some(text);
+                    return;
+                }
+                TreeAction::Save(export) => {
+                    let stem = value_stem(tree.value(), "txid", "transaction");
+                    self.start_result_export(export, &stem);
+                    return;
+                }
+            }
+            if key.code == KeyCode::Char('a') {
+                tree.annotations = !tree.annotations;
+                return;
+            }
+        }
+
         match key.code {
             KeyCode::Esc => {
                 if let Some((tab, focus)) = self.transactions_return_target.take() {
@@ -944,67 +2298,52 @@ impl App {
         }
     }
 
-    fn refresh_psbt_picker(&mut self) {
-        let mut entries = vec![PsbtFileEntry {
-            name: "..".to_string(),
-            path: self
-                .psbt
-                .picker_dir
-                .parent()
-                .map(PathBuf::from)
-                .unwrap_or_else(|| self.psbt.picker_dir.clone()),
-            is_dir: true,
-        }];
-
-        if let Ok(read_dir) = std::fs::read_dir(&self.psbt.picker_dir) {
-            for entry in read_dir.flatten() {
-                let path = entry.path();
-                let is_dir = path.is_dir();
-                let name = entry.file_name().to_string_lossy().to_string();
-                entries.push(PsbtFileEntry { name, path, is_dir });
-            }
-        }
-        entries.sort_by(|a, b| {
-            b.is_dir
-                .cmp(&a.is_dir)
-                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
-        });
-
-        self.psbt.picker_entries = entries;
-        if self.psbt.picker_entries.is_empty() {
-            self.psbt.picker_selected = 0;
-        } else {
-            self.psbt.picker_selected =
-                self.psbt.picker_selected.min(self.psbt.picker_entries.len() - 1);
-        }
-    }
-
-    fn open_psbt_picker(&mut self, mode: PsbtFileMode) {
-        self.psbt.picker_mode = mode;
-        self.psbt.picker_open = true;
-        self.refresh_psbt_picker();
-    }
-
-    fn load_psbt_from_file(&mut self, path: &PathBuf) {
-        match std::fs::read_to_string(path) {
-            Ok(content) => {
-                self.psbt.psbt = content.trim().to_string();
-                self.psbt.output = None;
-                self.psbt.error = None;
-                self.psbt.scroll = 0;
-                self.psbt.picker_open = false;
+    fn load_psbts_from_files(&mut self, paths: &[PathBuf]) {
+        let mut errors = Vec::new();
+        let mut loaded = Vec::new();
+        for path in paths {
+            match std::fs::read(path)
+                .map_err(|e| e.to_string())
+                .and_then(|bytes| crate::psbt::read_file(&bytes))
+            {
+                Ok((psbt, encoding)) => {
+                    let name = path
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.display().to_string());
+                    loaded.push(format!("loaded {} ({})", name, encoding.label()));
+                    self.psbt.push(LoadedPsbt {
+                        encoding,
+                        ..LoadedPsbt::new(name, psbt)
+                    });
+                }
+                Err(e) => errors.push(format!("load {}: {}", path.display(), e)),
             }
-            Err(e) => self.psbt.error = Some(format!("load {}: {}", path.display(), e)),
         }
+        if !loaded.is_empty() {
+            self.psbt.view = PsbtView::Summary;
+        }
+        self.psbt
+            .set_output((!loaded.is_empty()).then(|| loaded.join("\n")));
+        self.psbt.error = (!errors.is_empty()).then(|| errors.join("; "));
+        self.psbt.scroll = 0;
+        self.psbt.picker.open = false;
     }
 
     fn save_psbt_to_file(&mut self, path: &PathBuf) {
-        match std::fs::write(path, format!("{}\n", self.psbt.psbt.trim())) {
+        let encoding = self.psbt.save_encoding;
+        let written = crate::psbt::write_file(self.psbt.current_text(), encoding)
+            .and_then(|bytes| std::fs::write(path, bytes).map_err(|e| e.to_string()));
+        match written {
             Ok(_) => {
                 self.psbt.error = None;
-                self.psbt.output = Some(format!("saved to {}", path.display()));
+                self.psbt.set_output(Some(format!(
+                    "saved to {} ({})",
+                    path.display(),
+                    encoding.label()
+                )));
                 self.psbt.scroll = 0;
-                self.psbt.picker_open = false;
+                self.psbt.picker.open = false;
             }
             Err(e) => self.psbt.error = Some(format!("save {}: {}", path.display(), e)),
         }
@@ -1013,113 +2352,616 @@ impl App {
     fn handle_psbt_content(&mut self, key: KeyEvent) {
         use crossterm::event::{KeyCode, KeyModifiers};
 
-        if self.psbt.picker_open {
+        if self.psbt.picker.open {
+            if self.psbt.picker.mode == FileMode::Save
+                && self.psbt.picker.confirm_overwrite.is_none()
+                && key.code == KeyCode::Char('f')
+            {
+                self.psbt.save_encoding = self.psbt.save_encoding.next();
+                self.psbt
+                    .picker
+                    .set_extension(self.psbt.save_encoding.extension());
+                return;
+            }
+            match self.psbt.picker.handle_key(key) {
+                PickerAction::None => {}
+                PickerAction::Close => self.input_mode = InputMode::Normal,
+                PickerAction::EditName => self.input_mode = InputMode::SaveName,
+                PickerAction::Load(path) => self.load_psbts_from_files(&[path]),
+                PickerAction::LoadMany(paths) => self.load_psbts_from_files(&paths),
+                PickerAction::Save(path) => self.save_psbt_to_file(&path),
+            }
+            return;
+        }
+
+        if self.psbt.create.open {
+            self.handle_psbt_create(key);
+            return;
+        }
+
+        if self.psbt.process.open {
+            self.handle_psbt_process(key);
+            return;
+        }
+
+        if self.psbt.qr.open {
+            self.handle_psbt_qr(key);
+            return;
+        }
+
+        if self.psbt.signers_open {
+            self.handle_psbt_signers(key);
+            return;
+        }
+
+        if let Some(check) = &self.psbt.broadcast {
             match key.code {
-                KeyCode::Esc => {
-                    self.psbt.picker_open = false;
-                    self.input_mode = InputMode::Normal;
+                KeyCode::Esc | KeyCode::Char('n') => self.psbt.broadcast = None,
+                KeyCode::Char('y') if check.allowed && self.psbt.in_flight_request.is_none() => {
+                    self.psbt.action_params = Some(serde_json::json!([
+                        check.hex,
+                        sat_vb_to_btc_kvb(self.psbt.config.max_fee_rate)
+                    ]));
+                    self.psbt.rpc_in_flight = Some(PsbtRpcAction::Broadcast);
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if !self.psbt.picker_entries.is_empty() {
-                        self.psbt.picker_selected =
-                            (self.psbt.picker_selected + 1).min(self.psbt.picker_entries.len() - 1);
+                _ => {}
+            }
+            return;
+        }
+
+        if self.psbt.output_focus
+            && let Some(tree) = &mut self.psbt.output_tree
+        {
+            match tree.handle_key(key) {
+                TreeAction::Copy(text) => self.clipboard = Some(text),
+                TreeAction::Save(export) => self.start_result_export(export, "psbt-output"),
+                TreeAction::None => {}
+                TreeAction::Ignored => match key.code {
+                    KeyCode::Esc | KeyCode::Char('o') => self.psbt.output_focus = false,
+                    KeyCode::Char('a') => tree.annotations = !tree.annotations,
+                    _ => {}
+                },
+            }
+            return;
+        }
+
+        if self.psbt.view == PsbtView::Fields
+            && !self.psbt.tree().is_empty()
+            && self.handle_psbt_tree(key)
+        {
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc => self.focus = Focus::TabBar,
+            KeyCode::Char('v') => {
+                self.psbt.view = match self.psbt.view {
+                    PsbtView::Summary => PsbtView::Fields,
+                    PsbtView::Fields => PsbtView::Raw,
+                    PsbtView::Raw => PsbtView::Summary,
+                };
+            }
+            KeyCode::Char('r') if self.psbt.view == PsbtView::Summary => {
+                if let Some(current) = self.psbt.psbts.get_mut(self.psbt.active) {
+                    current.summary = None;
+                    // Drop a running summary so its result doesn't stand in for the new one.
+                    self.psbt.summary_for = None;
+                    self.psbt.summary_request = None;
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.psbt.scroll = self.psbt.scroll.saturating_add(1)
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.psbt.scroll = self.psbt.scroll.saturating_sub(1)
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.psbt.scroll = self.psbt.scroll.saturating_add(20);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.psbt.scroll = self.psbt.scroll.saturating_sub(20);
+            }
+            KeyCode::Char('o') if self.psbt.output_tree.is_some() => self.psbt.output_focus = true,
+            KeyCode::Char('l') => self.psbt.picker.open(FileMode::Load),
+            KeyCode::Char('i') => self.open_psbt_editor(String::new(), false),
+            KeyCode::Char('E') if !self.psbt.current_text().is_empty() => {
+                self.open_psbt_editor(self.psbt.current_text().to_string(), true);
+            }
+            KeyCode::Char('y') if let Some(current) = self.psbt.current() => {
+                self.clipboard = Some(current.psbt.clone());
+                let message = format!(
+                    "copied {} to the clipboard (OSC 52, {} chars)",
+                    current.name,
+                    current.psbt.len()
+                );
+                self.psbt.set_output(Some(message));
+                self.psbt.error = None;
+            }
+            KeyCode::Char('s') if let Some(current) = self.psbt.current() => {
+                let encoding = current.encoding;
+                self.psbt.picker.save_name = current.name.clone();
+                self.psbt.save_encoding = encoding;
+                self.psbt.picker.set_extension(encoding.extension());
+                self.psbt.picker.open(FileMode::Save);
+            }
+            KeyCode::Char(']') if !self.psbt.psbts.is_empty() => {
+                self.psbt
+                    .select((self.psbt.active + 1) % self.psbt.psbts.len());
+            }
+            KeyCode::Char('[') if !self.psbt.psbts.is_empty() => {
+                let len = self.psbt.psbts.len();
+                self.psbt.select((self.psbt.active + len - 1) % len);
+            }
+            KeyCode::Char('m') if self.psbt.current().is_some() => {
+                let active = self.psbt.active;
+                if !self.psbt.marked.remove(&active) {
+                    self.psbt.marked.insert(active);
+                }
+            }
+            KeyCode::Char('x') => self.psbt.remove_current(),
+            KeyCode::Char('q') if self.psbt.current().is_some() => {
+                let psbt = self.psbt.current_text().to_string();
+                self.psbt.qr.open(&psbt);
+            }
+            KeyCode::Char('c') | KeyCode::Char('J') if self.psbt.in_flight_request.is_none() => {
+                let batch = self.psbt.batch();
+                if batch.len() < 2 {
+                    self.psbt.error =
+                        Some("mark at least two PSBTs (m) to combine or join".to_string());
+                } else {
+                    self.psbt.action_params = Some(serde_json::json!([batch]));
+                    self.psbt.rpc_in_flight = Some(if key.code == KeyCode::Char('c') {
+                        PsbtRpcAction::Combine
+                    } else {
+                        PsbtRpcAction::Join
+                    });
+                }
+            }
+            KeyCode::Char('D') => self.diff_psbts(),
+            KeyCode::Char('n') => {
+                self.psbt.create.open = true;
+                self.psbt.create.error = None;
+            }
+            KeyCode::Char('a')
+                if !self.psbt.current_text().trim().is_empty()
+                    && self.psbt.in_flight_request.is_none() =>
+            {
+                self.psbt.rpc_in_flight = Some(PsbtRpcAction::Analyze);
+            }
+            KeyCode::Char('d')
+                if !self.psbt.current_text().trim().is_empty()
+                    && self.psbt.in_flight_request.is_none() =>
+            {
+                self.psbt.rpc_in_flight = Some(PsbtRpcAction::Decode);
+            }
+            KeyCode::Char('p') if !self.psbt.current_text().trim().is_empty() => {
+                self.psbt.process.open = true;
+                self.psbt.process.error = None;
+            }
+            KeyCode::Char('f')
+                if !self.psbt.current_text().trim().is_empty()
+                    && self.psbt.in_flight_request.is_none() =>
+            {
+                self.psbt.rpc_in_flight = Some(PsbtRpcAction::Finalize);
+            }
+            KeyCode::Char('u')
+                if !self.psbt.current_text().trim().is_empty()
+                    && self.psbt.in_flight_request.is_none() =>
+            {
+                self.psbt.rpc_in_flight = Some(PsbtRpcAction::UtxoUpdate);
+            }
+            KeyCode::Char('e') if self.psbt.in_flight_request.is_none() => {
+                self.psbt.rpc_in_flight = Some(PsbtRpcAction::EnumerateSigners);
+            }
+            KeyCode::Char('b')
+                if !self.psbt.current_text().trim().is_empty()
+                    && self.psbt.in_flight_request.is_none() =>
+            {
+                self.psbt.rpc_in_flight = Some(PsbtRpcAction::TestAccept);
+            }
+            _ => {}
+        }
+    }
+
+    fn open_psbt_editor(&mut self, text: String, replace: bool) {
+        self.psbt.editor = PsbtEditor {
+            text: TextArea::new(&text),
+            replace,
+            validation: None,
+        };
+        self.psbt.editor.revalidate();
+        self.input_mode = InputMode::PsbtEditor;
+    }
+
+    /// Loads the editor contents as a new PSBT, or in place of the active one when
+    /// editing it. Invalid text keeps the editor open.
+    fn load_psbt_from_editor(&mut self) {
+        let (psbt, encoding) = match self.psbt.editor.parse() {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                self.psbt.editor.validation = Some(Err(e));
+                return;
+            }
+        };
+        if self.psbt.editor.replace {
+            self.psbt.replace_current(psbt);
+            let name = self
+                .psbt
+                .current()
+                .map(|p| p.name.clone())
+                .unwrap_or_default();
+            self.psbt
+                .set_output(Some(format!("updated {} from the editor", name)));
+        } else {
+            self.psbt.push(LoadedPsbt {
+                encoding,
+                ..LoadedPsbt::new("pasted".to_string(), psbt)
+            });
+            self.psbt
+                .set_output(Some(format!("loaded pasted PSBT ({})", encoding.label())));
+        }
+        self.psbt.error = None;
+        self.psbt.scroll = 0;
+        self.psbt.view = PsbtView::Summary;
+        self.input_mode = InputMode::Normal;
+    }
+
+    /// Bracketed paste: goes into the PSBT editor (opening it from the PSBT tab), or is
+    /// appended in one step to whichever single-line input is active, so filters re-run
+    /// once. Modes without a text field ignore it.
+    fn handle_paste(&mut self, text: String) {
+        let line: String = text.chars().filter(|c| !c.is_control()).collect();
+        match self.input_mode {
+            InputMode::PsbtEditor => {
+                self.psbt.editor.text.insert_str(&text);
+                self.psbt.editor.revalidate();
+            }
+            InputMode::Normal => {
+                let popup_open = self.psbt.picker.open
+                    || self.psbt.create.open
+                    || self.psbt.process.open
+                    || self.psbt.qr.open
+                    || self.psbt.signers_open
+                    || self.psbt.broadcast.is_some();
+                if self.tab == Tab::Psbt && self.focus == Focus::Content && !popup_open {
+                    self.open_psbt_editor(text, false);
+                }
+            }
+            InputMode::WalletPicker => {}
+            InputMode::TxSearch => self.transactions.search_input.push_str(&line),
+            InputMode::ArgInput => self.active_browser().arg_input.push_str(&line),
+            InputMode::SaveName => self.active_file_picker().save_name.push_str(&line),
+            InputMode::MethodSearch => {
+                let b = self.active_browser();
+                b.method_search.push_str(&line);
+                b.update_method_filter();
+            }
+            InputMode::DetailSearch => self.active_browser().detail_search.push_str(&line),
+            InputMode::DetailFilter => {
+                let b = self.active_browser();
+                b.filter.push_str(&line);
+                b.apply_filter();
+            }
+            InputMode::PeersQuery => {
+                self.peers_query_input.push_str(&line);
+                self.clear_peers_query_completion();
+            }
+            InputMode::PsbtForm => {
+                if let Some(text) = self.psbt_form_text() {
+                    text.push_str(&line);
+                }
+            }
+            InputMode::ArgForm => {
+                if let Some(form) = &mut self.active_browser().form
+                    && let Some(text) = form.text_mut()
+                {
+                    text.push_str(&line);
+                    if let Some(row) = form.selected_row() {
+                        form.include(&row.path);
                     }
                 }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    self.psbt.picker_selected = self.psbt.picker_selected.saturating_sub(1);
+            }
+        }
+    }
+
+    /// Diffs the marked PSBT (or the only other loaded one) against the active one and
+    /// shows the result in the output panel.
+    fn diff_psbts(&mut self) {
+        let active = self.psbt.active;
+        let others: Vec<usize> = if self.psbt.marked.is_empty() {
+            (0..self.psbt.psbts.len())
+                .filter(|&i| i != active)
+                .collect()
+        } else {
+            self.psbt
+                .marked
+                .iter()
+                .copied()
+                .filter(|&i| i != active)
+                .collect()
+        };
+        let [other] = others[..] else {
+            self.psbt.error =
+                Some("mark exactly one other PSBT (m) to diff against the active one".to_string());
+            return;
+        };
+        let (from, to) = (&self.psbt.psbts[other], &self.psbt.psbts[active]);
+        match (&from.parsed, &to.parsed) {
+            (Ok(a), Ok(b)) => {
+                let mut lines = vec![format!("diff {} -> {}", from.name, to.name)];
+                lines.extend(crate::psbt::diff(a, b));
+                self.psbt.set_output(Some(lines.join("\n")));
+                self.psbt.error = None;
+                self.psbt.scroll = 0;
+            }
+            (Err(e), _) => self.psbt.error = Some(format!("{}: {}", from.name, e)),
+            (_, Err(e)) => self.psbt.error = Some(format!("{}: {}", to.name, e)),
+        }
+    }
+
+    /// Signer list opened by `e`: Enter signs the active PSBT with the selected device
+    /// through the `--signer` command if configured, or else with `walletprocesspsbt` on
+    /// the selected wallet, which uses that wallet's own signer whatever is selected.
+    fn handle_psbt_signers(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let max = self.psbt.signers.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Esc => self.psbt.signers_open = false,
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.psbt.signer_selected = (self.psbt.signer_selected + 1).min(max);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.psbt.signer_selected = self.psbt.signer_selected.saturating_sub(1);
+            }
+            KeyCode::Enter
+                if self.psbt.in_flight_request.is_none()
+                    && !self.psbt.current_text().trim().is_empty() =>
+            {
+                let Some(signer) = self.psbt.signers.get(self.psbt.signer_selected) else {
+                    return;
+                };
+                if self.psbt.config.signer.is_some() {
+                    self.psbt.action_params = Some(serde_json::json!(signer.fingerprint));
+                    self.psbt.rpc_in_flight = Some(PsbtRpcAction::ExternalSign);
+                } else {
+                    let params = self.psbt.process.wallet_params(self.psbt.current_text());
+                    self.psbt.action_params = Some(params);
+                    self.psbt.rpc_in_flight = Some(PsbtRpcAction::WalletSign);
                 }
-                KeyCode::Char('e') if self.psbt.picker_mode == PsbtFileMode::Save => {
-                    self.input_mode = InputMode::PsbtSaveName;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_psbt_qr(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let qr = &mut self.psbt.qr;
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => qr.open = false,
+            KeyCode::Char('f') => {
+                qr.format = qr.format.toggle();
+                qr.rebuild();
+            }
+            KeyCode::Char(']') if qr.density + 1 < crate::psbt_qr::DENSITIES.len() => {
+                qr.density += 1;
+                qr.rebuild();
+            }
+            KeyCode::Char('[') if qr.density > 0 => {
+                qr.density -= 1;
+                qr.rebuild();
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                qr.fps = (qr.fps + 1).min(crate::psbt_qr::MAX_FPS);
+            }
+            KeyCode::Char('-') => qr.fps = qr.fps.saturating_sub(1).max(1),
+            KeyCode::Char(' ') => qr.paused = !qr.paused,
+            KeyCode::Right | KeyCode::Char('l') => qr.step(1),
+            KeyCode::Left | KeyCode::Char('h') => qr.step(-1),
+            _ => {}
+        }
+    }
+
+    /// The free-text field being edited in whichever PSBT form is open.
+    fn psbt_form_text(&mut self) -> Option<&mut String> {
+        if self.psbt.process.open {
+            let row = self.psbt.process.selected_row();
+            self.psbt.process.text_mut(row)
+        } else {
+            let row = self.psbt.create.selected_row();
+            self.psbt.create.text_mut(row)
+        }
+    }
+
+    /// Process form opened by `p`: runs `walletprocesspsbt` or `descriptorprocesspsbt`
+    /// on the active PSBT with the chosen options.
+    fn handle_psbt_process(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let form = &mut self.psbt.process;
+        let row = form.selected_row();
+        let max = form.rows().len() - 1;
+        match key.code {
+            KeyCode::Esc => form.open = false,
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                form.selected = (form.selected + 1).min(max);
+            }
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+                form.selected = form.selected.saturating_sub(1);
+            }
+            KeyCode::Char('x') => {
+                if let ProcessRow::Descriptor(i) = row {
+                    form.remove_descriptor(i);
+                    self.save_psbt_descriptors();
                 }
-                KeyCode::Char('w') if self.psbt.picker_mode == PsbtFileMode::Save => {
-                    let target = self.psbt.picker_dir.join(self.psbt.save_name.trim());
-                    self.save_psbt_to_file(&target);
+            }
+            KeyCode::Char(' ') => {
+                form.toggle(row);
+            }
+            KeyCode::Enter if row == ProcessRow::Submit => {
+                if self.psbt.in_flight_request.is_some() {
+                    return;
                 }
-                KeyCode::Enter => {
-                    if let Some(entry) = self.psbt.picker_entries.get(self.psbt.picker_selected) {
-                        if entry.is_dir {
-                            self.psbt.picker_dir = entry.path.clone();
-                            self.psbt.picker_selected = 0;
-                            self.refresh_psbt_picker();
-                        } else if self.psbt.picker_mode == PsbtFileMode::Load {
-                            let path = entry.path.clone();
-                            self.load_psbt_from_file(&path);
-                        } else {
-                            let path = entry.path.clone();
-                            self.save_psbt_to_file(&path);
+                let psbt = self.psbt.current_text().to_string();
+                let form = &mut self.psbt.process;
+                let request = match form.mode {
+                    ProcessMode::Wallet => {
+                        Ok((PsbtRpcAction::WalletProcess, form.wallet_params(&psbt)))
+                    }
+                    ProcessMode::Descriptors => form
+                        .descriptor_params(&psbt)
+                        .map(|params| (PsbtRpcAction::DescriptorProcess, params)),
+                };
+                match request {
+                    Ok((action, params)) => {
+                        form.error = None;
+                        if form.save_new_descriptor() {
+                            self.save_psbt_descriptors();
                         }
+                        self.psbt.action_params = Some(params);
+                        self.psbt.rpc_in_flight = Some(action);
                     }
+                    Err(e) => form.error = Some(e),
+                }
+            }
+            KeyCode::Enter => {
+                if form.text_mut(row).is_some() {
+                    self.input_mode = InputMode::PsbtForm;
+                } else {
+                    form.toggle(row);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_psbt_create(&mut self, key: KeyEvent) {
+        use crossterm::event::KeyCode;
+
+        let form = &mut self.psbt.create;
+        let row = form.selected_row();
+        let max = form.rows().len() - 1;
+        match key.code {
+            KeyCode::Esc => form.open = false,
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                form.selected = (form.selected + 1).min(max);
+            }
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+                form.selected = form.selected.saturating_sub(1);
+            }
+            KeyCode::Char('o') => form.add_output(),
+            KeyCode::Char('x') => {
+                if let FormRow::Address(i) | FormRow::Amount(i) = row {
+                    form.remove_output(i);
+                }
+            }
+            KeyCode::Char('f') => {
+                if let FormRow::Address(i) | FormRow::Amount(i) = row {
+                    form.outputs[i].subtract_fee = !form.outputs[i].subtract_fee;
+                }
+            }
+            KeyCode::Char(' ') => {
+                form.toggle(row);
+            }
+            KeyCode::Enter if row == FormRow::Submit => {
+                if self.psbt.in_flight_request.is_some() {
+                    return;
+                }
+                match form.build_params() {
+                    Ok(params) => {
+                        form.error = None;
+                        self.psbt.action_params = Some(params);
+                        self.psbt.rpc_in_flight = Some(PsbtRpcAction::Create);
+                    }
+                    Err(e) => form.error = Some(e),
+                }
+            }
+            KeyCode::Enter => {
+                if form.text_mut(row).is_some() {
+                    self.input_mode = InputMode::PsbtForm;
+                } else {
+                    form.toggle(row);
                 }
-                _ => {}
             }
-            return;
+            _ => {}
         }
+    }
+
+    /// Tree navigation for the PSBT field view; returns false for keys it doesn't use.
+    fn handle_psbt_tree(&mut self, key: KeyEvent) -> bool {
+        use crossterm::event::KeyCode;
+
+        let rows = crate::psbt::flatten(self.psbt.tree(), &self.psbt.tree_expanded);
+        let max = rows.len().saturating_sub(1);
+        let Some(row) = rows.get(self.psbt.tree_selected.min(max)) else {
+            return false;
+        };
+        let path = row.path.clone();
+        let has_children = !row.node.children.is_empty();
+        let expanded = row.expanded;
+        drop(rows);
 
         match key.code {
-            KeyCode::Esc => self.focus = Focus::TabBar,
-            KeyCode::Down | KeyCode::Char('j') => self.psbt.scroll = self.psbt.scroll.saturating_add(1),
-            KeyCode::Up | KeyCode::Char('k') => self.psbt.scroll = self.psbt.scroll.saturating_sub(1),
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.psbt.scroll = self.psbt.scroll.saturating_add(20);
-            }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.psbt.scroll = self.psbt.scroll.saturating_sub(20);
-            }
-            KeyCode::Char('l') => self.open_psbt_picker(PsbtFileMode::Load),
-            KeyCode::Char('s') => self.open_psbt_picker(PsbtFileMode::Save),
-            KeyCode::Char('a')
-                if !self.psbt.psbt.trim().is_empty() && self.psbt.in_flight_request.is_none() =>
-            {
-                self.psbt.rpc_in_flight = Some(PsbtRpcAction::Analyze);
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.psbt.tree_selected = (self.psbt.tree_selected + 1).min(max);
             }
-            KeyCode::Char('d')
-                if !self.psbt.psbt.trim().is_empty() && self.psbt.in_flight_request.is_none() =>
-            {
-                self.psbt.rpc_in_flight = Some(PsbtRpcAction::Decode);
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.psbt.tree_selected = self.psbt.tree_selected.saturating_sub(1);
             }
-            KeyCode::Char('p')
-                if !self.psbt.psbt.trim().is_empty() && self.psbt.in_flight_request.is_none() =>
-            {
-                self.psbt.rpc_in_flight = Some(PsbtRpcAction::WalletProcess);
+            KeyCode::Char('g') => self.psbt.tree_selected = 0,
+            KeyCode::Char('G') => self.psbt.tree_selected = max,
+            KeyCode::Enter | KeyCode::Char(' ') if has_children => {
+                if expanded {
+                    self.psbt.tree_expanded.remove(&path);
+                } else {
+                    self.psbt.tree_expanded.insert(path);
+                }
             }
-            KeyCode::Char('f')
-                if !self.psbt.psbt.trim().is_empty() && self.psbt.in_flight_request.is_none() =>
-            {
-                self.psbt.rpc_in_flight = Some(PsbtRpcAction::Finalize);
+            KeyCode::Right if has_children => {
+                self.psbt.tree_expanded.insert(path);
             }
-            KeyCode::Char('u')
-                if !self.psbt.psbt.trim().is_empty() && self.psbt.in_flight_request.is_none() =>
-            {
-                self.psbt.rpc_in_flight = Some(PsbtRpcAction::UtxoUpdate);
+            KeyCode::Left | KeyCode::Char('h') => {
+                if expanded {
+                    self.psbt.tree_expanded.remove(&path);
+                } else if let Some((parent, _)) = path.rsplit_once('/') {
+                    let parent = parent.to_string();
+                    let rows = crate::psbt::flatten(self.psbt.tree(), &self.psbt.tree_expanded);
+                    if let Some(idx) = rows.iter().position(|r| r.path == parent) {
+                        self.psbt.tree_selected = idx;
+                    }
+                }
             }
-            _ => {}
+            _ => return false,
         }
+        true
     }
 
     fn handle_zmq_content(&mut self, key: KeyEvent) {
         use crossterm::event::{KeyCode, KeyModifiers};
 
-        if self.zmq.block_popup_loading || self.zmq.block_popup.is_some() || self.zmq.block_popup_error.is_some() {
-            match key.code {
-                KeyCode::Esc => {
-                    self.zmq.block_popup_loading = false;
-                    self.zmq.block_popup = None;
-                    self.zmq.block_popup_error = None;
-                    self.zmq.block_popup_scroll = 0;
-                }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    self.zmq.block_popup_scroll = self.zmq.block_popup_scroll.saturating_add(1);
-                }
-                KeyCode::Up | KeyCode::Char('k') => {
-                    self.zmq.block_popup_scroll = self.zmq.block_popup_scroll.saturating_sub(1);
-                }
-                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                    self.zmq.block_popup_scroll = self.zmq.block_popup_scroll.saturating_add(20);
-                }
-                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                    self.zmq.block_popup_scroll = self.zmq.block_popup_scroll.saturating_sub(20);
+        if self.zmq.block_popup_loading
+            || self.zmq.block_popup.is_some()
+            || self.zmq.block_popup_error.is_some()
+        {
+            if key.code == KeyCode::Esc {
+                self.zmq.block_popup_loading = false;
+                self.zmq.block_popup = None;
+                self.zmq.block_popup_error = None;
+            } else if let Some(tree) = &mut self.zmq.block_popup {
+                match tree.handle_key(key) {
+                    TreeAction::Copy(text) => self.clipboard = Some(text),
+                    TreeAction::Save(export) => {
+                        let stem = value_stem(tree.value(), "hash", "block");
+                        self.start_result_export(export, &stem);
+                    }
+                    TreeAction::None => {}
+                    TreeAction::Ignored => {
+                        if key.code == KeyCode::Char('a') {
+                            tree.annotations = !tree.annotations;
+                        }
+                    }
                 }
-                _ => {}
             }
             return;
         }
@@ -1154,35 +2996,68 @@ impl App {
                 let fwd_index = max - rev_index;
                 let entry = &self.zmq.entries[fwd_index];
                 if entry.topic == "hashtx" {
-                    self.transactions.search_input = entry.hash.clone();
-                    self.transactions.searching = true;
-                    self.transactions.result = None;
-                    self.transactions.error = None;
-                    self.transactions.result_scroll = 0;
-                    self.transactions_return_target = Some((Tab::Zmq, Focus::Content));
-                    self.tab = Tab::Transactions;
-                    self.focus = Focus::Content;
-                    self.input_mode = InputMode::Normal;
+                    self.open_transaction(entry.hash.clone(), Tab::Zmq);
                 } else if entry.topic == "hashblock" {
                     self.zmq.block_lookup = Some(entry.hash.clone());
                     self.zmq.block_popup_loading = true;
                     self.zmq.block_popup = None;
                     self.zmq.block_popup_error = None;
-                    self.zmq.block_popup_scroll = 0;
                 }
             }
             _ => {}
         }
     }
 
+    /// Looks up `txid` in the Transactions tab; Esc there returns to `from`.
+    fn open_transaction(&mut self, txid: String, from: Tab) {
+        self.transactions.search_input = txid;
+        self.transactions.searching = true;
+        self.transactions.result = None;
+        self.transactions.decoded = None;
+        self.transactions.error = None;
+        self.transactions.result_scroll = 0;
+        self.transactions_return_target = Some((from, Focus::Content));
+        self.tab = Tab::Transactions;
+        self.focus = Focus::Content;
+        self.input_mode = InputMode::Normal;
+    }
+
     fn handle_peers_content(&mut self, key: KeyEvent) {
         use crossterm::event::{KeyCode, KeyModifiers};
 
         if self.peers_popup.is_some() {
+            if self.peers_popup_raw
+                && let Some(tree) = &mut self.peers_popup_tree
+            {
+                match tree.handle_key(key) {
+                    TreeAction::Ignored => {}
+                    TreeAction::None => return,
+                    TreeAction::Copy(text) => {
+                        self.clipboard = Some(text);
+                        return;
+                    }
+                    TreeAction::Save(export) => {
+                        let stem = value_stem(tree.value(), "id", "peer");
+                        self.start_result_export(export, &stem);
+                        return;
+                    }
+                }
+            }
             match key.code {
                 KeyCode::Esc => {
                     self.peers_popup = None;
                     self.peers_popup_scroll = 0;
+                    self.sync_peer_tree();
+                }
+                KeyCode::Char('r') => {
+                    self.peers_popup_raw = !self.peers_popup_raw;
+                    self.peers_popup_scroll = 0;
+                    self.sync_peer_tree();
+                }
+                KeyCode::Char('a') => {
+                    if let Some(tree) = &mut self.peers_popup_tree {
+                        tree.annotations = !tree.annotations;
+                    }
                 }
                 KeyCode::Down | KeyCode::Char('j') => {
                     self.peers_popup_scroll = self.peers_popup_scroll.saturating_add(1);
@@ -1224,11 +3099,100 @@ impl App {
             return;
         }
 
+        if self.addrman.open {
+            let len = self.addrman.visible.len();
+            let max = len.saturating_sub(1);
+            match key.code {
+                KeyCode::Esc => self.addrman.open = false,
+                KeyCode::Char('r') => self.addrman.request_refresh(),
+                KeyCode::Char('n') => {
+                    self.addrman.network_filter =
+                        (self.addrman.network_filter + 1) % ADDRMAN_NETWORKS.len();
+                    self.addrman.selected = 0;
+                    self.addrman.request_refresh();
+                }
+                KeyCode::Char('s') => {
+                    self.addrman.service_filter =
+                        (self.addrman.service_filter + 1) % ADDRMAN_SERVICES.len();
+                    self.addrman.selected = 0;
+                    self.addrman.refresh_visible();
+                }
+                KeyCode::Down | KeyCode::Char('j') if len > 0 => {
+                    self.addrman.selected = (self.addrman.selected + 1).min(max);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.addrman.selected = self.addrman.selected.saturating_sub(1);
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.addrman.selected = (self.addrman.selected + 20).min(max);
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.addrman.selected = self.addrman.selected.saturating_sub(20);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        if self.peers_export_picker.open {
+            if key.code == KeyCode::Char('f')
+                && self.peers_export_picker.confirm_overwrite.is_none()
+            {
+                self.peers_export_format = self.peers_export_format.toggle();
+                self.peers_export_picker
+                    .set_extension(self.peers_export_format.extension());
+                return;
+            }
+            match self.peers_export_picker.handle_key(key) {
+                PickerAction::None | PickerAction::Load(_) | PickerAction::LoadMany(_) => {}
+                PickerAction::Close => self.input_mode = InputMode::Normal,
+                PickerAction::EditName => self.input_mode = InputMode::SaveName,
+                PickerAction::Save(path) => self.export_peers_to_file(&path),
+            }
+            return;
+        }
+
+        if self.peers_events_focused {
+            let max = self.peers_events.len().saturating_sub(1);
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('e') => self.peers_events_focused = false,
+                KeyCode::Down | KeyCode::Char('j') => {
+                    self.peers_events_scroll = (self.peers_events_scroll + 1).min(max);
+                }
+                KeyCode::Up | KeyCode::Char('k') => {
+                    self.peers_events_scroll = self.peers_events_scroll.saturating_sub(1);
+                }
+                KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.peers_events_scroll = (self.peers_events_scroll + 20).min(max);
+                }
+                KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    self.peers_events_scroll = self.peers_events_scroll.saturating_sub(20);
+                }
+                KeyCode::Char('g') => self.peers_events_scroll = 0,
+                KeyCode::Char('G') => self.peers_events_scroll = max,
+                _ => {}
+            }
+            return;
+        }
+
         let len = self.peers_visible_indices.len();
         let max = len.saturating_sub(1);
 
         match key.code {
             KeyCode::Esc => self.focus = Focus::TabBar,
+            KeyCode::Char('e') => self.peers_events_focused = true,
+            KeyCode::Char('x') if len > 0 => {
+                let stamp = crate::format::fmt_unix_time(crate::format::unix_now())
+                    .replace(' ', "_")
+                    .replace(':', "");
+                self.peers_export_picker.save_name =
+                    format!("peers-{}.{}", stamp, self.peers_export_format.extension());
+                self.peers_export_picker.open(FileMode::Save);
+            }
+            KeyCode::Char('a') => {
+                self.addrman.open = true;
+                self.addrman.request_refresh();
+            }
             KeyCode::Char(':') => {
                 self.input_mode = InputMode::PeersQuery;
                 self.peers_query_input.clear();
@@ -1260,26 +3224,92 @@ impl App {
                 self.peers_selected = self.peers_selected.saturating_sub(20);
             }
             KeyCode::Enter if len > 0 => {
-                self.peers_popup = self
-                    .peers
-                    .as_ref()
-                    .and_then(|peers| {
-                        let src_idx = self.peers_visible_indices.get(self.peers_selected)?;
-                        peers.get(*src_idx)
-                    })
-                    .and_then(|peer| serde_json::to_string_pretty(peer).ok());
+                self.peers_popup = self.peers.as_ref().and_then(|peers| {
+                    let src_idx = self.peers_visible_indices.get(self.peers_selected)?;
+                    peers.get(*src_idx).map(|peer| peer.id)
+                });
                 self.peers_popup_scroll = 0;
+                self.sync_peer_tree();
             }
             _ => {}
         }
     }
 
+    fn start_result_export(&mut self, export: Export, stem: &str) {
+        let name = match &export.label {
+            Some(label) => format!("{}-{}", stem, label.replace(['/', '\\'], "_")),
+            None => stem.to_string(),
+        };
+        let export_picker = &mut self.result_export.picker;
+        export_picker.save_name = format!("{}.{}", name, export.extension);
+        export_picker.open(FileMode::Save);
+        self.result_export.pending = Some(export);
+    }
+
+    fn handle_result_export(&mut self, key: KeyEvent) {
+        match self.result_export.picker.handle_key(key) {
+            PickerAction::None | PickerAction::Load(_) | PickerAction::LoadMany(_) => {}
+            PickerAction::Close => self.result_export.pending = None,
+            PickerAction::EditName => self.input_mode = InputMode::SaveName,
+            PickerAction::Save(path) => {
+                let Some(export) = self.result_export.pending.take() else {
+                    return;
+                };
+                self.result_export.picker.open = false;
+                let notice = match std::fs::write(&path, &export.text) {
+                    Ok(_) => format!("saved to {}", path.display()),
+                    Err(e) => format!("save {}: {}", path.display(), e),
+                };
+                if let Some(tree) = self.focused_result_tree() {
+                    tree.notice = Some(notice);
+                }
+            }
+        }
+    }
+
+    /// The result tree that has the keys, for export feedback.
+    fn focused_result_tree(&mut self) -> Option<&mut JsonTree> {
+        match self.tab {
+            Tab::Rpc | Tab::Wallet => self.active_browser().result.as_mut(),
+            Tab::Transactions => self.transactions.decoded.as_mut(),
+            Tab::Zmq => self.zmq.block_popup.as_mut(),
+            Tab::Psbt => self.psbt.output_tree.as_mut(),
+            Tab::Peers => self.peers_popup_tree.as_mut(),
+            Tab::Dashboard => None,
+        }
+    }
+
+    fn export_peers_to_file(&mut self, path: &PathBuf) {
+        let Some(peers) = &self.peers else {
+            return;
+        };
+        let content = peers_export::export(
+            peers,
+            &self.peers_visible_indices,
+            &self.peers_query.columns,
+            &self.peer_history,
+            self.peers_export_format,
+        );
+        self.peers_export_status = Some(match std::fs::write(path, content) {
+            Ok(_) => {
+                self.peers_export_picker.open = false;
+                Ok(format!(
+                    "exported {} peers to {}",
+                    self.peers_visible_indices.len(),
+                    path.display()
+                ))
+            }
+            Err(e) => Err(format!("export {}: {}", path.display(), e)),
+        });
+    }
+
     fn refresh_peers_view(&mut self) {
         let Some(peers) = &self.peers else {
             self.peers_visible_indices.clear();
             self.peers_selected = 0;
             self.peers_popup = None;
             self.peers_popup_scroll = 0;
+            self.peers_popup_tree = None;
             return;
         };
 
@@ -1289,7 +3319,33 @@ impl App {
             self.peers_popup = None;
             self.peers_popup_scroll = 0;
         } else {
-            self.peers_selected = self.peers_selected.min(self.peers_visible_indices.len() - 1);
+            self.peers_selected = self
+                .peers_selected
+                .min(self.peers_visible_indices.len() - 1);
+        }
+        self.sync_peer_tree();
+    }
+
+    /// Keeps the raw peer tree in step with the popup and the latest poll; the tree keeps
+    /// what was expanded across polls.
+    fn sync_peer_tree(&mut self) {
+        let peer = self
+            .peers_popup
+            .filter(|_| self.peers_popup_raw)
+            .and_then(|id| self.peers.as_ref()?.iter().find(|p| p.id == id))
+            .and_then(|peer| serde_json::to_value(peer).ok());
+        let Some(value) = peer else {
+            self.peers_popup_tree = None;
+            return;
+        };
+        match &mut self.peers_popup_tree {
+            Some(tree) => tree.set_value(value),
+            None => {
+                let schema = self
+                    .result_schema("getpeerinfo")
+                    .and_then(|s| s.items.as_deref());
+                self.peers_popup_tree = Some(JsonTree::new(value, schema));
+            }
         }
     }
 
@@ -1328,11 +3384,180 @@ impl App {
         }
     }
 
+    fn handle_call_confirm(&mut self, key: KeyEvent) {
+        let b = self.active_browser();
+        let Some(confirm) = &mut b.confirm else {
+            return;
+        };
+        match confirm.handle_key(key, &b.methods[b.selected].name) {
+            Some(true) => {
+                b.confirm = None;
+                b.calling = true;
+            }
+            Some(false) => {
+                b.confirm = None;
+                b.call_params = None;
+            }
+            None => {}
+        }
+    }
+
+    /// Lists the macro directory and opens the palette.
+    pub fn open_macros(&mut self) {
+        let paths = storage::macro_dir()
+            .map(|dir| rpc_macro::list(&dir))
+            .unwrap_or_default();
+        self.macros.entries = paths
+            .into_iter()
+            .map(|path| {
+                let script = Macro::load(&path);
+                MacroEntry {
+                    name: path
+                        .file_stem()
+                        .map(|s| s.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                    description: script.map(|m| m.description),
+                    path,
+                }
+            })
+            .collect();
+        self.macros.open = true;
+        self.macros.query.clear();
+        self.macros.selected = 0;
+        self.macros.status = None;
+    }
+
+    fn start_macro(&mut self, path: PathBuf) {
+        let script = match Macro::load(&path) {
+            Ok(script) => script,
+            Err(e) => {
+                self.macros.status = Some(e);
+                return;
+            }
+        };
+        let class = script.class();
+        self.macros.run_seq = self.macros.run_seq.wrapping_add(1);
+        let mut run = MacroRun {
+            id: self.macros.run_seq,
+            path,
+            name: script.name.clone(),
+            steps: script.steps.iter().map(|s| s.text.clone()).collect(),
+            reports: Vec::new(),
+            done: false,
+            confirm: None,
+            pending: None,
+            scroll_back: 0,
+        };
+        // With --read-only the client refuses the calls that would need confirming.
+        if self.read_only || class == MethodClass::ReadOnly {
+            self.macros.start = Some((run.id, script.steps));
+        } else {
+            run.confirm = Some(CallConfirm {
+                class,
+                typed: String::new(),
+            });
+            run.pending = Some(script);
+        }
+        self.macros.run = Some(run);
+    }
+
+    fn handle_macros(&mut self, key: KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        if let Some(run) = &mut self.macros.run {
+            if let Some(confirm) = &mut run.confirm {
+                match confirm.handle_key(key, &run.name) {
+                    Some(true) => {
+                        run.confirm = None;
+                        if let Some(script) = run.pending.take() {
+                            self.macros.start = Some((run.id, script.steps));
+                        }
+                    }
+                    Some(false) => self.macros.run = None,
+                    None => {}
+                }
+                return;
+            }
+            match key.code {
+                KeyCode::Esc => {
+                    if !run.done {
+                        self.macros.cancel = true;
+                    }
+                    self.macros.run = None;
+                }
+                KeyCode::Char('r') if run.done => {
+                    let path = run.path.clone();
+                    self.start_macro(path);
+                }
+                KeyCode::Char('k') | KeyCode::Up => {
+                    run.scroll_back = run.scroll_back.saturating_add(1)
+                }
+                KeyCode::Char('j') | KeyCode::Down => {
+                    run.scroll_back = run.scroll_back.saturating_sub(1)
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        let count = self.macros.filtered().len();
+        match key.code {
+            KeyCode::Esc => self.macros.open = false,
+            KeyCode::Down => {
+                self.macros.selected = (self.macros.selected + 1).min(count.saturating_sub(1))
+            }
+            KeyCode::Up => self.macros.selected = self.macros.selected.saturating_sub(1),
+            KeyCode::Enter => {
+                let path = self
+                    .macros
+                    .filtered()
+                    .get(self.macros.selected)
+                    .map(|e| e.path.clone());
+                if let Some(path) = path {
+                    self.start_macro(path);
+                }
+            }
+            KeyCode::Backspace => {
+                self.macros.query.pop();
+                self.macros.selected = 0;
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.macros.query.clear();
+                self.macros.selected = 0;
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.macros.query.push(c);
+                self.macros.selected = 0;
+            }
+            _ => {}
+        }
+    }
+
     fn handle_browser_content(&mut self, key: KeyEvent) {
         use crossterm::event::KeyCode;
 
+        if self.history.open {
+            self.handle_history(key);
+            return;
+        }
+
+        if self.active_browser().confirm.is_some() {
+            self.handle_call_confirm(key);
+            return;
+        }
+
+        if self.active_browser().form.is_some() {
+            self.handle_arg_form(key);
+            return;
+        }
+
         match key.code {
             KeyCode::Esc => self.focus = Focus::TabBar,
+            KeyCode::Char('H') => {
+                self.history.open = true;
+                self.history.selected = 0;
+                self.history.status = None;
+            }
             KeyCode::Tab => {
                 let b = self.active_browser();
                 b.pane = match b.pane {
@@ -1351,9 +3576,7 @@ impl App {
                     }
                     BrowserPane::Detail => {
                         self.input_mode = InputMode::DetailSearch;
-                        let b = self.active_browser();
-                        b.detail_search.clear();
-                        b.detail_matches.clear();
+                        self.active_browser().clear_search();
                     }
                 }
             }
@@ -1367,6 +3590,67 @@ impl App {
         }
     }
 
+    /// Keys for the argument form that replaces the Detail pane while it is open.
+    fn handle_arg_form(&mut self, key: KeyEvent) {
+        use crossterm::event::{KeyCode, KeyModifiers};
+
+        let read_only = self.read_only;
+        let b = self.active_browser();
+        let Some(form) = &mut b.form else {
+            return;
+        };
+        let rows = form.rows();
+        let max = rows.len().saturating_sub(1);
+        let row = form.selected_row();
+        match key.code {
+            KeyCode::Esc => b.form = None,
+            KeyCode::Down | KeyCode::Char('j') | KeyCode::Tab => {
+                form.selected = (form.selected + 1).min(max);
+            }
+            KeyCode::Up | KeyCode::Char('k') | KeyCode::BackTab => {
+                form.selected = form.selected.saturating_sub(1);
+            }
+            KeyCode::Char('c') => b.submit_form(read_only),
+            KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                b.submit_form(read_only)
+            }
+            _ => {
+                let Some(row) = row else {
+                    return;
+                };
+                match (key.code, row.kind) {
+                    (KeyCode::Enter, RowKind::AddItem | RowKind::AddEntry) => {
+                        form.add_child(&row.path);
+                        if form.editing_key {
+                            self.input_mode = InputMode::ArgForm;
+                        }
+                    }
+                    (KeyCode::Enter, RowKind::Field) => {
+                        let kind = form.field(&row.path).map(|f| f.kind());
+                        if kind == Some(FieldKind::Scalar) {
+                            self.input_mode = InputMode::ArgForm;
+                        } else {
+                            form.toggle(&row.path);
+                        }
+                    }
+                    (KeyCode::Char(' '), RowKind::Field) => {
+                        form.toggle(&row.path);
+                    }
+                    (KeyCode::Char('x') | KeyCode::Delete, RowKind::Field) => {
+                        form.remove(&row.path)
+                    }
+                    (KeyCode::Char('r'), RowKind::Field)
+                        if form.field(&row.path).is_some_and(|f| f.custom_key) =>
+                    {
+                        form.editing_key = true;
+                        self.input_mode = InputMode::ArgForm;
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
     fn handle_methods_pane(&mut self, key: KeyEvent) {
         use crossterm::event::KeyCode;
 
@@ -1375,30 +3659,17 @@ impl App {
                 let b = self.active_browser();
                 let len = b.methods.len();
                 if len > 0 {
-                    b.selected = (b.selected + 1) % len;
-                    b.list_state.select(Some(b.selected));
-                    b.result = None;
-                    b.error = None;
-                    b.arg_input.clear();
-                    b.result_scroll = 0;
-                    b.detail_search.clear();
-                    b.detail_matches.clear();
+                    b.select((b.selected + 1) % len);
                 }
             }
             KeyCode::Char('k') | KeyCode::Up => {
                 let b = self.active_browser();
                 let len = b.methods.len();
                 if len > 0 {
-                    b.selected = (b.selected + len - 1) % len;
-                    b.list_state.select(Some(b.selected));
-                    b.result = None;
-                    b.error = None;
-                    b.arg_input.clear();
-                    b.result_scroll = 0;
-                    b.detail_search.clear();
-                    b.detail_matches.clear();
+                    b.select((b.selected + len - 1) % len);
                 }
             }
+            KeyCode::Char('R') => self.check_node_methods(true),
             KeyCode::Char('w') if self.tab == Tab::Wallet => {
                 self.wallet.fetching_wallets = true;
             }
@@ -1421,54 +3692,86 @@ impl App {
         }
     }
 
+    fn scroll_detail(&mut self, delta: isize) {
+        let b = self.active_browser();
+        let scroll = (b.detail_scroll as isize).saturating_add(delta);
+        b.detail_scroll = scroll.clamp(0, u16::MAX as isize) as u16;
+    }
+
     fn handle_detail_pane(&mut self, key: KeyEvent) {
         use crossterm::event::{KeyCode, KeyModifiers};
 
+        // Enter still calls the method; the result tree gets the other keys first.
+        let b = self.active_browser();
+        if b.showing_result()
+            && key.code != KeyCode::Enter
+            && let Some(tree) = &mut b.result
+        {
+            match tree.handle_key(key) {
+                TreeAction::Ignored => {}
+                TreeAction::None => return,
+                TreeAction::Copy(text) => {
+                    self.clipboard = Some(text);
+                    return;
+                }
+                TreeAction::Save(export) => {
+                    let stem = b.methods[b.selected].name.clone();
+                    self.start_result_export(export, &stem);
+                    return;
+                }
+            }
+        }
+
         match key.code {
             KeyCode::Enter => {
-                let needs_args = {
-                    let b = self.active_browser();
-                    let needs = !b.methods[b.selected].params.is_empty();
-                    if needs {
-                        b.editing_args = true;
-                    } else {
-                        b.calling = true;
-                    }
-                    needs
-                };
-                if needs_args {
-                    self.input_mode = InputMode::ArgInput;
+                let read_only = self.read_only;
+                let b = self.active_browser();
+                let method = &b.methods[b.selected];
+                if method.params.is_empty() {
+                    b.request_call(read_only);
+                } else {
+                    b.form = Some(ArgForm::new(method));
                 }
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.active_browser().result_scroll =
-                    self.active_browser().result_scroll.saturating_add(1);
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.active_browser().result_scroll =
-                    self.active_browser().result_scroll.saturating_sub(1);
+            KeyCode::Char('e') => {
+                let b = self.active_browser();
+                b.editing_args = true;
+                b.show_help = true;
+                self.input_mode = InputMode::ArgInput;
             }
-            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.active_browser().result_scroll =
-                    self.active_browser().result_scroll.saturating_add(20);
+            KeyCode::Char('?') => {
+                let b = self.active_browser();
+                if b.result.is_some() {
+                    b.show_help = !b.show_help;
+                }
             }
-            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.active_browser().result_scroll =
-                    self.active_browser().result_scroll.saturating_sub(20);
+            KeyCode::Char('a') => {
+                if let Some(tree) = &mut self.active_browser().result {
+                    tree.annotations = !tree.annotations;
+                }
             }
-            KeyCode::Char('n') => {
+            KeyCode::Char('|') => {
                 let b = self.active_browser();
-                if !b.detail_matches.is_empty() {
-                    b.detail_match_index = (b.detail_match_index + 1) % b.detail_matches.len();
-                    b.result_scroll = b.detail_matches[b.detail_match_index];
+                if b.raw_result.is_some() {
+                    b.show_help = false;
+                    self.input_mode = InputMode::DetailFilter;
                 }
             }
-            KeyCode::Char('N') => {
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_detail(1),
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_detail(-1),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_detail(20)
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.scroll_detail(-20)
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') => {
                 let b = self.active_browser();
-                if !b.detail_matches.is_empty() {
-                    let len = b.detail_matches.len();
-                    b.detail_match_index = (b.detail_match_index + len - 1) % len;
-                    b.result_scroll = b.detail_matches[b.detail_match_index];
+                if let Some(tree) = &mut b.result
+                    && !tree.matches().is_empty()
+                {
+                    tree.next_match(key.code == KeyCode::Char('n'));
+                    b.show_help = false;
                 }
             }
             _ => {}
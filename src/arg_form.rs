@@ -0,0 +1,523 @@
+use serde_json::{Map, Value};
+
+use crate::wallet_schema::{RpcMethod, Schema};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    /// Free text converted according to the schema type.
+    Scalar,
+    Boolean,
+    Object,
+    Array,
+}
+
+/// One editable node of the argument form: a param, an object property or an array item.
+pub struct Field {
+    pub name: String,
+    pub description: String,
+    pub schema: Schema,
+    pub required: bool,
+    /// Optional fields stay out of the request until they are edited or toggled on.
+    pub included: bool,
+    /// A free-form object entry whose key the user chooses (e.g. an address in `outputs`).
+    pub custom_key: bool,
+    pub aliases: Vec<String>,
+    pub text: String,
+    pub children: Vec<Field>,
+}
+
+impl Field {
+    fn new(name: &str, description: &str, schema: &Schema, required: bool) -> Self {
+        let mut field = Field {
+            name: name.to_string(),
+            description: description.to_string(),
+            schema: schema.clone(),
+            required,
+            included: required,
+            custom_key: false,
+            aliases: Vec::new(),
+            text: String::new(),
+            children: Vec::new(),
+        };
+        match field.kind() {
+            FieldKind::Object => {
+                field.children = schema
+                    .properties
+                    .iter()
+                    .map(|(name, prop)| {
+                        Field::new(
+                            name,
+                            prop.description.as_deref().unwrap_or_default(),
+                            prop,
+                            schema.required.contains(name),
+                        )
+                    })
+                    .collect();
+            }
+            FieldKind::Boolean => field.text = "false".to_string(),
+            _ => {}
+        }
+        field
+    }
+
+    pub fn kind(&self) -> FieldKind {
+        match self.schema.schema_type.as_deref() {
+            Some("boolean") => FieldKind::Boolean,
+            Some("object")
+                if !self.schema.properties.is_empty()
+                    || self.schema.additional_schema().is_some() =>
+            {
+                FieldKind::Object
+            }
+            Some("array") if self.schema.items.is_some() => FieldKind::Array,
+            _ => FieldKind::Scalar,
+        }
+    }
+
+    /// Converts the field to JSON, validating it against its schema.
+    pub fn to_value(&self) -> Result<Value, String> {
+        match self.kind() {
+            FieldKind::Boolean => Ok(Value::Bool(self.text == "true")),
+            FieldKind::Scalar => scalar_value(&self.schema, self.text.trim()),
+            FieldKind::Object => {
+                let mut map = Map::new();
+                for child in &self.children {
+                    if child.custom_key && child.name.trim().is_empty() {
+                        return Err("entry needs a key (r to rename)".to_string());
+                    }
+                    if !child.included {
+                        if child.required {
+                            return Err(format!("{}: required", child.name));
+                        }
+                        continue;
+                    }
+                    let value = child
+                        .to_value()
+                        .map_err(|e| format!("{}: {}", child.name, e))?;
+                    if map.insert(child.name.trim().to_string(), value).is_some() {
+                        return Err(format!("duplicate key {}", child.name.trim()));
+                    }
+                }
+                Ok(Value::Object(map))
+            }
+            FieldKind::Array => self
+                .children
+                .iter()
+                .enumerate()
+                .map(|(i, item)| item.to_value().map_err(|e| format!("[{}]: {}", i, e)))
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+        }
+    }
+
+    /// Validation error for this field alone, for showing next to it.
+    pub fn error(&self) -> Option<String> {
+        if !self.included {
+            return None;
+        }
+        match self.kind() {
+            FieldKind::Scalar => scalar_value(&self.schema, self.text.trim()).err(),
+            _ => None,
+        }
+    }
+}
+
+fn scalar_value(schema: &Schema, text: &str) -> Result<Value, String> {
+    let type_str = schema
+        .type_str
+        .as_ref()
+        .and_then(|t| t.get(1))
+        .map(String::as_str);
+    match (schema.schema_type.as_deref(), type_str) {
+        (_, Some("string or numeric")) => {
+            Ok(number(text).unwrap_or_else(|| Value::String(text.to_string())))
+        }
+        (Some("string"), _) => {
+            if let Some(pattern) = &schema.pattern
+                && !matches_pattern(pattern, text)
+            {
+                return Err(format!("must match {}", pattern));
+            }
+            Ok(Value::String(text.to_string()))
+        }
+        (Some("number"), _) => number(text).ok_or_else(|| "expected a number".to_string()),
+        (Some("object") | Some("array"), _) => {
+            serde_json::from_str(text).map_err(|e| format!("expected JSON: {}", e))
+        }
+        // oneOf or untyped: JSON if it parses, otherwise a string.
+        _ if text.is_empty() => Err("value is empty".to_string()),
+        _ => Ok(serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string()))),
+    }
+}
+
+fn number(text: &str) -> Option<Value> {
+    if let Ok(n) = text.parse::<i64>() {
+        return Some(Value::from(n));
+    }
+    // Through serde so amounts like 0.1 keep their shortest representation.
+    match serde_json::from_str::<Value>(text) {
+        Ok(v @ Value::Number(_)) => Some(v),
+        _ => None,
+    }
+}
+
+/// Checks the `^[class]*$` / `^[class]+$` patterns openrpc.json uses; anything else passes.
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let Some(inner) = pattern.strip_prefix("^[") else {
+        return true;
+    };
+    let (class, allow_empty) = if let Some(class) = inner.strip_suffix("]*$") {
+        (class, true)
+    } else if let Some(class) = inner.strip_suffix("]+$") {
+        (class, false)
+    } else {
+        return true;
+    };
+    let class: Vec<char> = class.chars().collect();
+    let in_class = |c: char| {
+        let mut i = 0;
+        while i < class.len() {
+            if i + 2 < class.len() && class[i + 1] == '-' {
+                if (class[i]..=class[i + 2]).contains(&c) {
+                    return true;
+                }
+                i += 3;
+            } else {
+                if class[i] == c {
+                    return true;
+                }
+                i += 1;
+            }
+        }
+        false
+    };
+    (allow_empty || !text.is_empty()) && text.chars().all(in_class)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    Field,
+    /// "+ add item" under an array.
+    AddItem,
+    /// "+ add entry" under an object with free-form keys.
+    AddEntry,
+}
+
+pub struct Row {
+    pub path: Vec<usize>,
+    pub depth: usize,
+    pub kind: RowKind,
+}
+
+/// Per-parameter form for a method call, built from the openrpc.json param schemas.
+pub struct ArgForm {
+    pub fields: Vec<Field>,
+    pub selected: usize,
+    /// Editing the key of a free-form entry rather than its value.
+    pub editing_key: bool,
+    pub error: Option<String>,
+}
+
+impl ArgForm {
+    pub fn new(method: &RpcMethod) -> Self {
+        let fields = method
+            .params
+            .iter()
+            .map(|p| Field {
+                aliases: p.aliases.clone(),
+                ..Field::new(&p.name, &p.description, &p.schema, p.required)
+            })
+            .collect();
+        ArgForm {
+            fields,
+            selected: 0,
+            editing_key: false,
+            error: None,
+        }
+    }
+
+    pub fn rows(&self) -> Vec<Row> {
+        fn walk(fields: &[Field], path: &mut Vec<usize>, depth: usize, rows: &mut Vec<Row>) {
+            for (i, field) in fields.iter().enumerate() {
+                path.push(i);
+                rows.push(Row {
+                    path: path.clone(),
+                    depth,
+                    kind: RowKind::Field,
+                });
+                if field.included {
+                    walk(&field.children, path, depth + 1, rows);
+                    let add = match field.kind() {
+                        FieldKind::Array => Some(RowKind::AddItem),
+                        FieldKind::Object if field.schema.additional_schema().is_some() => {
+                            Some(RowKind::AddEntry)
+                        }
+                        _ => None,
+                    };
+                    if let Some(kind) = add {
+                        rows.push(Row {
+                            path: path.clone(),
+                            depth: depth + 1,
+                            kind,
+                        });
+                    }
+                }
+                path.pop();
+            }
+        }
+        let mut rows = Vec::new();
+        walk(&self.fields, &mut Vec::new(), 0, &mut rows);
+        rows
+    }
+
+    pub fn selected_row(&self) -> Option<Row> {
+        let mut rows = self.rows();
+        if rows.is_empty() {
+            return None;
+        }
+        let index = self.selected.min(rows.len() - 1);
+        Some(rows.swap_remove(index))
+    }
+
+    pub fn field(&self, path: &[usize]) -> Option<&Field> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.fields.get(*first)?, |field, &i| field.children.get(i))
+    }
+
+    pub fn field_mut(&mut self, path: &[usize]) -> Option<&mut Field> {
+        let (first, rest) = path.split_first()?;
+        rest.iter()
+            .try_fold(self.fields.get_mut(*first)?, |field, &i| {
+                field.children.get_mut(i)
+            })
+    }
+
+    /// Marks a field and all its parents as part of the request.
+    pub fn include(&mut self, path: &[usize]) {
+        for len in 1..=path.len() {
+            if let Some(field) = self.field_mut(&path[..len]) {
+                field.included = true;
+            }
+        }
+    }
+
+    /// The text being edited for the selected row, if it is a free-text field.
+    pub fn text_mut(&mut self) -> Option<&mut String> {
+        let row = self.selected_row()?;
+        if row.kind != RowKind::Field {
+            return None;
+        }
+        let editing_key = self.editing_key;
+        let field = self.field_mut(&row.path)?;
+        if editing_key && field.custom_key {
+            Some(&mut field.name)
+        } else if field.kind() == FieldKind::Scalar {
+            Some(&mut field.text)
+        } else {
+            None
+        }
+    }
+
+    /// Space/Enter on a non-text field: flips booleans and includes or omits optional
+    /// objects and arrays. Returns false if the row has nothing to toggle.
+    pub fn toggle(&mut self, path: &[usize]) -> bool {
+        let Some(field) = self.field_mut(path) else {
+            return false;
+        };
+        match field.kind() {
+            FieldKind::Boolean if !field.included => {
+                field.included = true;
+                field.text = "true".to_string();
+            }
+            FieldKind::Boolean => {
+                field.text = if field.text == "true" {
+                    "false"
+                } else {
+                    "true"
+                }
+                .to_string();
+            }
+            FieldKind::Object | FieldKind::Array if !field.included => field.included = true,
+            FieldKind::Object | FieldKind::Array if !field.required => field.included = false,
+            _ => return false,
+        }
+        if field.included {
+            self.include(path);
+        }
+        true
+    }
+
+    /// Appends an array item or free-form object entry under `path` and selects it.
+    pub fn add_child(&mut self, path: &[usize]) {
+        let Some(parent) = self.field_mut(path) else {
+            return;
+        };
+        let child = match parent.kind() {
+            FieldKind::Array => {
+                let items = parent.schema.items.as_deref().cloned().unwrap_or_default();
+                Field::new(&format!("[{}]", parent.children.len()), "", &items, true)
+            }
+            FieldKind::Object => {
+                let Some(schema) = parent.schema.additional_schema() else {
+                    return;
+                };
+                Field {
+                    custom_key: true,
+                    ..Field::new("", "", &schema, true)
+                }
+            }
+            _ => return,
+        };
+        let custom_key = child.custom_key;
+        parent.children.push(child);
+        let mut child_path = path.to_vec();
+        child_path.push(parent.children.len() - 1);
+        if let Some(index) = self.rows().iter().position(|r| r.path == child_path) {
+            self.selected = index;
+        }
+        self.editing_key = custom_key;
+    }
+
+    /// Removes an array item or free-form entry, or omits an optional field.
+    pub fn remove(&mut self, path: &[usize]) {
+        let Some((&last, parent_path)) = path.split_last() else {
+            return;
+        };
+        let parent_kind = self.field(parent_path).map(|p| p.kind());
+        let removable =
+            parent_kind == Some(FieldKind::Array) || self.field(path).is_some_and(|f| f.custom_key);
+        if removable && let Some(parent) = self.field_mut(parent_path) {
+            parent.children.remove(last);
+            if parent.kind() == FieldKind::Array {
+                for (i, item) in parent.children.iter_mut().enumerate() {
+                    item.name = format!("[{}]", i);
+                }
+            }
+        } else if let Some(field) = self.field_mut(path)
+            && !field.required
+        {
+            field.included = false;
+            field.text.clear();
+        }
+        let rows = self.rows().len();
+        self.selected = self.selected.min(rows.saturating_sub(1));
+    }
+
+    /// Builds the call params: a positional array when the included params form a
+    /// prefix of the signature, otherwise named params so skipped optionals keep
+    /// their node defaults.
+    pub fn build(&self) -> Result<Value, String> {
+        let mut values = Vec::new();
+        for field in &self.fields {
+            if !field.included {
+                if field.required {
+                    return Err(format!("{}: required", field.name));
+                }
+                values.push(None);
+                continue;
+            }
+            let value = field
+                .to_value()
+                .map_err(|e| format!("{}: {}", field.name, e))?;
+            values.push(Some(value));
+        }
+        while values.last().is_some_and(|v| v.is_none()) {
+            values.pop();
+        }
+        if values.iter().all(Option::is_some) {
+            return Ok(Value::Array(values.into_iter().flatten().collect()));
+        }
+        let named = self
+            .fields
+            .iter()
+            .zip(values)
+            .filter_map(|(field, value)| Some((field.name.clone(), value?)))
+            .collect();
+        Ok(Value::Object(named))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wallet_schema::{Availability, RpcParam};
+    use serde_json::json;
+
+    fn param(name: &str, schema_type: &str, required: bool) -> RpcParam {
+        RpcParam {
+            name: name.to_string(),
+            description: String::new(),
+            required,
+            schema_type: schema_type.to_string(),
+            schema: Schema {
+                schema_type: Some(schema_type.to_string()),
+                ..Schema::default()
+            },
+            aliases: Vec::new(),
+        }
+    }
+
+    /// One required param followed by two optionals.
+    fn form() -> ArgForm {
+        ArgForm::new(&RpcMethod {
+            name: "test".to_string(),
+            description: String::new(),
+            params: vec![
+                param("address", "string", true),
+                param("amount", "number", false),
+                param("comment", "string", false),
+            ],
+            result: None,
+            availability: Availability::Unknown,
+        })
+    }
+
+    fn set(form: &mut ArgForm, index: usize, text: &str) {
+        form.fields[index].text = text.to_string();
+        form.include(&[index]);
+    }
+
+    #[test]
+    fn trailing_optionals_are_trimmed_to_an_array() {
+        let mut form = form();
+        set(&mut form, 0, "bc1q");
+        assert_eq!(form.build().unwrap(), json!(["bc1q"]));
+        set(&mut form, 1, "0.1");
+        assert_eq!(form.build().unwrap(), json!(["bc1q", 0.1]));
+    }
+
+    #[test]
+    fn skipped_middle_optional_builds_named_params() {
+        let mut form = form();
+        set(&mut form, 0, "bc1q");
+        set(&mut form, 2, "rent");
+        assert_eq!(
+            form.build().unwrap(),
+            json!({"address": "bc1q", "comment": "rent"})
+        );
+    }
+
+    #[test]
+    fn missing_required_param_is_an_error() {
+        let mut form = form();
+        set(&mut form, 1, "0.1");
+        form.fields[0].included = false;
+        assert_eq!(form.build().unwrap_err(), "address: required");
+        // A required number left blank can't stand in for the param either.
+        set(&mut form, 0, "bc1q");
+        form.fields[1].required = true;
+        form.fields[1].text.clear();
+        assert_eq!(form.build().unwrap_err(), "amount: expected a number");
+    }
+
+    #[test]
+    fn hex_pattern() {
+        let hex = "^[0-9a-fA-F]*$";
+        assert!(matches_pattern(hex, ""));
+        assert!(matches_pattern(hex, "00ffAB19"));
+        assert!(!matches_pattern(hex, "0x00"));
+        assert!(!matches_pattern(hex, "abcg"));
+        assert!(!matches_pattern("^[0-9a-fA-F]+$", ""));
+    }
+}
@@ -0,0 +1,195 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Results larger than this (as JSON) aren't kept, so the history file stays small.
+pub const MAX_STORED_RESULT: usize = 64 * 1024;
+
+/// Methods whose arguments can hold passphrases, private keys or private descriptors.
+/// Their calls stay in the history for the session but are never written to disk.
+const SECRET_PARAMS: &[&str] = &[
+    "createwallet",
+    "deriveaddresses",
+    "descriptorprocesspsbt",
+    "encryptwallet",
+    "getdescriptorinfo",
+    "importdescriptors",
+    "importmulti",
+    "importprivkey",
+    "migratewallet",
+    "sethdseed",
+    "signmessagewithprivkey",
+    "signrawtransactionwithkey",
+    "walletpassphrase",
+    "walletpassphrasechange",
+];
+
+/// Methods whose results can hold private keys or private descriptors; the results are
+/// never written to disk.
+const SECRET_RESULTS: &[&str] = &["dumpprivkey", "gethdkeys", "listdescriptors"];
+
+/// Whether calls to `method` may carry secrets in their arguments.
+pub fn has_secret_params(method: &str) -> bool {
+    SECRET_PARAMS.contains(&method)
+}
+
+/// One call made from the RPC or Wallet tab.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CallRecord {
+    pub method: String,
+    pub params: Value,
+    #[serde(default)]
+    pub wallet: Option<String>,
+    /// Unix time the call was sent.
+    pub time: u64,
+    pub duration_ms: u64,
+    pub success: bool,
+    #[serde(default)]
+    pub error: Option<String>,
+    /// The result, unless it was larger than `MAX_STORED_RESULT`.
+    #[serde(default)]
+    pub result: Option<Value>,
+}
+
+impl CallRecord {
+    pub fn new(
+        method: String,
+        params: Value,
+        wallet: Option<String>,
+        time: u64,
+        duration_ms: u64,
+        result: &Result<Value, String>,
+    ) -> Self {
+        let (success, error, result) = match result {
+            Ok(value) => {
+                let small =
+                    serde_json::to_string(value).is_ok_and(|json| json.len() <= MAX_STORED_RESULT);
+                (true, None, small.then(|| value.clone()))
+            }
+            Err(e) => (false, Some(e.clone()), None),
+        };
+        CallRecord {
+            method,
+            params,
+            wallet,
+            time,
+            duration_ms,
+            success,
+            error,
+            result,
+        }
+    }
+
+    /// The record as written to the history file: nothing for calls whose arguments may
+    /// be secret, and no result when it may hold private keys.
+    pub fn persisted(&self) -> Option<CallRecord> {
+        if has_secret_params(&self.method) {
+            return None;
+        }
+        let mut record = self.clone();
+        if SECRET_RESULTS.contains(&self.method.as_str()) {
+            record.result = None;
+        }
+        Some(record)
+    }
+
+    pub fn favorite(&self) -> FavoriteCall {
+        FavoriteCall {
+            method: self.method.clone(),
+            params: self.params.clone(),
+            wallet: self.wallet.clone(),
+        }
+    }
+}
+
+/// A starred call; starred methods are listed first when searching the method list.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct FavoriteCall {
+    pub method: String,
+    pub params: Value,
+    #[serde(default)]
+    pub wallet: Option<String>,
+}
+
+/// A row of the history popup.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum HistoryRow {
+    Favorite(usize),
+    Call(usize),
+}
+
+#[derive(Default)]
+pub struct CallHistory {
+    /// Oldest first, as stored.
+    pub calls: Vec<CallRecord>,
+    pub favorites: Vec<FavoriteCall>,
+}
+
+impl CallHistory {
+    /// Favorites, then calls newest first, limited to methods `include` accepts (the
+    /// methods of the tab the popup was opened from).
+    pub fn rows(&self, include: impl Fn(&str) -> bool) -> Vec<HistoryRow> {
+        let favorites = self
+            .favorites
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| include(&f.method))
+            .map(|(i, _)| HistoryRow::Favorite(i));
+        let calls = self
+            .calls
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| include(&c.method))
+            .map(|(i, _)| HistoryRow::Call(i));
+        favorites.chain(calls).collect()
+    }
+
+    pub fn is_favorite(&self, call: &FavoriteCall) -> bool {
+        self.favorites.contains(call)
+    }
+
+    pub fn is_favorite_method(&self, method: &str) -> bool {
+        self.favorites.iter().any(|f| f.method == method)
+    }
+
+    /// Stars the call, or unstars it if it already was.
+    pub fn toggle_favorite(&mut self, call: FavoriteCall) {
+        if let Some(i) = self.favorites.iter().position(|f| *f == call) {
+            self.favorites.remove(i);
+        } else {
+            self.favorites.push(call);
+        }
+    }
+
+    /// The newest call matching `call` that kept its result.
+    pub fn last_result(&self, call: &FavoriteCall) -> Option<&CallRecord> {
+        self.calls
+            .iter()
+            .rev()
+            .find(|c| c.result.is_some() && c.favorite() == *call)
+    }
+}
+
+/// Params as text for the raw args prompt: positional values comma-separated, named
+/// ones as `name=value` (leading positional values of a named call come from `args`).
+pub fn args_text(params: &Value) -> String {
+    let compact = |v: &Value| serde_json::to_string(v).unwrap_or_default();
+    match params {
+        Value::Array(items) => items.iter().map(compact).collect::<Vec<_>>().join(", "),
+        Value::Object(map) => {
+            let positional = map
+                .get("args")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(compact);
+            let named = map
+                .iter()
+                .filter(|(k, _)| *k != "args")
+                .map(|(k, v)| format!("{}={}", k, compact(v)));
+            positional.chain(named).collect::<Vec<_>>().join(", ")
+        }
+        Value::Null => String::new(),
+        other => compact(other),
+    }
+}
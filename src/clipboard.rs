@@ -0,0 +1,25 @@
+//! Copying to the system clipboard through the terminal (OSC 52). The terminal
+//! does the work, so it also works over SSH, as long as the terminal allows it.
+
+use std::io::Write;
+
+use base64::Engine;
+
+/// The OSC 52 sequence that sets the clipboard to `text`, wrapped for tmux passthrough
+/// when running inside tmux.
+pub fn osc52_sequence(text: &str) -> String {
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+    let sequence = format!("\x1b]52;c;{}\x07", encoded);
+    if std::env::var_os("TMUX").is_some() {
+        format!("\x1bPtmux;{}\x1b\\", sequence.replace('\x1b', "\x1b\x1b"))
+    } else {
+        sequence
+    }
+}
+
+pub fn copy(text: &str) -> Result<(), String> {
+    let mut out = std::io::stdout();
+    out.write_all(osc52_sequence(text).as_bytes())
+        .and_then(|_| out.flush())
+        .map_err(|e| format!("clipboard: {}", e))
+}
@@ -0,0 +1,192 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FileMode {
+    Load,
+    Save,
+}
+
+pub struct FileEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
+
+/// What the owning tab should do after the picker consumed a key.
+pub enum PickerAction {
+    None,
+    Close,
+    EditName,
+    Load(PathBuf),
+    LoadMany(Vec<PathBuf>),
+    Save(PathBuf),
+}
+
+pub struct FilePicker {
+    pub open: bool,
+    pub mode: FileMode,
+    pub dir: PathBuf,
+    pub entries: Vec<FileEntry>,
+    pub selected: usize,
+    pub save_name: String,
+    /// Enables Space to mark several files for loading at once.
+    pub multi_select: bool,
+    pub marked: BTreeSet<PathBuf>,
+    /// Files with this extension are highlighted; `.` hides all other files.
+    pub highlight_ext: Option<&'static str>,
+    pub filter_only: bool,
+    /// An existing file a save would replace; `y` writes it, any other key cancels.
+    pub confirm_overwrite: Option<PathBuf>,
+}
+
+impl FilePicker {
+    pub fn new(save_name: &str) -> Self {
+        FilePicker {
+            open: false,
+            mode: FileMode::Load,
+            dir: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            entries: Vec::new(),
+            selected: 0,
+            save_name: save_name.to_string(),
+            multi_select: false,
+            marked: BTreeSet::new(),
+            highlight_ext: None,
+            filter_only: false,
+            confirm_overwrite: None,
+        }
+    }
+
+    /// Whether `path` has the highlighted extension.
+    pub fn is_highlighted(&self, path: &Path) -> bool {
+        match (self.highlight_ext, path.extension()) {
+            (Some(want), Some(ext)) => ext.to_string_lossy().eq_ignore_ascii_case(want),
+            _ => false,
+        }
+    }
+
+    pub fn open(&mut self, mode: FileMode) {
+        self.mode = mode;
+        self.open = true;
+        self.marked.clear();
+        self.confirm_overwrite = None;
+        self.refresh();
+    }
+
+    pub fn refresh(&mut self) {
+        let mut entries = vec![FileEntry {
+            name: "..".to_string(),
+            path: self
+                .dir
+                .parent()
+                .map(PathBuf::from)
+                .unwrap_or_else(|| self.dir.clone()),
+            is_dir: true,
+        }];
+
+        if let Ok(read_dir) = std::fs::read_dir(&self.dir) {
+            for entry in read_dir.flatten() {
+                let path = entry.path();
+                let is_dir = path.is_dir();
+                if self.filter_only && !is_dir && !self.is_highlighted(&path) {
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().to_string();
+                entries.push(FileEntry { name, path, is_dir });
+            }
+        }
+        entries.sort_by(|a, b| {
+            b.is_dir
+                .cmp(&a.is_dir)
+                .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+        });
+
+        self.entries = entries;
+        if self.entries.is_empty() {
+            self.selected = 0;
+        } else {
+            self.selected = self.selected.min(self.entries.len() - 1);
+        }
+    }
+
+    /// Replaces the extension of the pending save name, e.g. when the output format changes.
+    pub fn set_extension(&mut self, ext: &str) {
+        let stem = Path::new(self.save_name.trim())
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        self.save_name = format!("{stem}.{ext}");
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> PickerAction {
+        if let Some(path) = self.confirm_overwrite.take() {
+            return match key.code {
+                KeyCode::Char('y') => PickerAction::Save(path),
+                _ => PickerAction::None,
+            };
+        }
+        match key.code {
+            KeyCode::Esc => {
+                self.open = false;
+                PickerAction::Close
+            }
+            KeyCode::Down | KeyCode::Char('j') if !self.entries.is_empty() => {
+                self.selected = (self.selected + 1).min(self.entries.len() - 1);
+                PickerAction::None
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+                PickerAction::None
+            }
+            KeyCode::Char(' ') if self.multi_select && self.mode == FileMode::Load => {
+                if let Some(entry) = self.entries.get(self.selected)
+                    && !entry.is_dir
+                    && !self.marked.remove(&entry.path)
+                {
+                    self.marked.insert(entry.path.clone());
+                }
+                self.selected = (self.selected + 1).min(self.entries.len().saturating_sub(1));
+                PickerAction::None
+            }
+            KeyCode::Char('.') if self.highlight_ext.is_some() => {
+                self.filter_only = !self.filter_only;
+                self.refresh();
+                PickerAction::None
+            }
+            KeyCode::Char('e') if self.mode == FileMode::Save => PickerAction::EditName,
+            KeyCode::Char('w') if self.mode == FileMode::Save => {
+                self.save(self.dir.join(self.save_name.trim()))
+            }
+            KeyCode::Enter => {
+                let Some(entry) = self.entries.get(self.selected) else {
+                    return PickerAction::None;
+                };
+                if entry.is_dir {
+                    self.dir = entry.path.clone();
+                    self.selected = 0;
+                    self.refresh();
+                    PickerAction::None
+                } else if self.mode == FileMode::Load && !self.marked.is_empty() {
+                    PickerAction::LoadMany(std::mem::take(&mut self.marked).into_iter().collect())
+                } else if self.mode == FileMode::Load {
+                    PickerAction::Load(entry.path.clone())
+                } else {
+                    self.save(entry.path.clone())
+                }
+            }
+            _ => PickerAction::None,
+        }
+    }
+
+    /// Saves to `path`, asking first if that would replace an existing file.
+    fn save(&mut self, path: PathBuf) -> PickerAction {
+        if path.exists() {
+            self.confirm_overwrite = Some(path);
+            PickerAction::None
+        } else {
+            PickerAction::Save(path)
+        }
+    }
+}
@@ -39,6 +39,10 @@ pub fn fmt_bytes(n: u64) -> String {
     }
 }
 
+pub fn fmt_rate(bytes_per_sec: f64) -> String {
+    format!("{}/s", fmt_bytes(bytes_per_sec.max(0.0).round() as u64))
+}
+
 pub fn fmt_difficulty(d: f64) -> String {
     const E: f64 = 1e18;
     const P: f64 = 1e15;
@@ -101,13 +105,65 @@ pub fn fmt_duration(secs: u64) -> String {
 }
 
 pub fn fmt_relative_time(unix: u64) -> String {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap_or_default()
-        .as_secs();
+    let now = unix_now();
     if now > unix {
         format!("{} ago", fmt_duration(now - unix))
     } else {
         "just now".to_string()
     }
 }
+
+pub fn fmt_unix_time(unix: u64) -> String {
+    // Civil-from-days (Howard Hinnant), UTC.
+    let days = (unix / 86_400) as i64;
+    let secs = unix % 86_400;
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+const SERVICE_FLAGS: [(u64, &str); 6] = [
+    (1 << 0, "NETWORK"),
+    (1 << 2, "BLOOM"),
+    (1 << 3, "WITNESS"),
+    (1 << 6, "COMPACT_FILTERS"),
+    (1 << 10, "NETWORK_LIMITED"),
+    (1 << 11, "P2P_V2"),
+];
+
+pub fn service_flag_names(services: u64) -> Vec<&'static str> {
+    SERVICE_FLAGS
+        .iter()
+        .filter(|(bit, _)| services & bit != 0)
+        .map(|(_, name)| *name)
+        .collect()
+}
+
+pub fn service_flag_bit(name: &str) -> Option<u64> {
+    SERVICE_FLAGS
+        .iter()
+        .find(|(_, n)| *n == name)
+        .map(|(bit, _)| *bit)
+}
@@ -0,0 +1,745 @@
+//! A small subset of jq for filtering RPC results: paths (`.tx[0].vout`, `.["key"]`),
+//! iteration (`.[]`), pipes, `,`, comparisons, `and`/`or`, array construction and the
+//! builtins `select`, `map`, `length`, `keys`, `has`, `not`, `first` and `last`.
+//! Variables (`$name`) can be bound by the caller but not defined in the filter.
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+/// Parses `filter` and runs it on `input`, returning every output in order.
+pub fn apply(filter: &str, input: &Value) -> Result<Vec<Value>, String> {
+    apply_with_vars(filter, input, &BTreeMap::new())
+}
+
+/// Like `apply`, with `$name` in the filter standing for `vars[name]`.
+pub fn apply_with_vars(
+    filter: &str,
+    input: &Value,
+    vars: &BTreeMap<String, Value>,
+) -> Result<Vec<Value>, String> {
+    let tokens = tokenize(filter)?;
+    let mut parser = Parser {
+        tokens,
+        pos: 0,
+        vars,
+    };
+    let expr = parser.pipe()?;
+    if let Some(token) = parser.peek() {
+        return Err(format!("unexpected {}", token.describe()));
+    }
+    eval(&expr, input)
+}
+
+#[derive(Clone, PartialEq)]
+enum Token {
+    /// `.` on its own, e.g. in `.[]` or `.`.
+    Dot,
+    /// `.name`
+    Field(String),
+    Str(String),
+    Num(f64),
+    Ident(String),
+    /// `$name`
+    Var(String),
+    Punct(&'static str),
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Dot => "'.'".to_string(),
+            Token::Field(name) => format!("'.{}'", name),
+            Token::Str(s) => format!("\"{}\"", s),
+            Token::Num(n) => format!("'{}'", n),
+            Token::Ident(name) => format!("'{}'", name),
+            Token::Var(name) => format!("'${}'", name),
+            Token::Punct(p) => format!("'{}'", p),
+        }
+    }
+}
+
+const PUNCTS: [&str; 13] = [
+    "==", "!=", "<=", ">=", "<", ">", "|", ",", "[", "]", "(", ")", "?",
+];
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let is_ident_start = |c: char| c.is_ascii_alphabetic() || c == '_';
+    let is_ident = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '.' {
+            i += 1;
+            if i < chars.len() && is_ident_start(chars[i]) {
+                let start = i;
+                while i < chars.len() && is_ident(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Field(chars[start..i].iter().collect()));
+            } else if i < chars.len() && chars[i] == '"' {
+                let (s, next) = read_string(&chars, i)?;
+                tokens.push(Token::Field(s));
+                i = next;
+            } else {
+                tokens.push(Token::Dot);
+            }
+        } else if c == '"' {
+            let (s, next) = read_string(&chars, i)?;
+            tokens.push(Token::Str(s));
+            i = next;
+        } else if c.is_ascii_digit() {
+            let start = i;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text.parse().map_err(|_| format!("bad number '{}'", text))?;
+            tokens.push(Token::Num(n));
+        } else if c == '$' && chars.get(i + 1).is_some_and(|&n| is_ident_start(n)) {
+            i += 1;
+            let start = i;
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Var(chars[start..i].iter().collect()));
+        } else if is_ident_start(c) {
+            let start = i;
+            while i < chars.len() && is_ident(chars[i]) {
+                i += 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else if let Some(p) = PUNCTS.iter().find(|p| {
+            p.chars()
+                .enumerate()
+                .all(|(k, pc)| chars.get(i + k) == Some(&pc))
+        }) {
+            tokens.push(Token::Punct(p));
+            i += p.len();
+        } else if c == '-' && ends_value(tokens.last()) {
+            return Err("subtraction isn't supported".to_string());
+        } else if c == '-' && chars.get(i + 1).is_some_and(|d| d.is_ascii_digit()) {
+            // Only negative literals, e.g. `.[-1]`; there is no arithmetic.
+            let start = i;
+            i += 1;
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let n = text.parse().map_err(|_| format!("bad number '{}'", text))?;
+            tokens.push(Token::Num(n));
+        } else {
+            return Err(format!("unexpected character '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Whether a `-` after `token` would subtract rather than start a negative number.
+fn ends_value(token: Option<&Token>) -> bool {
+    match token {
+        None => false,
+        Some(Token::Punct(p)) => matches!(*p, "]" | ")" | "?"),
+        Some(_) => true,
+    }
+}
+
+/// Reads a JSON string literal starting at the opening quote.
+fn read_string(chars: &[char], start: usize) -> Result<(String, usize), String> {
+    let mut i = start + 1;
+    let mut escaped = false;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if !escaped => escaped = true,
+            '"' if !escaped => {
+                let literal: String = chars[start..=i].iter().collect();
+                let s = serde_json::from_str(&literal).map_err(|e| format!("bad string: {}", e))?;
+                return Ok((s, i + 1));
+            }
+            _ => escaped = false,
+        }
+        i += 1;
+    }
+    Err("unterminated string".to_string())
+}
+
+enum Expr {
+    Identity,
+    Literal(Value),
+    /// `base.name` / `base["name"]` / `base[0]`; the index is evaluated against the input.
+    Index(Box<Expr>, Box<Expr>),
+    /// `base[]`
+    Iterate(Box<Expr>),
+    /// `expr?`: errors produce no output.
+    Try(Box<Expr>),
+    /// `[expr]`, or `[]`.
+    Array(Option<Box<Expr>>),
+    Pipe(Box<Expr>, Box<Expr>),
+    Comma(Box<Expr>, Box<Expr>),
+    Compare(&'static str, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Call(String, Option<Box<Expr>>),
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    vars: &'a BTreeMap<String, Value>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn eat(&mut self, punct: &'static str) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn eat_ident(&mut self, name: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Ident(n)) if n == name) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, punct: &'static str) -> Result<(), String> {
+        if self.eat(punct) {
+            Ok(())
+        } else {
+            Err(match self.peek() {
+                Some(token) => format!("expected '{}', found {}", punct, token.describe()),
+                None => format!("expected '{}'", punct),
+            })
+        }
+    }
+
+    fn pipe(&mut self) -> Result<Expr, String> {
+        let lhs = self.comma()?;
+        if self.eat("|") {
+            Ok(Expr::Pipe(Box::new(lhs), Box::new(self.pipe()?)))
+        } else {
+            Ok(lhs)
+        }
+    }
+
+    fn comma(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.or()?;
+        while self.eat(",") {
+            lhs = Expr::Comma(Box::new(lhs), Box::new(self.or()?));
+        }
+        Ok(lhs)
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and()?;
+        while self.eat_ident("or") {
+            lhs = Expr::Or(Box::new(lhs), Box::new(self.and()?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.compare()?;
+        while self.eat_ident("and") {
+            lhs = Expr::And(Box::new(lhs), Box::new(self.compare()?));
+        }
+        Ok(lhs)
+    }
+
+    fn compare(&mut self) -> Result<Expr, String> {
+        let lhs = self.postfix()?;
+        for op in ["==", "!=", "<=", ">=", "<", ">"] {
+            if self.eat(op) {
+                let rhs = self.postfix()?;
+                return Ok(Expr::Compare(op, Box::new(lhs), Box::new(rhs)));
+            }
+        }
+        Ok(lhs)
+    }
+
+    fn postfix(&mut self) -> Result<Expr, String> {
+        let mut expr = self.primary()?;
+        loop {
+            match self.peek() {
+                Some(Token::Field(name)) => {
+                    let key = Expr::Literal(Value::String(name.clone()));
+                    self.pos += 1;
+                    expr = Expr::Index(Box::new(expr), Box::new(key));
+                }
+                Some(Token::Dot) if self.tokens.get(self.pos + 1) == Some(&Token::Punct("[")) => {
+                    // `.a.[0]` reads like `.a[0]`.
+                    self.pos += 1;
+                }
+                Some(Token::Punct("[")) => {
+                    self.pos += 1;
+                    if self.eat("]") {
+                        expr = Expr::Iterate(Box::new(expr));
+                    } else {
+                        let index = self.pipe()?;
+                        self.expect("]")?;
+                        expr = Expr::Index(Box::new(expr), Box::new(index));
+                    }
+                }
+                Some(Token::Punct("?")) => {
+                    self.pos += 1;
+                    expr = Expr::Try(Box::new(expr));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        let Some(token) = self.peek().cloned() else {
+            return Err("unexpected end of filter".to_string());
+        };
+        self.pos += 1;
+        match token {
+            Token::Dot => Ok(Expr::Identity),
+            Token::Field(name) => Ok(Expr::Index(
+                Box::new(Expr::Identity),
+                Box::new(Expr::Literal(Value::String(name))),
+            )),
+            Token::Str(s) => Ok(Expr::Literal(Value::String(s))),
+            Token::Num(n) => Ok(Expr::Literal(number(n))),
+            Token::Var(name) => match self.vars.get(&name) {
+                Some(value) => Ok(Expr::Literal(value.clone())),
+                None => Err(format!("${} is not defined", name)),
+            },
+            Token::Punct("(") => {
+                let inner = self.pipe()?;
+                self.expect(")")?;
+                Ok(inner)
+            }
+            Token::Punct("[") => {
+                if self.eat("]") {
+                    return Ok(Expr::Array(None));
+                }
+                let inner = self.pipe()?;
+                self.expect("]")?;
+                Ok(Expr::Array(Some(Box::new(inner))))
+            }
+            Token::Ident(name) => match name.as_str() {
+                "true" => Ok(Expr::Literal(Value::Bool(true))),
+                "false" => Ok(Expr::Literal(Value::Bool(false))),
+                "null" => Ok(Expr::Literal(Value::Null)),
+                "length" | "keys" | "not" | "first" | "last" => Ok(Expr::Call(name, None)),
+                "select" | "map" | "has" => {
+                    self.expect("(")?;
+                    let arg = self.pipe()?;
+                    self.expect(")")?;
+                    Ok(Expr::Call(name, Some(Box::new(arg))))
+                }
+                _ => Err(format!("unknown function '{}'", name)),
+            },
+            other => Err(format!("unexpected {}", other.describe())),
+        }
+    }
+}
+
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        Value::from(n as i64)
+    } else {
+        serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn eval(expr: &Expr, input: &Value) -> Result<Vec<Value>, String> {
+    Ok(match expr {
+        Expr::Identity => vec![input.clone()],
+        Expr::Literal(v) => vec![v.clone()],
+        Expr::Index(base, index) => {
+            let mut out = Vec::new();
+            for key in eval(index, input)? {
+                for value in eval(base, input)? {
+                    out.push(index_value(&value, &key)?);
+                }
+            }
+            out
+        }
+        Expr::Iterate(base) => {
+            let mut out = Vec::new();
+            for value in eval(base, input)? {
+                match value {
+                    Value::Array(items) => out.extend(items),
+                    Value::Object(map) => out.extend(map.into_iter().map(|(_, v)| v)),
+                    other => return Err(format!("Cannot iterate over {}", type_name(&other))),
+                }
+            }
+            out
+        }
+        Expr::Try(inner) => eval(inner, input).unwrap_or_default(),
+        Expr::Array(None) => vec![Value::Array(Vec::new())],
+        Expr::Array(Some(inner)) => vec![Value::Array(eval(inner, input)?)],
+        Expr::Pipe(lhs, rhs) => {
+            let mut out = Vec::new();
+            for value in eval(lhs, input)? {
+                out.extend(eval(rhs, &value)?);
+            }
+            out
+        }
+        Expr::Comma(lhs, rhs) => {
+            let mut out = eval(lhs, input)?;
+            out.extend(eval(rhs, input)?);
+            out
+        }
+        Expr::Compare(op, lhs, rhs) => {
+            let mut out = Vec::new();
+            for r in eval(rhs, input)? {
+                for l in eval(lhs, input)? {
+                    let ord = compare(&l, &r);
+                    out.push(Value::Bool(match *op {
+                        "==" => ord == Ordering::Equal,
+                        "!=" => ord != Ordering::Equal,
+                        "<" => ord == Ordering::Less,
+                        "<=" => ord != Ordering::Greater,
+                        ">" => ord == Ordering::Greater,
+                        _ => ord != Ordering::Less,
+                    }));
+                }
+            }
+            out
+        }
+        Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => {
+            let is_and = matches!(expr, Expr::And(..));
+            let mut out = Vec::new();
+            for l in eval(lhs, input)? {
+                if truthy(&l) != is_and {
+                    out.push(Value::Bool(!is_and));
+                    continue;
+                }
+                for r in eval(rhs, input)? {
+                    out.push(Value::Bool(truthy(&r)));
+                }
+            }
+            out
+        }
+        Expr::Call(name, arg) => call(name, arg.as_deref(), input)?,
+    })
+}
+
+fn call(name: &str, arg: Option<&Expr>, input: &Value) -> Result<Vec<Value>, String> {
+    let arg = || arg.ok_or_else(|| format!("{} needs an argument", name));
+    Ok(match name {
+        "length" => vec![match input {
+            Value::Null => Value::from(0),
+            Value::Bool(_) => return Err("boolean has no length".to_string()),
+            Value::Number(n) => number(n.as_f64().unwrap_or(0.0).abs()),
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+        }],
+        "keys" => vec![match input {
+            Value::Object(map) => Value::Array(map.keys().cloned().map(Value::String).collect()),
+            Value::Array(items) => Value::Array((0..items.len()).map(Value::from).collect()),
+            other => return Err(format!("{} has no keys", type_name(other))),
+        }],
+        "not" => vec![Value::Bool(!truthy(input))],
+        "first" => vec![index_value(input, &Value::from(0))?],
+        "last" => vec![index_value(input, &Value::from(-1))?],
+        "select" => eval(arg()?, input)?
+            .iter()
+            .filter(|v| truthy(v))
+            .map(|_| input.clone())
+            .collect(),
+        "map" => {
+            let items: Vec<Value> = match input {
+                Value::Array(items) => items.clone(),
+                Value::Object(map) => map.values().cloned().collect(),
+                other => return Err(format!("Cannot iterate over {}", type_name(other))),
+            };
+            let mut out = Vec::new();
+            for item in &items {
+                out.extend(eval(arg()?, item)?);
+            }
+            vec![Value::Array(out)]
+        }
+        "has" => {
+            let mut out = Vec::new();
+            for key in eval(arg()?, input)? {
+                out.push(Value::Bool(match (input, &key) {
+                    (Value::Object(map), Value::String(k)) => map.contains_key(k),
+                    (Value::Array(items), Value::Number(n)) => n
+                        .as_f64()
+                        .is_some_and(|i| i >= 0.0 && (i as usize) < items.len()),
+                    _ => {
+                        return Err(format!(
+                            "Cannot check whether {} has a {} key",
+                            type_name(input),
+                            type_name(&key)
+                        ));
+                    }
+                }));
+            }
+            out
+        }
+        _ => return Err(format!("unknown function '{}'", name)),
+    })
+}
+
+fn index_value(value: &Value, key: &Value) -> Result<Value, String> {
+    match (value, key) {
+        (Value::Null, Value::String(_) | Value::Number(_)) => Ok(Value::Null),
+        (Value::Object(map), Value::String(k)) => Ok(map.get(k).cloned().unwrap_or(Value::Null)),
+        (Value::Array(items), Value::Number(n)) => {
+            let i = n.as_f64().unwrap_or(0.0) as i64;
+            let i = if i < 0 { items.len() as i64 + i } else { i };
+            Ok(usize::try_from(i)
+                .ok()
+                .and_then(|i| items.get(i))
+                .cloned()
+                .unwrap_or(Value::Null))
+        }
+        (Value::String(_) | Value::Number(_) | Value::Bool(_), _)
+        | (Value::Object(_), Value::Number(_))
+        | (Value::Array(_), Value::String(_)) => Err(format!(
+            "Cannot index {} with {}",
+            type_name(value),
+            match key {
+                Value::String(s) => format!("\"{}\"", s),
+                other => type_name(other).to_string(),
+            }
+        )),
+        _ => Err(format!(
+            "Cannot index {} with {}",
+            type_name(value),
+            type_name(key)
+        )),
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// jq's ordering: null < false < true < numbers < strings < arrays < objects.
+fn compare(a: &Value, b: &Value) -> Ordering {
+    fn rank(v: &Value) -> u8 {
+        match v {
+            Value::Null => 0,
+            Value::Bool(false) => 1,
+            Value::Bool(true) => 2,
+            Value::Number(_) => 3,
+            Value::String(_) => 4,
+            Value::Array(_) => 5,
+            Value::Object(_) => 6,
+        }
+    }
+    match (a, b) {
+        (Value::Number(x), Value::Number(y)) => {
+            let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+            x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+        }
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        (Value::Array(x), Value::Array(y)) => x
+            .iter()
+            .zip(y)
+            .map(|(x, y)| compare(x, y))
+            .find(|o| o.is_ne())
+            .unwrap_or(x.len().cmp(&y.len())),
+        (Value::Object(x), Value::Object(y)) => {
+            let keys = x.keys().cmp(y.keys());
+            if keys.is_ne() {
+                return keys;
+            }
+            x.values()
+                .zip(y.values())
+                .map(|(x, y)| compare(x, y))
+                .find(|o| o.is_ne())
+                .unwrap_or(Ordering::Equal)
+        }
+        _ => rank(a).cmp(&rank(b)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn run(filter: &str, input: Value) -> Vec<Value> {
+        apply(filter, &input).unwrap_or_else(|e| panic!("{}: {}", filter, e))
+    }
+
+    fn error(filter: &str, input: Value) -> String {
+        match apply(filter, &input) {
+            Ok(out) => panic!("{} gave {:?}", filter, out),
+            Err(e) => e,
+        }
+    }
+
+    #[test]
+    fn paths() {
+        let block = json!({"tx": [{"txid": "a", "vout": [{"n": 0}, {"n": 1}]}], "my key": 5});
+        assert_eq!(run(".", json!(1)), vec![json!(1)]);
+        assert_eq!(run(".tx[0].txid", block.clone()), vec![json!("a")]);
+        assert_eq!(run(".tx[0].vout[1].n", block.clone()), vec![json!(1)]);
+        assert_eq!(run(".tx.[0].txid", block.clone()), vec![json!("a")]);
+        assert_eq!(run(r#".["my key"]"#, block.clone()), vec![json!(5)]);
+        assert_eq!(run(r#"."my key""#, block.clone()), vec![json!(5)]);
+        assert_eq!(run(".missing.deeper", block.clone()), vec![Value::Null]);
+        assert_eq!(run(".tx[5]", block), vec![Value::Null]);
+    }
+
+    #[test]
+    fn iteration_and_negative_index() {
+        let input = json!([1, 2, 3]);
+        assert_eq!(
+            run(".[]", input.clone()),
+            vec![json!(1), json!(2), json!(3)]
+        );
+        assert_eq!(run(".[-1]", input.clone()), vec![json!(3)]);
+        assert_eq!(run(".[-3]", input.clone()), vec![json!(1)]);
+        assert_eq!(run(".[-4]", input.clone()), vec![Value::Null]);
+        assert_eq!(run("first, last", input.clone()), vec![json!(1), json!(3)]);
+        assert_eq!(
+            run("[.[] | . > 1]", input),
+            vec![json!([false, true, true])]
+        );
+        assert_eq!(
+            run(".[]", json!({"a": 1, "b": 2})),
+            vec![json!(1), json!(2)]
+        );
+    }
+
+    #[test]
+    fn builtins() {
+        let utxos = json!([{"amount": 0.5}, {"amount": 0.001}, {"amount": 2}]);
+        assert_eq!(
+            run("map(select(.amount >= 0.01)) | length", utxos.clone()),
+            vec![json!(2)]
+        );
+        assert_eq!(
+            run("map(.amount)", utxos.clone()),
+            vec![json!([0.5, 0.001, 2])]
+        );
+        assert_eq!(
+            run(".[] | select(.amount > 1)", utxos),
+            vec![json!({"amount": 2})]
+        );
+        let obj = json!({"b": 1, "a": null});
+        assert_eq!(run("keys", obj.clone()), vec![json!(["a", "b"])]);
+        assert_eq!(
+            run(r#"has("a"), has("c")"#, obj),
+            vec![json!(true), json!(false)]
+        );
+        assert_eq!(
+            run("has(1), has(2)", json!([0, 0])),
+            vec![json!(true), json!(false)]
+        );
+        assert_eq!(run("length", json!("héllo")), vec![json!(5)]);
+        assert_eq!(run("length", Value::Null), vec![json!(0)]);
+        assert_eq!(run("null | not", Value::Null), vec![json!(true)]);
+        assert_eq!(run(".a? , 1", json!([1])), vec![json!(1)]);
+    }
+
+    #[test]
+    fn comparison_ordering() {
+        // null < false < true < numbers < strings < arrays < objects
+        let ordered = [
+            json!(null),
+            json!(false),
+            json!(true),
+            json!(-1),
+            json!(2.5),
+            json!("a"),
+            json!("b"),
+            json!([]),
+            json!([1]),
+            json!({}),
+            json!({"a": 1}),
+        ];
+        for pair in ordered.windows(2) {
+            let input = json!(pair);
+            assert_eq!(
+                run(".[0] < .[1]", input.clone()),
+                vec![json!(true)],
+                "{}",
+                input
+            );
+            assert_eq!(
+                run(".[0] >= .[1]", input),
+                vec![json!(false)],
+                "{}",
+                pair[0]
+            );
+        }
+        assert_eq!(
+            run("1 == 1.0, 1 != 2", Value::Null),
+            vec![json!(true), json!(true)]
+        );
+        assert_eq!(
+            run("true and null, false or 1", Value::Null),
+            vec![json!(false), json!(true)]
+        );
+    }
+
+    #[test]
+    fn variables() {
+        let vars = BTreeMap::from([("min".to_string(), json!(2))]);
+        let out = apply_with_vars(".[] | select(. >= $min)", &json!([1, 2, 3]), &vars).unwrap();
+        assert_eq!(out, vec![json!(2), json!(3)]);
+        assert_eq!(error("$nope", Value::Null), "$nope is not defined");
+    }
+
+    #[test]
+    fn error_messages() {
+        assert_eq!(error(".a", json!([1])), r#"Cannot index array with "a""#);
+        assert_eq!(
+            error(".[0]", json!({"a": 1})),
+            "Cannot index object with number"
+        );
+        assert_eq!(error(".[]", json!(1)), "Cannot iterate over number");
+        assert_eq!(error("keys", json!(true)), "boolean has no keys");
+        assert_eq!(
+            error("frobnicate", Value::Null),
+            "unknown function 'frobnicate'"
+        );
+        assert_eq!(error(".a)", Value::Null), "unexpected ')'");
+        assert_eq!(error("select(.a", Value::Null), "expected ')'");
+        assert_eq!(error(".[0", Value::Null), "expected ']'");
+        assert_eq!(error("", Value::Null), "unexpected end of filter");
+        assert_eq!(error(r#"."abc"#, Value::Null), "unterminated string");
+        assert_eq!(error(".a & .b", Value::Null), "unexpected character '&'");
+        assert_eq!(
+            error(r#"has("a")"#, json!([1])),
+            "Cannot check whether array has a string key"
+        );
+    }
+
+    #[test]
+    fn minus_after_a_value_is_not_a_negative_literal() {
+        assert_eq!(error(".[0] -1", json!([5])), "subtraction isn't supported");
+        assert_eq!(error("(.a) - 1", json!({})), "subtraction isn't supported");
+        assert_eq!(error(".a -1", json!({})), "subtraction isn't supported");
+        assert_eq!(error("1 -1", Value::Null), "subtraction isn't supported");
+        assert_eq!(run(".[0] == -1", json!([-1])), vec![json!(true)]);
+        assert_eq!(run("[.[-1], -2]", json!([7])), vec![json!([7, -2])]);
+    }
+}
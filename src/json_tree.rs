@@ -0,0 +1,500 @@
+use std::collections::{HashMap, HashSet};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde_json::Value;
+
+use crate::result_view::{abbreviate_hash, annotation, resolve};
+use crate::wallet_schema::Schema;
+
+/// Containers this shallow (the root is depth 0) start expanded...
+const OPEN_DEPTH: usize = 3;
+/// ...unless they have more children than this.
+const OPEN_MAX_LEN: usize = 50;
+/// Large arrays show this many children at a time, so a `getblock` with thousands of
+/// transactions only builds rows for what was asked for.
+pub const PAGE: usize = 200;
+const MAX_MATCHES: usize = 10_000;
+
+/// What the owning pane should do after the tree consumed a key.
+pub enum TreeAction {
+    None,
+    /// The key isn't a tree key.
+    Ignored,
+    Copy(String),
+    /// Write the selected (`s`) or whole (`S`) value to a file.
+    Save(Export),
+}
+
+/// A value on its way to a file.
+pub struct Export {
+    pub text: String,
+    /// `hex` for hex strings, `txt` for other strings, `json` otherwise.
+    pub extension: &'static str,
+    /// The selected node's key or index, for the file name; `None` for the whole value.
+    pub label: Option<String>,
+}
+
+/// Strings without quotes, the rest as pretty JSON.
+fn plain_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => serde_json::to_string_pretty(other).unwrap_or_else(|_| other.to_string()),
+    }
+}
+
+fn export(value: &Value, label: Option<String>) -> Export {
+    let is_hex = |s: &str| {
+        !s.is_empty() && s.len().is_multiple_of(2) && s.bytes().all(|b| b.is_ascii_hexdigit())
+    };
+    let extension = match value {
+        Value::String(s) if is_hex(s) => "hex",
+        Value::String(_) => "txt",
+        _ => "json",
+    };
+    Export {
+        text: plain_text(value) + "\n",
+        extension,
+        label,
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum RowKind {
+    Scalar,
+    Object(usize),
+    Array(usize),
+    /// Placeholder for the children of a large array that aren't shown yet.
+    More(usize),
+}
+
+pub struct TreeRow {
+    pub depth: usize,
+    /// JSON pointer of the node, e.g. `/vout/3/scriptPubKey`.
+    pub pointer: String,
+    /// Object key or `[i]`; empty for the root.
+    pub label: String,
+    /// Readable path, e.g. `vout[3].scriptPubKey.address`.
+    pub path: String,
+    pub kind: RowKind,
+    pub open: bool,
+    /// The value as JSON, for scalars.
+    pub text: String,
+    /// `text` with a long hash shortened.
+    pub short: Option<String>,
+    pub annotation: Option<String>,
+    pub description: Option<String>,
+}
+
+/// A collapsible view of a JSON value, shared by every result pane. Rows are only built
+/// for expanded nodes; what the user expanded or collapsed survives `set_value`.
+pub struct JsonTree {
+    value: Value,
+    schema: Option<Schema>,
+    /// Expand/collapse choices that override the defaults, by pointer.
+    toggled: HashMap<String, bool>,
+    /// Children shown so far for arrays longer than `PAGE`, by pointer.
+    shown: HashMap<String, usize>,
+    rows: Vec<TreeRow>,
+    pub selected: usize,
+    /// Shortened hashes and unit annotations; off shows the plain JSON.
+    pub annotations: bool,
+    matches: Vec<String>,
+    /// `matches` again, for the per-row lookup while drawing.
+    match_set: HashSet<String>,
+    pub match_index: usize,
+    /// Feedback for the last copy, shown until the next key.
+    pub notice: Option<String>,
+}
+
+impl JsonTree {
+    pub fn new(value: Value, schema: Option<&Schema>) -> Self {
+        let mut tree = JsonTree {
+            value,
+            schema: schema.cloned(),
+            toggled: HashMap::new(),
+            shown: HashMap::new(),
+            rows: Vec::new(),
+            selected: 0,
+            annotations: true,
+            matches: Vec::new(),
+            match_set: HashSet::new(),
+            match_index: 0,
+            notice: None,
+        };
+        tree.rebuild();
+        tree
+    }
+
+    /// Swaps in a fresh value (e.g. the next poll of the same data), keeping what was
+    /// expanded and the selected node where it still exists.
+    pub fn set_value(&mut self, value: Value) {
+        self.value = value;
+        self.matches.clear();
+        self.match_set.clear();
+        self.rebuild();
+    }
+
+    pub fn rows(&self) -> &[TreeRow] {
+        &self.rows
+    }
+
+    pub fn has_schema(&self) -> bool {
+        self.schema.is_some()
+    }
+
+    pub fn selected_row(&self) -> Option<&TreeRow> {
+        self.rows.get(self.selected)
+    }
+
+    pub fn selected_value(&self) -> Option<&Value> {
+        match self.selected_row()?.kind {
+            RowKind::More(_) => None,
+            _ => self.value.pointer(&self.selected_row()?.pointer),
+        }
+    }
+
+    /// The selected value for the clipboard: strings without quotes, the rest as JSON.
+    pub fn copy_text(&self) -> Option<String> {
+        self.selected_value().map(plain_text)
+    }
+
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+
+    fn rebuild(&mut self) {
+        let selected = self.selected_row().map(|r| r.pointer.clone());
+        let mut rows = Vec::new();
+        self.build(
+            &self.value,
+            self.schema.as_ref(),
+            Node {
+                depth: 0,
+                pointer: String::new(),
+                label: String::new(),
+                path: String::new(),
+            },
+            &mut rows,
+        );
+        self.rows = rows;
+        if let Some(pointer) = selected
+            && let Some(index) = self.rows.iter().position(|r| r.pointer == pointer)
+        {
+            self.selected = index;
+        }
+        self.selected = self.selected.min(self.rows.len().saturating_sub(1));
+    }
+
+    fn is_open(&self, pointer: &str, depth: usize, len: usize) -> bool {
+        self.toggled
+            .get(pointer)
+            .copied()
+            .unwrap_or(depth < OPEN_DEPTH && len <= OPEN_MAX_LEN)
+    }
+
+    fn build(&self, value: &Value, schema: Option<&Schema>, node: Node, rows: &mut Vec<TreeRow>) {
+        let resolved = schema.map(|s| resolve(s, value));
+        let description = resolved
+            .and_then(|s| s.description.clone())
+            .or_else(|| schema.and_then(|s| s.description.clone()));
+        let (kind, len) = match value {
+            Value::Object(map) => (RowKind::Object(map.len()), map.len()),
+            Value::Array(items) => (RowKind::Array(items.len()), items.len()),
+            _ => (RowKind::Scalar, 0),
+        };
+        let open =
+            kind != RowKind::Scalar && len > 0 && self.is_open(&node.pointer, node.depth, len);
+        let (text, short, annotation) = match kind {
+            RowKind::Scalar => (
+                value.to_string(),
+                value
+                    .as_str()
+                    .and_then(abbreviate_hash)
+                    .map(|s| format!("\"{}\"", s)),
+                resolved.and_then(|s| annotation(value, s)),
+            ),
+            _ => (String::new(), None, None),
+        };
+        rows.push(TreeRow {
+            depth: node.depth,
+            pointer: node.pointer.clone(),
+            label: node.label,
+            path: node.path.clone(),
+            kind,
+            open,
+            text,
+            short,
+            annotation,
+            description,
+        });
+        if !open {
+            return;
+        }
+
+        match value {
+            Value::Object(map) => {
+                let extra = resolved.and_then(|s| s.additional_schema());
+                for (key, child) in map {
+                    let child_schema = resolved
+                        .and_then(|s| s.properties.get(key))
+                        .or(extra.as_ref());
+                    let path = if node.path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", node.path, key)
+                    };
+                    let child_node = Node {
+                        depth: node.depth + 1,
+                        pointer: format!("{}/{}", node.pointer, escape(key)),
+                        label: key.clone(),
+                        path,
+                    };
+                    self.build(child, child_schema, child_node, rows);
+                }
+            }
+            Value::Array(items) => {
+                let item_schema = resolved.and_then(|s| s.items.as_deref());
+                let shown = if items.len() > PAGE {
+                    self.shown
+                        .get(&node.pointer)
+                        .copied()
+                        .unwrap_or(PAGE)
+                        .min(items.len())
+                } else {
+                    items.len()
+                };
+                for (i, child) in items.iter().take(shown).enumerate() {
+                    let child_node = Node {
+                        depth: node.depth + 1,
+                        pointer: format!("{}/{}", node.pointer, i),
+                        label: format!("[{}]", i),
+                        path: format!("{}[{}]", node.path, i),
+                    };
+                    self.build(child, item_schema, child_node, rows);
+                }
+                if shown < items.len() {
+                    rows.push(TreeRow {
+                        depth: node.depth + 1,
+                        pointer: format!("{}/#more", node.pointer),
+                        label: String::new(),
+                        path: node.path.clone(),
+                        kind: RowKind::More(items.len() - shown),
+                        open: false,
+                        text: String::new(),
+                        short: None,
+                        annotation: None,
+                        description: None,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub fn move_by(&mut self, delta: isize) {
+        let last = self.rows.len().saturating_sub(1);
+        self.selected = self.selected.saturating_add_signed(delta).min(last);
+    }
+
+    /// Expands or collapses the selected container, or shows the next page of a large array.
+    pub fn toggle(&mut self) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        match row.kind {
+            RowKind::Object(n) | RowKind::Array(n) if n > 0 => {
+                let (pointer, open) = (row.pointer.clone(), row.open);
+                self.toggled.insert(pointer, !open);
+                self.rebuild();
+            }
+            RowKind::More(_) => {
+                let parent = row.pointer.trim_end_matches("/#more").to_string();
+                let index = self.selected;
+                *self.shown.entry(parent).or_insert(PAGE) += PAGE;
+                self.rebuild();
+                self.selected = index.min(self.rows.len().saturating_sub(1));
+            }
+            _ => {}
+        }
+    }
+
+    /// Opens a closed container, or steps into an open one.
+    fn expand(&mut self) {
+        match self.selected_row() {
+            Some(row) if row.open => self.move_by(1),
+            Some(_) => self.toggle(),
+            None => {}
+        }
+    }
+
+    /// Closes an open container, or steps out to the parent.
+    fn collapse(&mut self) {
+        let Some(row) = self.selected_row() else {
+            return;
+        };
+        if row.open {
+            self.toggle();
+        } else if let Some(parent) = self.rows[..self.selected]
+            .iter()
+            .rposition(|r| r.depth < row.depth)
+        {
+            self.selected = parent;
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> TreeAction {
+        self.notice = None;
+        match key.code {
+            KeyCode::Down | KeyCode::Char('j') => self.move_by(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_by(-1),
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => self.move_by(20),
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.move_by(-20)
+            }
+            KeyCode::Char('g') | KeyCode::Home => self.selected = 0,
+            KeyCode::Char('G') | KeyCode::End => self.selected = self.rows.len().saturating_sub(1),
+            KeyCode::Enter | KeyCode::Char(' ') => self.toggle(),
+            KeyCode::Right | KeyCode::Char('l') => self.expand(),
+            KeyCode::Left | KeyCode::Char('h') => self.collapse(),
+            KeyCode::Char('y') => {
+                let Some(text) = self.copy_text() else {
+                    return TreeAction::None;
+                };
+                self.notice = Some(format!("copied value ({} chars)", text.chars().count()));
+                return TreeAction::Copy(text);
+            }
+            KeyCode::Char('Y') => {
+                let Some(path) = self.selected_row().map(|r| r.path.clone()) else {
+                    return TreeAction::None;
+                };
+                self.notice = Some("copied path".to_string());
+                return TreeAction::Copy(if path.is_empty() {
+                    ".".to_string()
+                } else {
+                    path
+                });
+            }
+            KeyCode::Char('C') => {
+                let text = plain_text(&self.value);
+                self.notice = Some(format!("copied all ({} chars)", text.chars().count()));
+                return TreeAction::Copy(text);
+            }
+            KeyCode::Char('s') => {
+                let (Some(row), Some(value)) = (self.selected_row(), self.selected_value()) else {
+                    return TreeAction::None;
+                };
+                let label = row.label.trim_matches(['[', ']']);
+                let label = (!label.is_empty()).then(|| label.to_string());
+                return TreeAction::Save(export(value, label));
+            }
+            KeyCode::Char('S') => return TreeAction::Save(export(&self.value, None)),
+            _ => return TreeAction::Ignored,
+        }
+        TreeAction::None
+    }
+
+    pub fn matches(&self) -> &[String] {
+        &self.matches
+    }
+
+    pub fn is_match(&self, pointer: &str) -> bool {
+        self.match_set.contains(pointer)
+    }
+
+    /// Finds nodes whose key or scalar value contains `query` (case-insensitive), anywhere
+    /// in the value, and reveals the first one.
+    pub fn search(&mut self, query: &str) {
+        self.matches.clear();
+        self.match_set.clear();
+        self.match_index = 0;
+        let query = query.to_lowercase();
+        if query.is_empty() {
+            return;
+        }
+        find(&self.value, "", "", &query, &mut self.matches);
+        self.match_set = self.matches.iter().cloned().collect();
+        if let Some(first) = self.matches.first().cloned() {
+            self.reveal(&first);
+        }
+    }
+
+    pub fn clear_search(&mut self) {
+        self.matches.clear();
+        self.match_set.clear();
+        self.match_index = 0;
+    }
+
+    pub fn next_match(&mut self, forward: bool) {
+        let len = self.matches.len();
+        if len == 0 {
+            return;
+        }
+        self.match_index = if forward {
+            (self.match_index + 1) % len
+        } else {
+            (self.match_index + len - 1) % len
+        };
+        let pointer = self.matches[self.match_index].clone();
+        self.reveal(&pointer);
+    }
+
+    /// Expands every ancestor of `pointer` (paging large arrays far enough) and selects it.
+    fn reveal(&mut self, pointer: &str) {
+        let mut prefix = String::new();
+        for segment in pointer.split('/').skip(1) {
+            if let Some(Value::Array(items)) = self.value.pointer(&prefix)
+                && items.len() > PAGE
+                && let Ok(index) = segment.parse::<usize>()
+            {
+                let needed = (index / PAGE + 1) * PAGE;
+                let shown = self.shown.entry(prefix.clone()).or_insert(PAGE);
+                *shown = (*shown).max(needed);
+            }
+            self.toggled.insert(prefix.clone(), true);
+            prefix = format!("{}/{}", prefix, segment);
+        }
+        self.rebuild();
+        if let Some(index) = self.rows.iter().position(|r| r.pointer == pointer) {
+            self.selected = index;
+        }
+    }
+}
+
+struct Node {
+    depth: usize,
+    pointer: String,
+    label: String,
+    path: String,
+}
+
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn find(value: &Value, pointer: &str, key: &str, query: &str, out: &mut Vec<String>) {
+    if out.len() >= MAX_MATCHES {
+        return;
+    }
+    let hit = key.to_lowercase().contains(query)
+        || match value {
+            Value::Object(_) | Value::Array(_) => false,
+            Value::String(s) => s.to_lowercase().contains(query),
+            other => other.to_string().contains(query),
+        };
+    if hit && !pointer.is_empty() {
+        out.push(pointer.to_string());
+    }
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                find(v, &format!("{}/{}", pointer, escape(k)), k, query, out);
+            }
+        }
+        Value::Array(items) => {
+            for (i, v) in items.iter().enumerate() {
+                find(v, &format!("{}/{}", pointer, i), "", query, out);
+            }
+        }
+        _ => {}
+    }
+}
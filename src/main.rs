@@ -1,15 +1,36 @@
 mod app;
+mod arg_form;
+mod call_history;
+mod clipboard;
+mod file_picker;
 mod format;
+mod jq;
+mod json_tree;
+mod method_class;
+mod node_methods;
+mod peer_history;
+mod peers_export;
 mod peers_query;
+mod psbt;
+mod psbt_create;
+mod psbt_process;
+mod psbt_qr;
+mod psbt_summary;
+mod raw_args;
+mod result_view;
 mod rpc;
+mod rpc_macro;
 mod rpc_types;
+mod signer;
+mod storage;
 mod tabs;
+mod text_area;
 mod ui;
 mod wallet_schema;
 
 use std::path::PathBuf;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use clap::Parser;
 use crossterm::event::{EventStream, KeyEventKind};
@@ -17,7 +38,10 @@ use futures_util::StreamExt;
 use tokio::sync::mpsc;
 use tokio::time::interval;
 
-use app::{App, Event, PollResult, PsbtRpcAction, PsbtRpcResult, SearchResult, ZmqEntry};
+use app::{
+    AddrmanResult, App, CallOutcome, Event, PollResult, PsbtConfig, PsbtRpcAction, PsbtRpcResult,
+    SearchResult, ZmqEntry,
+};
 use rpc::RpcClient;
 
 #[derive(Parser)]
@@ -59,6 +83,31 @@ struct Args {
     #[arg(long)]
     zmqport: Option<u16>,
 
+    /// Highest fee rate (sat/vB) a PSBT broadcast may pay; passed to testmempoolaccept and
+    /// sendrawtransaction. Defaults to Bitcoin Core's own limit.
+    #[arg(long, default_value_t = app::DEFAULT_MAX_FEE_RATE)]
+    maxfeerate: f64,
+
+    /// HWI-compatible command used to list devices and sign PSBTs directly. Quote words
+    /// containing spaces as in a shell.
+    #[arg(long)]
+    signer: Option<String>,
+
+    /// Use JSON-RPC 2.0 (Bitcoin Core v28+), which reports malformed replies and
+    /// errors strictly instead of the legacy 1.0 behaviour.
+    #[arg(long)]
+    jsonrpc2: bool,
+
+    /// Only allow read-only RPCs; anything that changes node or wallet state, moves
+    /// funds or stops the node is refused before it is sent.
+    #[arg(long)]
+    read_only: bool,
+
+    /// Run a macro file against the node without the TUI, printing each step; exits
+    /// non-zero when a step fails.
+    #[arg(long, value_name = "FILE")]
+    run_macro: Option<PathBuf>,
+
     #[arg(long)]
     debug: bool,
 }
@@ -95,6 +144,21 @@ impl Args {
         }
     }
 
+    /// Chain name in the form bitcoind passes to external signers.
+    fn chain(&self) -> &str {
+        if self.testnet {
+            "test"
+        } else if self.testnet4 {
+            "testnet4"
+        } else if self.regtest {
+            "regtest"
+        } else if self.signet {
+            "signet"
+        } else {
+            "main"
+        }
+    }
+
     fn cookie_path(&self) -> Option<PathBuf> {
         self.rpccookiefile
             .as_ref()
@@ -134,16 +198,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         "starting"
     );
 
-    let rpc = Arc::new(RpcClient::new(
-        &args.host,
-        rpc_port,
-        cookie_path,
-        args.rpcuser.as_deref(),
-        args.rpcpassword.as_deref(),
-    ));
+    let rpc = Arc::new(
+        RpcClient::new(
+            &args.host,
+            rpc_port,
+            cookie_path,
+            args.rpcuser.as_deref(),
+            args.rpcpassword.as_deref(),
+        )
+        .jsonrpc2(args.jsonrpc2)
+        .read_only(args.read_only),
+    );
+
+    if let Some(path) = &args.run_macro {
+        let code = run_macro_headless(&rpc, path).await;
+        std::process::exit(code);
+    }
+
+    let profile = format!("{}-{}", args.host, rpc_port);
+    let psbt_config = PsbtConfig {
+        max_fee_rate: args.maxfeerate,
+        signer: args.signer.clone(),
+        chain: args.chain().to_string(),
+    };
 
     let mut terminal = ratatui::init();
-    let result = run(&mut terminal, rpc, args.interval, zmq_addr).await;
+    let _ = crossterm::execute!(std::io::stdout(), crossterm::event::EnableBracketedPaste);
+    let result = run(
+        &mut terminal,
+        rpc,
+        args.interval,
+        zmq_addr,
+        profile,
+        psbt_config,
+    )
+    .await;
+    let _ = crossterm::execute!(std::io::stdout(), crossterm::event::DisableBracketedPaste);
     ratatui::restore();
     result
 }
@@ -153,28 +243,74 @@ async fn run(
     rpc: Arc<RpcClient>,
     poll_interval: u64,
     zmq_addr: Option<String>,
+    profile: String,
+    psbt_config: PsbtConfig,
 ) -> Result<(), Box<dyn std::error::Error>> {
     const EVENT_CHANNEL_CAPACITY: usize = 1024;
     const POLL_WAKE_CHANNEL_CAPACITY: usize = 8;
 
-    let mut app = App::default();
+    let mut app = App {
+        profile_dir: storage::profile_dir(&profile),
+        read_only: rpc.is_read_only(),
+        ..App::default()
+    };
+    app.psbt.config = psbt_config;
+    app.load_peer_events();
+    app.load_psbt_descriptors();
+    app.load_call_history();
     let mut reader = EventStream::new();
     let mut tick = interval(Duration::from_millis(250));
+    // Drives QR animation; fast enough for the highest configurable frame rate.
+    let mut qr_tick = interval(Duration::from_millis(1000 / psbt_qr::MAX_FPS as u64));
 
     let (tx, mut rx) = mpsc::channel::<Event>(EVENT_CHANNEL_CAPACITY);
     let (poll_wake_tx, poll_wake_rx) = mpsc::channel::<()>(POLL_WAKE_CHANNEL_CAPACITY);
     let zmq_enabled = zmq_addr.is_some();
 
-    spawn_polling(rpc.clone(), tx.clone(), poll_interval, zmq_enabled, poll_wake_rx);
+    spawn_polling(
+        rpc.clone(),
+        tx.clone(),
+        poll_interval,
+        zmq_enabled,
+        poll_wake_rx,
+    );
 
     if let Some(addr) = zmq_addr {
         app.zmq.enabled = true;
         spawn_zmq(addr, tx.clone());
     }
 
+    let mut macro_task: Option<tokio::task::JoinHandle<()>> = None;
+
     loop {
         terminal.draw(|frame| ui::render(&app, frame))?;
 
+        if std::mem::take(&mut app.macros.cancel)
+            && let Some(task) = macro_task.take()
+        {
+            task.abort();
+        }
+
+        if let Some((run_id, steps)) = app.macros.start.take() {
+            if let Some(task) = macro_task.take() {
+                task.abort();
+            }
+            let rpc = rpc.clone();
+            let tx = tx.clone();
+            macro_task = Some(tokio::spawn(async move {
+                let mut state = rpc_macro::MacroState::default();
+                for step in &steps {
+                    let report = run_macro_step(&rpc, &mut state, step).await;
+                    let ok = report.ok;
+                    let _ = tx.send(Event::MacroStep(run_id, Box::new(report))).await;
+                    if !ok {
+                        break;
+                    }
+                }
+                let _ = tx.send(Event::MacroDone(run_id)).await;
+            }));
+        }
+
         if app.transactions.searching {
             app.transactions.searching = false;
             app.transactions.request_seq = app.transactions.request_seq.wrapping_add(1);
@@ -207,6 +343,15 @@ async fn run(
             });
         }
 
+        if let Some((version, subversion)) = app.method_check.fetch.take() {
+            let rpc = rpc.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let result = fetch_node_methods(&rpc, version, subversion).await;
+                let _ = tx.send(Event::NodeMethodsComplete(Box::new(result))).await;
+            });
+        }
+
         if app.wallet.browser.calling {
             app.wallet.browser.calling = false;
             app.wallet.browser.request_seq = app.wallet.browser.request_seq.wrapping_add(1);
@@ -216,24 +361,29 @@ async fn run(
                 .name
                 .clone();
             let arg_text = app.wallet.browser.arg_input.clone();
+            let form_params = app.wallet.browser.call_params.take();
             let wallet_name = app.wallet.wallet_name.clone();
             let rpc = rpc.clone();
             let tx = tx.clone();
             tokio::spawn(async move {
-                let params = parse_args(&arg_text);
-                let wallet = if wallet_name.is_empty() {
-                    None
-                } else {
-                    Some(wallet_name.as_str())
+                let params = form_params.map_or_else(|| raw_args::parse(&arg_text), Ok);
+                let wallet = (!wallet_name.is_empty()).then_some(wallet_name);
+                let time = format::unix_now();
+                let started = Instant::now();
+                let result = match &params {
+                    Ok(p) => rpc.call_raw(&method, p.clone(), wallet.as_deref()).await,
+                    Err(e) => Err(e.clone()),
                 };
-                let result = match params {
-                    Ok(p) => rpc.call_raw(&method, p, wallet).await.map(|v| {
-                        serde_json::to_string_pretty(&v).unwrap_or_else(|_| v.to_string())
-                    }),
-                    Err(e) => Err(e),
+                let outcome = CallOutcome {
+                    method,
+                    params: params.ok(),
+                    wallet,
+                    time,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    result,
                 };
                 let _ = tx
-                    .send(Event::WalletRpcComplete(request_id, Box::new(result)))
+                    .send(Event::WalletRpcComplete(request_id, Box::new(outcome)))
                     .await;
             });
         }
@@ -245,45 +395,110 @@ async fn run(
             app.rpc.in_flight_request = Some(request_id);
             let method = app.rpc.methods[app.rpc.selected].name.clone();
             let arg_text = app.rpc.arg_input.clone();
+            let form_params = app.rpc.call_params.take();
             let rpc = rpc.clone();
             let tx = tx.clone();
             tokio::spawn(async move {
-                let params = parse_args(&arg_text);
-                let result = match params {
-                    Ok(p) => rpc.call_raw(&method, p, None).await.map(|v| {
-                        serde_json::to_string_pretty(&v).unwrap_or_else(|_| v.to_string())
-                    }),
-                    Err(e) => Err(e),
+                let params = form_params.map_or_else(|| raw_args::parse(&arg_text), Ok);
+                let time = format::unix_now();
+                let started = Instant::now();
+                let result = match &params {
+                    Ok(p) => rpc.call_raw(&method, p.clone(), None).await,
+                    Err(e) => Err(e.clone()),
                 };
-                let _ = tx.send(Event::RpcComplete(request_id, Box::new(result))).await;
+                let outcome = CallOutcome {
+                    method,
+                    params: params.ok(),
+                    wallet: None,
+                    time,
+                    duration_ms: started.elapsed().as_millis() as u64,
+                    result,
+                };
+                let _ = tx
+                    .send(Event::RpcComplete(request_id, Box::new(outcome)))
+                    .await;
             });
         }
 
-        if app.psbt.in_flight_request.is_none() && let Some(action) = app.psbt.rpc_in_flight.take() {
+        if let Some(text) = app.clipboard.take()
+            && let Err(e) = clipboard::copy(&text)
+        {
+            tracing::warn!(error = %e, "OSC 52 copy failed");
+        }
+
+        app.psbt.queue_summary();
+        if app.psbt.summary_request.is_none()
+            && let Some(psbt) = app.psbt.summary_for.clone()
+        {
+            app.psbt.request_seq = app.psbt.request_seq.wrapping_add(1);
+            let request_id = app.psbt.request_seq;
+            app.psbt.summary_request = Some(request_id);
+            let config = app.psbt.config.clone();
+            let wallet_name = app.wallet.wallet_name.clone();
+            let rpc = rpc.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let wallet = Some(wallet_name.as_str()).filter(|w| !w.is_empty());
+                let result = summarize_psbt(&rpc, psbt.trim(), &config, wallet).await;
+                let _ = tx
+                    .send(Event::PsbtSummaryComplete(request_id, Box::new(result)))
+                    .await;
+            });
+        }
+        if app.psbt.in_flight_request.is_none()
+            && let Some(action) = app.psbt.rpc_in_flight.take()
+        {
             app.psbt.request_seq = app.psbt.request_seq.wrapping_add(1);
             let request_id = app.psbt.request_seq;
             app.psbt.in_flight_request = Some(request_id);
             app.psbt.running_action = Some(action);
-            let psbt = app.psbt.psbt.trim().to_string();
+            let psbt = app.psbt.current_text().trim().to_string();
+            app.psbt.sent_for = Some((app.psbt.active, psbt.clone()));
+            let action_params = app.psbt.action_params.take();
+            let config = app.psbt.config.clone();
             let wallet_name = app.wallet.wallet_name.clone();
             let rpc = rpc.clone();
             let tx = tx.clone();
             tokio::spawn(async move {
-                let result = run_psbt_action(&rpc, action, &psbt, &wallet_name).await;
+                let result =
+                    run_psbt_action(&rpc, action, &psbt, action_params, &config, &wallet_name)
+                        .await;
                 let _ = tx
                     .send(Event::PsbtRpcComplete(request_id, Box::new(result)))
                     .await;
             });
         }
 
+        if app.addrman.fetching {
+            app.addrman.fetching = false;
+            app.addrman.request_seq = app.addrman.request_seq.wrapping_add(1);
+            let request_id = app.addrman.request_seq;
+            app.addrman.in_flight_request = Some(request_id);
+            let network = app.addrman.network();
+            let rpc = rpc.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                const ADDRMAN_SAMPLE: u64 = 2500;
+                let (info, addresses) = tokio::join!(
+                    rpc.get_addrman_info(),
+                    rpc.get_node_addresses(ADDRMAN_SAMPLE, network),
+                );
+                let _ = tx
+                    .send(Event::AddrmanComplete(
+                        request_id,
+                        Box::new(AddrmanResult { info, addresses }),
+                    ))
+                    .await;
+            });
+        }
+
         if let Some(block_hash) = app.zmq.block_lookup.take() {
             let rpc = rpc.clone();
             let tx = tx.clone();
             tokio::spawn(async move {
                 let result = rpc
                     .call_raw("getblock", serde_json::json!([block_hash, 1]), None)
-                    .await
-                    .map(|v| serde_json::to_string_pretty(&v).unwrap_or_else(|_| v.to_string()));
+                    .await;
                 let _ = tx.send(Event::ZmqBlockComplete(Box::new(result))).await;
             });
         }
@@ -292,11 +507,16 @@ async fn run(
             _ = tick.tick() => {
                 app.update(Event::Tick);
             }
+            _ = qr_tick.tick(), if app.psbt.qr.open => {
+                app.psbt.qr.tick();
+            }
             event = reader.next() => {
-                if let Some(Ok(crossterm::event::Event::Key(key))) = event
-                    && key.kind == KeyEventKind::Press
-                {
-                    app.update(Event::Key(key));
+                match event {
+                    Some(Ok(crossterm::event::Event::Key(key))) if key.kind == KeyEventKind::Press => {
+                        app.update(Event::Key(key));
+                    }
+                    Some(Ok(crossterm::event::Event::Paste(text))) => app.update(Event::Paste(text)),
+                    _ => {}
                 }
             }
             event = rx.recv() => {
@@ -422,7 +642,11 @@ fn spawn_polling(
                 chaintips,
             };
 
-            if tx.send(Event::PollComplete(Box::new(result))).await.is_err() {
+            if tx
+                .send(Event::PollComplete(Box::new(result)))
+                .await
+                .is_err()
+            {
                 break;
             }
 
@@ -444,53 +668,53 @@ fn spawn_polling(
             }
 
             if let Some((tip_hash, height)) = tip_info {
-                    let is_cold_start = last_height.is_none();
-                    let mut updated = cached_recent_blocks.clone();
-                    let mut start_height = height.saturating_sub(RECENT_BLOCK_HISTORY - 1);
-
-                    if let Some(prev_height) = last_height {
-                        let delta = height.saturating_sub(prev_height);
-                        if height > prev_height && delta <= RECENT_BLOCK_HISTORY {
-                            start_height = prev_height + 1;
-                        } else {
-                            updated.clear();
-                        }
+                let is_cold_start = last_height.is_none();
+                let mut updated = cached_recent_blocks.clone();
+                let mut start_height = height.saturating_sub(RECENT_BLOCK_HISTORY - 1);
+
+                if let Some(prev_height) = last_height {
+                    let delta = height.saturating_sub(prev_height);
+                    if height > prev_height && delta <= RECENT_BLOCK_HISTORY {
+                        start_height = prev_height + 1;
                     } else {
                         updated.clear();
                     }
+                } else {
+                    updated.clear();
+                }
 
-                    if is_cold_start {
-                        // Fetch from tip downward, sending incremental updates
-                        for h in (start_height..=height).rev() {
-                            if let Ok(mut stats) = rpc.get_block_stats(h).await {
-                                stats.pool = get_block_pool(&rpc, h).await;
-                                updated.push(stats);
-                                updated.sort_by_key(|b| b.height);
-                                let snapshot = updated.clone();
-                                let _ = tx.send(Event::RecentBlocksComplete(snapshot)).await;
-                            }
+                if is_cold_start {
+                    // Fetch from tip downward, sending incremental updates
+                    for h in (start_height..=height).rev() {
+                        if let Ok(mut stats) = rpc.get_block_stats(h).await {
+                            stats.pool = get_block_pool(&rpc, h).await;
+                            updated.push(stats);
+                            updated.sort_by_key(|b| b.height);
+                            let snapshot = updated.clone();
+                            let _ = tx.send(Event::RecentBlocksComplete(snapshot)).await;
                         }
-                    } else {
-                        for h in start_height..=height {
-                            if let Ok(mut stats) = rpc.get_block_stats(h).await {
-                                stats.pool = get_block_pool(&rpc, h).await;
-                                updated.push(stats);
-                            }
+                    }
+                } else {
+                    for h in start_height..=height {
+                        if let Ok(mut stats) = rpc.get_block_stats(h).await {
+                            stats.pool = get_block_pool(&rpc, h).await;
+                            updated.push(stats);
                         }
                     }
+                }
 
-                    if updated.len() > RECENT_BLOCK_HISTORY as usize {
-                        let keep_from = updated.len() - RECENT_BLOCK_HISTORY as usize;
-                        updated.drain(0..keep_from);
-                    }
+                if updated.len() > RECENT_BLOCK_HISTORY as usize {
+                    let keep_from = updated.len() - RECENT_BLOCK_HISTORY as usize;
+                    updated.drain(0..keep_from);
+                }
 
-                    if !updated.is_empty() {
-                        cached_recent_blocks = updated.clone();
-                        let _ = tx.send(Event::RecentBlocksComplete(updated)).await;
-                    }
+                if !updated.is_empty() {
+                    cached_recent_blocks = updated.clone();
+                    let _ = tx.send(Event::RecentBlocksComplete(updated)).await;
+                }
 
-                    last_tip = Some(tip_hash);
-                    last_height = Some(height);
+                last_tip = Some(tip_hash);
+                last_height = Some(height);
             }
 
             tokio::select! {
@@ -534,15 +758,15 @@ fn extract_pool_name(coinbase_hex: &str) -> Option<String> {
     let mut last_match = None;
     let mut i = 0;
     while i < bytes.len() {
-        if bytes[i] == b'/' {
-            if let Some(end) = bytes[i + 1..].iter().position(|&b| b == b'/') {
-                let name = &bytes[i + 1..i + 1 + end];
-                if !name.is_empty() && name.iter().all(|&b| b.is_ascii_graphic() || b == b' ') {
-                    last_match = Some(String::from_utf8_lossy(name).into_owned());
-                }
-                i += end + 2;
-                continue;
+        if bytes[i] == b'/'
+            && let Some(end) = bytes[i + 1..].iter().position(|&b| b == b'/')
+        {
+            let name = &bytes[i + 1..i + 1 + end];
+            if !name.is_empty() && name.iter().all(|&b| b.is_ascii_graphic() || b == b' ') {
+                last_match = Some(String::from_utf8_lossy(name).into_owned());
             }
+            i += end + 2;
+            continue;
         }
         i += 1;
     }
@@ -554,20 +778,20 @@ fn extract_pool_name(coinbase_hex: &str) -> Option<String> {
     let mut best = "";
     let mut start = None;
     for (i, &b) in bytes.iter().enumerate() {
-        if b >= 0x20 && b <= 0x7e {
+        if (0x20..=0x7e).contains(&b) {
             if start.is_none() {
                 start = Some(i);
             }
-        } else if let Some(s) = start.take() {
-            if i - s > best.len() {
-                best = std::str::from_utf8(&bytes[s..i]).unwrap_or("");
-            }
+        } else if let Some(s) = start.take()
+            && i - s > best.len()
+        {
+            best = std::str::from_utf8(&bytes[s..i]).unwrap_or("");
         }
     }
-    if let Some(s) = start {
-        if bytes.len() - s > best.len() {
-            best = std::str::from_utf8(&bytes[s..]).unwrap_or("");
-        }
+    if let Some(s) = start
+        && bytes.len() - s > best.len()
+    {
+        best = std::str::from_utf8(&bytes[s..]).unwrap_or("");
     }
 
     if best.len() >= 4 {
@@ -586,14 +810,18 @@ fn spawn_zmq(addr: String, tx: mpsc::Sender<Event>) {
         // silently suppress notifications.
         if let Err(e) = socket.subscribe("").await {
             tracing::error!(error = %e, "zmq subscribe failed");
-            let _ = tx.send(Event::ZmqError(format!("subscribe all: {}", e))).await;
+            let _ = tx
+                .send(Event::ZmqError(format!("subscribe all: {}", e)))
+                .await;
             return;
         }
         tracing::debug!("zmq subscribed to all topics");
         tracing::info!(addr, "zmq connecting");
         if let Err(e) = socket.connect(&addr).await {
             tracing::error!(addr, error = %e, "zmq connect failed");
-            let _ = tx.send(Event::ZmqError(format!("connect {}: {}", addr, e))).await;
+            let _ = tx
+                .send(Event::ZmqError(format!("connect {}: {}", addr, e)))
+                .await;
             return;
         }
 
@@ -609,10 +837,15 @@ fn spawn_zmq(addr: String, tx: mpsc::Sender<Event>) {
             };
             let frames: Vec<_> = msg.into_vec();
             if frames.len() < 2 {
-                tracing::warn!(frames = frames.len(), "zmq: skipping message with unexpected frame count");
+                tracing::warn!(
+                    frames = frames.len(),
+                    "zmq: skipping message with unexpected frame count"
+                );
                 continue;
             }
-            let topic = String::from_utf8_lossy(&frames[0]).trim_end_matches('\0').to_string();
+            let topic = String::from_utf8_lossy(&frames[0])
+                .trim_end_matches('\0')
+                .to_string();
             if topic != "hashtx" && topic != "hashblock" {
                 continue;
             }
@@ -636,13 +869,112 @@ fn spawn_zmq(addr: String, tx: mpsc::Sender<Event>) {
     });
 }
 
-fn parse_args(input: &str) -> Result<serde_json::Value, String> {
-    let trimmed = input.trim();
-    if trimmed.is_empty() {
-        return Ok(serde_json::json!([]));
+/// Runs one macro step: a call with its capture, an assertion, or a variable or wallet
+/// change.
+async fn run_macro_step(
+    rpc: &RpcClient,
+    state: &mut rpc_macro::MacroState,
+    step: &rpc_macro::Step,
+) -> rpc_macro::StepReport {
+    use rpc_macro::StepKind;
+
+    let started = Instant::now();
+    let outcome = match &step.kind {
+        StepKind::Wallet(name) => {
+            state.wallet = name.clone();
+            Ok(match name {
+                Some(name) => format!("calls go to wallet {}", name),
+                None => "calls go to no wallet".to_string(),
+            })
+        }
+        StepKind::Set(var, value) => {
+            state.vars.insert(var.clone(), value.clone());
+            Ok(format!("{} = {}", var, rpc_macro::summarize(value)))
+        }
+        StepKind::Call {
+            method,
+            args,
+            capture,
+        } => {
+            let params = state
+                .substitute(args)
+                .and_then(|args| raw_args::parse(&args));
+            match params {
+                Ok(params) => match rpc.call_raw(method, params, state.wallet.as_deref()).await {
+                    Ok(result) => state.record(result, capture.as_ref()),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            }
+        }
+        StepKind::Assert(filter) => state.check(filter),
+    };
+    let (ok, detail) = match outcome {
+        Ok(detail) => (true, detail),
+        Err(e) => (false, e),
+    };
+    rpc_macro::StepReport {
+        line: step.line,
+        text: step.text.clone(),
+        ok,
+        detail,
+        duration_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+/// `--run-macro`: runs the steps until one fails and returns the exit code (2 when the
+/// file can't be read or parsed).
+async fn run_macro_headless(rpc: &RpcClient, path: &std::path::Path) -> i32 {
+    let script = match rpc_macro::Macro::load(path) {
+        Ok(script) => script,
+        Err(e) => {
+            eprintln!("{}: {}", path.display(), e);
+            return 2;
+        }
+    };
+    println!("macro {} ({} steps)", script.name, script.steps.len());
+    let mut state = rpc_macro::MacroState::default();
+    for step in &script.steps {
+        let report = run_macro_step(rpc, &mut state, step).await;
+        println!(
+            "{} line {}: {} ({} ms)\n    {}",
+            if report.ok { "ok  " } else { "FAIL" },
+            report.line,
+            report.text,
+            report.duration_ms,
+            report.detail
+        );
+        if !report.ok {
+            return 1;
+        }
+    }
+    println!("passed");
+    0
+}
+
+/// Reads the node's method index from `help`, plus the full help of each method the
+/// bundled spec doesn't know.
+async fn fetch_node_methods(
+    rpc: &RpcClient,
+    version: u64,
+    subversion: String,
+) -> Result<node_methods::NodeMethods, String> {
+    let index = rpc.call_raw("help", serde_json::json!([]), None).await?;
+    let index = index.as_str().ok_or("help returned no text")?;
+    let bundled: std::collections::BTreeSet<String> =
+        wallet_schema::bundled_method_names().into_iter().collect();
+    let mut methods = node_methods::parse_help_index(index);
+    for method in methods.iter_mut().filter(|m| !bundled.contains(&m.name)) {
+        let help = rpc
+            .call_raw("help", serde_json::json!([method.name]), None)
+            .await?;
+        method.help = help.as_str().map(str::to_string);
     }
-    let wrapped = format!("[{}]", trimmed);
-    serde_json::from_str(&wrapped).map_err(|e| format!("Invalid args: {}", e))
+    Ok(node_methods::NodeMethods {
+        version,
+        subversion,
+        methods,
+    })
 }
 
 async fn search_tx(rpc: &RpcClient, txid: &str) -> Result<SearchResult, String> {
@@ -694,7 +1026,7 @@ fn reverse_32byte_hex(s: &str) -> Option<String> {
     Some(out)
 }
 
-async fn decode_tx_for_display(rpc: &RpcClient, txid: &str) -> Option<String> {
+async fn decode_tx_for_display(rpc: &RpcClient, txid: &str) -> Option<serde_json::Value> {
     let hex = match rpc.get_raw_transaction_hex(txid).await {
         Ok(hex) => hex,
         Err(e) => {
@@ -703,9 +1035,7 @@ async fn decode_tx_for_display(rpc: &RpcClient, txid: &str) -> Option<String> {
         }
     };
     match rpc.decode_raw_transaction(&hex).await {
-        Ok(decoded) => Some(
-            serde_json::to_string_pretty(&decoded).unwrap_or_else(|_| decoded.to_string()),
-        ),
+        Ok(decoded) => Some(decoded),
         Err(e) => {
             tracing::debug!(txid, error = %e, "decoderawtransaction failed");
             None
@@ -717,9 +1047,19 @@ async fn run_psbt_action(
     rpc: &RpcClient,
     action: PsbtRpcAction,
     psbt: &str,
+    action_params: Option<serde_json::Value>,
+    config: &PsbtConfig,
     wallet_name: &str,
 ) -> Result<PsbtRpcResult, String> {
-    if psbt.is_empty() {
+    let standalone = matches!(
+        action,
+        PsbtRpcAction::Create
+            | PsbtRpcAction::Combine
+            | PsbtRpcAction::Join
+            | PsbtRpcAction::Broadcast
+            | PsbtRpcAction::EnumerateSigners
+    );
+    if psbt.is_empty() && !standalone {
         return Err("No PSBT loaded".to_string());
     }
 
@@ -734,26 +1074,291 @@ async fn run_psbt_action(
         PsbtRpcAction::Analyze => ("analyzepsbt", serde_json::json!([psbt]), None),
         PsbtRpcAction::WalletProcess => (
             "walletprocesspsbt",
-            serde_json::json!([psbt, true, "DEFAULT", true, false]),
+            action_params.ok_or("No walletprocesspsbt options")?,
             wallet,
         ),
+        PsbtRpcAction::WalletSign => {
+            let info = rpc
+                .call_raw("getwalletinfo", serde_json::json!([]), wallet)
+                .await?;
+            if info.get("external_signer").and_then(|v| v.as_bool()) != Some(true) {
+                return Err(format!(
+                    "wallet '{}' has no external signer, so walletprocesspsbt would sign with its own keys; pass --signer <cmd> to sign with the device",
+                    info.get("walletname")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(wallet_name)
+                ));
+            }
+            (
+                "walletprocesspsbt",
+                action_params.ok_or("No walletprocesspsbt options")?,
+                wallet,
+            )
+        }
+        PsbtRpcAction::DescriptorProcess => (
+            "descriptorprocesspsbt",
+            action_params.ok_or("No descriptors selected")?,
+            None,
+        ),
         PsbtRpcAction::Finalize => ("finalizepsbt", serde_json::json!([psbt, false]), None),
         PsbtRpcAction::UtxoUpdate => ("utxoupdatepsbt", serde_json::json!([psbt]), None),
+        PsbtRpcAction::Create => (
+            "walletcreatefundedpsbt",
+            action_params.ok_or("No PSBT creation parameters")?,
+            wallet,
+        ),
+        PsbtRpcAction::Combine => (
+            "combinepsbt",
+            action_params.ok_or("No PSBTs to combine")?,
+            None,
+        ),
+        PsbtRpcAction::Join => ("joinpsbts", action_params.ok_or("No PSBTs to join")?, None),
+        PsbtRpcAction::Broadcast => (
+            "sendrawtransaction",
+            action_params.ok_or("No transaction to broadcast")?,
+            None,
+        ),
+        PsbtRpcAction::TestAccept => {
+            return test_psbt_broadcast(rpc, psbt, config.max_fee_rate).await;
+        }
+        PsbtRpcAction::EnumerateSigners => return enumerate_signers(rpc, config).await,
+        PsbtRpcAction::ExternalSign => {
+            let fingerprint = action_params
+                .as_ref()
+                .and_then(|v| v.as_str())
+                .ok_or("No signer selected")?;
+            return sign_with_command(psbt, fingerprint, config).await;
+        }
     };
 
     let value = rpc.call_raw(method, params, wallet_ctx).await?;
-    let output_json = serde_json::to_string_pretty(&value).unwrap_or_else(|_| value.to_string());
     let updated_psbt = match action {
-        PsbtRpcAction::UtxoUpdate => value.as_str().map(str::to_string),
+        PsbtRpcAction::UtxoUpdate | PsbtRpcAction::Combine | PsbtRpcAction::Join => {
+            value.as_str().map(str::to_string)
+        }
         _ => value
             .get("psbt")
             .and_then(|v| v.as_str())
             .map(str::to_string),
     };
 
+    let broadcast_txid = match action {
+        PsbtRpcAction::Broadcast => value.as_str().map(str::to_string),
+        _ => None,
+    };
+
     Ok(PsbtRpcResult {
         action,
-        output_json,
+        output: value,
         updated_psbt,
+        broadcast_check: None,
+        broadcast_txid,
+        signers: None,
+    })
+}
+
+/// Extracts the final transaction with `finalizepsbt` and dry-runs it through
+/// `testmempoolaccept` so the user can confirm before anything is broadcast.
+async fn test_psbt_broadcast(
+    rpc: &RpcClient,
+    psbt: &str,
+    max_fee_rate: f64,
+) -> Result<PsbtRpcResult, String> {
+    let finalized = rpc
+        .call_raw("finalizepsbt", serde_json::json!([psbt, true]), None)
+        .await?;
+    let hex = match finalized.get("hex").and_then(|v| v.as_str()) {
+        Some(hex) if finalized.get("complete").and_then(|v| v.as_bool()) == Some(true) => {
+            hex.to_string()
+        }
+        _ => {
+            return Err(
+                "finalizepsbt could not complete the PSBT; it still needs signatures".to_string(),
+            );
+        }
+    };
+
+    let value = rpc
+        .call_raw(
+            "testmempoolaccept",
+            serde_json::json!([[hex], app::sat_vb_to_btc_kvb(max_fee_rate)]),
+            None,
+        )
+        .await?;
+    let entry = value
+        .as_array()
+        .and_then(|a| a.first())
+        .ok_or("testmempoolaccept returned no result")?;
+    let fees = entry.get("fees");
+    let check = app::BroadcastCheck {
+        txid: entry
+            .get("txid")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string(),
+        allowed: entry
+            .get("allowed")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false),
+        reject_reason: entry
+            .get("reject-reason")
+            .and_then(|v| v.as_str())
+            .map(
+                |r| match entry.get("reject-details").and_then(|v| v.as_str()) {
+                    Some(details) => format!("{} ({})", r, details),
+                    None => r.to_string(),
+                },
+            ),
+        vsize: entry.get("vsize").and_then(|v| v.as_u64()),
+        fee: fees.and_then(|f| f.get("base")).and_then(|v| v.as_f64()),
+        effective_feerate: fees
+            .and_then(|f| f.get("effective-feerate"))
+            .and_then(|v| v.as_f64()),
+        hex,
+    };
+
+    Ok(PsbtRpcResult {
+        action: PsbtRpcAction::TestAccept,
+        output: value,
+        updated_psbt: None,
+        broadcast_check: Some(check),
+        broadcast_txid: None,
+        signers: None,
+    })
+}
+
+/// Gathers `decodepsbt`, `analyzepsbt`, wallet `getaddressinfo` for every output and the
+/// node's fee estimate, and builds the summary view from them. Wallet and estimate
+/// lookups are optional; without them the summary just says less.
+async fn summarize_psbt(
+    rpc: &RpcClient,
+    psbt: &str,
+    config: &PsbtConfig,
+    wallet: Option<&str>,
+) -> Result<psbt_summary::PsbtSummary, String> {
+    let (decoded, analyzed, estimate) = tokio::join!(
+        rpc.call_raw("decodepsbt", serde_json::json!([psbt]), None),
+        rpc.call_raw("analyzepsbt", serde_json::json!([psbt]), None),
+        rpc.call_raw(
+            "estimatesmartfee",
+            serde_json::json!([psbt_summary::ESTIMATE_TARGET]),
+            None
+        ),
+    );
+    let (decoded, analyzed) = (decoded?, analyzed?);
+
+    let mut addresses: Vec<String> = decoded
+        .pointer("/tx/vout")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|out| {
+            out.pointer("/scriptPubKey/address")
+                .and_then(|v| v.as_str())
+        })
+        .map(str::to_string)
+        .collect();
+    addresses.sort();
+    addresses.dedup();
+    // Looked up concurrently; a failed lookup (or no wallet at all) only leaves that
+    // output unclassified.
+    let lookups = addresses
+        .iter()
+        .map(|address| rpc.call_raw("getaddressinfo", serde_json::json!([address]), wallet));
+    let address_info = addresses
+        .iter()
+        .cloned()
+        .zip(futures_util::future::join_all(lookups).await)
+        .filter_map(|(address, info)| Some((address, info.ok()?)))
+        .collect();
+
+    Ok(psbt_summary::build(&psbt_summary::SummaryInputs {
+        decoded,
+        analyzed,
+        address_info,
+        estimate: estimate
+            .ok()
+            .and_then(|v| v.get("feerate").and_then(|r| r.as_f64())),
+        max_fee_rate: config.max_fee_rate,
+    }))
+}
+
+/// Lists signers through the `--signer` command if one is configured (HWI `enumerate`),
+/// otherwise through the node's `enumeratesigners`.
+async fn enumerate_signers(rpc: &RpcClient, config: &PsbtConfig) -> Result<PsbtRpcResult, String> {
+    let (value, signers) = match &config.signer {
+        Some(command) => {
+            let value = signer::run(command, &["enumerate"], None).await?;
+            let signers = value
+                .as_array()
+                .map(|devices| {
+                    devices
+                        .iter()
+                        .filter_map(|d| {
+                            let fingerprint = d.get("fingerprint")?.as_str()?.to_string();
+                            let name = ["model", "type"]
+                                .iter()
+                                .find_map(|k| d.get(*k).and_then(|v| v.as_str()))
+                                .unwrap_or("device")
+                                .to_string();
+                            Some(app::ExternalSigner { fingerprint, name })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            (value, signers)
+        }
+        None => {
+            let value = rpc
+                .call_raw("enumeratesigners", serde_json::json!([]), None)
+                .await?;
+            let signers = value
+                .get("signers")
+                .and_then(|v| v.as_array())
+                .map(|signers| {
+                    signers
+                        .iter()
+                        .filter_map(|s| {
+                            Some(app::ExternalSigner {
+                                fingerprint: s.get("fingerprint")?.as_str()?.to_string(),
+                                name: s.get("name")?.as_str()?.to_string(),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            (value, signers)
+        }
+    };
+
+    Ok(PsbtRpcResult {
+        action: PsbtRpcAction::EnumerateSigners,
+        output: value,
+        updated_psbt: None,
+        broadcast_check: None,
+        broadcast_txid: None,
+        signers: Some(signers),
+    })
+}
+
+/// Signs with the `--signer` command for the ExternalSign action.
+async fn sign_with_command(
+    psbt: &str,
+    fingerprint: &str,
+    config: &PsbtConfig,
+) -> Result<PsbtRpcResult, String> {
+    let command = config
+        .signer
+        .as_deref()
+        .ok_or("No --signer command configured")?;
+    let (value, updated_psbt) = signer::sign_tx(command, psbt, fingerprint, &config.chain).await?;
+
+    Ok(PsbtRpcResult {
+        action: PsbtRpcAction::ExternalSign,
+        output: value,
+        updated_psbt: Some(updated_psbt),
+        broadcast_check: None,
+        broadcast_txid: None,
+        signers: None,
     })
 }
@@ -0,0 +1,117 @@
+//! How much an RPC can change, for call confirmations and `--read-only`.
+
+use crate::wallet_schema::method_category;
+
+/// Ordered from least to most harmful.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MethodClass {
+    ReadOnly,
+    /// Changes node or wallet state: bans, pruning, chain state, loading wallets, keys.
+    StateChanging,
+    /// Broadcasts transactions or spends from the wallet.
+    FundMoving,
+    NodeStopping,
+}
+
+impl MethodClass {
+    /// What a call does, completing "<method> ...".
+    pub fn label(self) -> &'static str {
+        match self {
+            MethodClass::ReadOnly => "only reads",
+            MethodClass::StateChanging => "changes node or wallet state",
+            MethodClass::FundMoving => "moves funds",
+            MethodClass::NodeStopping => "stops the node",
+        }
+    }
+
+    /// Calls that must be confirmed by typing the method name rather than with `y`.
+    pub fn needs_typed_confirmation(self) -> bool {
+        matches!(self, MethodClass::FundMoving | MethodClass::NodeStopping)
+    }
+}
+
+const NODE_STOPPING: &[&str] = &["stop"];
+
+const FUND_MOVING: &[&str] = &[
+    "bumpfee",
+    "send",
+    "sendall",
+    "sendmany",
+    "sendrawtransaction",
+    "sendtoaddress",
+    "submitpackage",
+];
+
+/// Methods outside the wallet category that change something. Hidden methods such as
+/// `invalidateblock` aren't in the spec but can still be called by name.
+/// `fundrawtransaction` can lock the wallet's coins, and the scans start node-side jobs.
+const STATE_CHANGING: &[&str] = &[
+    "addnode",
+    "clearbanned",
+    "disconnectnode",
+    "dumptxoutset",
+    "fundrawtransaction",
+    "getblockfrompeer",
+    "importmempool",
+    "invalidateblock",
+    "loadtxoutset",
+    "logging",
+    "preciousblock",
+    "prioritisetransaction",
+    "pruneblockchain",
+    "reconsiderblock",
+    "savemempool",
+    "scanblocks",
+    "scantxoutset",
+    "setban",
+    "setmocktime",
+    "setnetworkactive",
+    "submitblock",
+    "submitheader",
+];
+
+/// Wallet methods that only read.
+const WALLET_READ_ONLY: &[&str] = &[
+    "getaddressesbylabel",
+    "getaddressinfo",
+    "getbalance",
+    "getbalances",
+    "gethdkeys",
+    "getreceivedbyaddress",
+    "getreceivedbylabel",
+    "gettransaction",
+    "getwalletinfo",
+    "listaddressgroupings",
+    "listdescriptors",
+    "listlabels",
+    "listlockunspent",
+    "listreceivedbyaddress",
+    "listreceivedbylabel",
+    "listsinceblock",
+    "listtransactions",
+    "listunspent",
+    "listwalletdir",
+    "listwallets",
+    "simulaterawtransaction",
+    "walletdisplayaddress",
+];
+
+/// Wallet methods change state unless curated as read-only; other categories only read
+/// unless curated otherwise. Methods the bundled spec doesn't know are treated as
+/// state-changing.
+pub fn classify(method: &str) -> MethodClass {
+    if NODE_STOPPING.contains(&method) {
+        return MethodClass::NodeStopping;
+    }
+    if FUND_MOVING.contains(&method) {
+        return MethodClass::FundMoving;
+    }
+    if STATE_CHANGING.contains(&method) {
+        return MethodClass::StateChanging;
+    }
+    match method_category(method) {
+        Some("wallet") if WALLET_READ_ONLY.contains(&method) => MethodClass::ReadOnly,
+        Some("wallet") | None => MethodClass::StateChanging,
+        Some(_) => MethodClass::ReadOnly,
+    }
+}
@@ -0,0 +1,157 @@
+//! The connected node's own method list, read from `help`, and how it differs from the
+//! bundled openrpc.json (which comes from a newer dev build).
+
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::wallet_schema::{Availability, RpcMethod, RpcParam, Schema};
+
+/// A method listed by the node's `help`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NodeMethod {
+    pub name: String,
+    /// Lowercased `help` section, e.g. `blockchain` or `wallet`.
+    pub category: String,
+    /// Full `help <name>` text, fetched only for methods the bundled spec doesn't have.
+    #[serde(default)]
+    pub help: Option<String>,
+}
+
+/// What one node version offers; cached per version in the profile directory.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NodeMethods {
+    pub version: u64,
+    pub subversion: String,
+    pub methods: Vec<NodeMethod>,
+}
+
+/// Parses the method index printed by `help` without arguments.
+pub fn parse_help_index(text: &str) -> Vec<NodeMethod> {
+    let mut category = String::new();
+    let mut methods = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if let Some(section) = line.strip_prefix("==").and_then(|l| l.strip_suffix("==")) {
+            category = section.trim().to_lowercase();
+        } else if let Some(name) = line.split_whitespace().next() {
+            methods.push(NodeMethod {
+                name: name.to_string(),
+                category: category.clone(),
+                help: None,
+            });
+        }
+    }
+    methods
+}
+
+/// Builds a method from its `help <name>` text: the numbered `Arguments:` entries become
+/// params and the rest (description, results, examples) the description.
+pub fn method_from_help(name: &str, help: &str) -> RpcMethod {
+    let mut description = Vec::new();
+    let mut params: Vec<RpcParam> = Vec::new();
+    let mut in_args = false;
+    for line in help.lines().skip(1) {
+        if line.trim() == "Arguments:" {
+            in_args = true;
+            continue;
+        }
+        if in_args {
+            if line.trim().is_empty() {
+                in_args = false;
+            } else if let Some(param) = parse_argument(line) {
+                params.push(param);
+            } else if let Some(last) = params.last_mut()
+                && line.starts_with(char::is_whitespace)
+                && !line
+                    .trim_start()
+                    .starts_with(['{', '}', '[', ']', '"', '.'])
+            {
+                last.description.push(' ');
+                last.description.push_str(line.trim());
+            }
+            continue;
+        }
+        description.push(line);
+    }
+    while description.first().is_some_and(|l| l.trim().is_empty()) {
+        description.remove(0);
+    }
+    while description.last().is_some_and(|l| l.trim().is_empty()) {
+        description.pop();
+    }
+    RpcMethod {
+        name: name.to_string(),
+        description: description.join("\n"),
+        params,
+        result: None,
+        availability: Availability::HelpOnly,
+    }
+}
+
+/// `2. verbosity    (numeric, optional, default=1) 0 for hex-encoded data, ...`
+fn parse_argument(line: &str) -> Option<RpcParam> {
+    let (index, rest) = line.split_once(". ")?;
+    if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let rest = rest.trim_start();
+    let (name, rest) = rest.split_once(char::is_whitespace)?;
+    let rest = rest.trim_start().strip_prefix('(')?;
+    let (meta, description) = rest.split_once(')')?;
+    let mut meta = meta.split(',').map(str::trim);
+    let type_name = meta.next().unwrap_or_default();
+    let required = meta.next().is_some_and(|r| r.starts_with("required"));
+
+    let schema_type = match type_name {
+        "string" => Some("string"),
+        "numeric" | "amount" => Some("number"),
+        "boolean" => Some("boolean"),
+        "json object" | "object" => Some("object"),
+        "json array" | "array" => Some("array"),
+        _ => None,
+    };
+    let schema = Schema {
+        schema_type: schema_type.map(str::to_string),
+        type_str: schema_type
+            .is_none()
+            .then(|| vec![String::new(), type_name.to_string()]),
+        ..Schema::default()
+    };
+    Some(RpcParam {
+        name: name.to_string(),
+        description: description.trim().to_string(),
+        required,
+        schema_type: schema.type_label(),
+        schema,
+        aliases: Vec::new(),
+    })
+}
+
+/// Marks the bundled methods by whether the node has them and adds the node's methods
+/// the bundled spec lacks, for the categories `include` accepts.
+pub fn reconcile(
+    bundled: Vec<RpcMethod>,
+    node: &NodeMethods,
+    include: impl Fn(&str) -> bool,
+) -> Vec<RpcMethod> {
+    let on_node: BTreeSet<&str> = node.methods.iter().map(|m| m.name.as_str()).collect();
+    let mut methods: Vec<RpcMethod> = bundled
+        .into_iter()
+        .map(|mut m| {
+            m.availability = if on_node.contains(m.name.as_str()) {
+                Availability::Available
+            } else {
+                Availability::Missing
+            };
+            m
+        })
+        .collect();
+    methods.extend(
+        node.methods
+            .iter()
+            .filter(|m| include(&m.category))
+            .filter_map(|m| Some(method_from_help(&m.name, m.help.as_deref()?))),
+    );
+    methods.sort_by(|a, b| a.name.cmp(&b.name));
+    methods
+}
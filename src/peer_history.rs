@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::rpc_types::PeerInfo;
+
+const MAX_SAMPLES: usize = 180;
+const DISCONNECTED_RETENTION: Duration = Duration::from_secs(600);
+
+/// Derived columns computed from the polling history rather than `getpeerinfo`.
+pub const RATE_COLUMNS: [&str; 2] = ["recv_rate", "send_rate"];
+
+pub struct PeerSample {
+    pub at: Instant,
+    pub bytessent: u64,
+    pub bytesrecv: u64,
+    pub pingtime: Option<f64>,
+}
+
+pub struct PeerHistory {
+    pub last: PeerInfo,
+    pub samples: VecDeque<PeerSample>,
+    pub connected: bool,
+    pub last_seen: Instant,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PeerEventKind {
+    Connected,
+    Disconnected,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PeerEvent {
+    pub time: u64,
+    pub kind: PeerEventKind,
+    pub id: i64,
+    pub addr: String,
+    #[serde(default)]
+    pub network: String,
+    #[serde(default)]
+    pub connection_type: String,
+    #[serde(default)]
+    pub inbound: bool,
+    /// Connection lifetime in seconds; only meaningful for disconnects.
+    #[serde(default)]
+    pub duration: u64,
+    #[serde(default)]
+    pub bytessent: u64,
+    #[serde(default)]
+    pub bytesrecv: u64,
+}
+
+impl PeerEvent {
+    fn new(kind: PeerEventKind, peer: &PeerInfo, time: u64) -> Self {
+        PeerEvent {
+            time,
+            kind,
+            id: peer.id,
+            addr: peer.addr.clone(),
+            network: peer.network.clone(),
+            connection_type: peer.connection_type.clone(),
+            inbound: peer.inbound,
+            duration: if peer.conntime > 0 {
+                time.saturating_sub(peer.conntime)
+            } else {
+                0
+            },
+            bytessent: peer.bytessent,
+            bytesrecv: peer.bytesrecv,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct PeerHistoryStore {
+    peers: HashMap<i64, PeerHistory>,
+    has_baseline: bool,
+}
+
+impl PeerHistoryStore {
+    /// Records a `getpeerinfo` snapshot and returns the connects/disconnects since the
+    /// previous one. The first snapshot only establishes a baseline.
+    pub fn record(&mut self, peers: &[PeerInfo], now: Instant, unix_now: u64) -> Vec<PeerEvent> {
+        let mut events = Vec::new();
+        let current: HashSet<i64> = peers.iter().map(|p| p.id).collect();
+        let previous: HashSet<i64> = self
+            .peers
+            .iter()
+            .filter(|(_, h)| h.connected)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for history in self.peers.values_mut() {
+            if self.has_baseline && history.connected && !current.contains(&history.last.id) {
+                events.push(PeerEvent::new(
+                    PeerEventKind::Disconnected,
+                    &history.last,
+                    unix_now,
+                ));
+            }
+            history.connected = false;
+        }
+
+        for peer in peers {
+            if self.has_baseline && !previous.contains(&peer.id) {
+                events.push(PeerEvent::new(PeerEventKind::Connected, peer, unix_now));
+            }
+            let history = self.peers.entry(peer.id).or_insert_with(|| PeerHistory {
+                last: peer.clone(),
+                samples: VecDeque::new(),
+                connected: true,
+                last_seen: now,
+            });
+            history.last = peer.clone();
+            history.connected = true;
+            history.last_seen = now;
+            history.samples.push_back(PeerSample {
+                at: now,
+                bytessent: peer.bytessent,
+                bytesrecv: peer.bytesrecv,
+                pingtime: peer.pingtime,
+            });
+            while history.samples.len() > MAX_SAMPLES {
+                history.samples.pop_front();
+            }
+        }
+
+        self.peers
+            .retain(|_, h| h.connected || now.duration_since(h.last_seen) < DISCONNECTED_RETENTION);
+        self.has_baseline = true;
+        events.sort_by_key(|e| (e.kind == PeerEventKind::Connected, e.id));
+        events
+    }
+
+    pub fn get(&self, id: i64) -> Option<&PeerHistory> {
+        self.peers.get(&id)
+    }
+}
+
+impl PeerHistory {
+    /// Current receive rate in bytes/s, from the two most recent samples.
+    pub fn recv_rate(&self) -> Option<f64> {
+        self.latest_rate(|s| s.bytesrecv)
+    }
+
+    /// Current send rate in bytes/s, from the two most recent samples.
+    pub fn send_rate(&self) -> Option<f64> {
+        self.latest_rate(|s| s.bytessent)
+    }
+
+    pub fn recv_rates(&self) -> Vec<u64> {
+        self.rate_series(|s| s.bytesrecv)
+    }
+
+    pub fn send_rates(&self) -> Vec<u64> {
+        self.rate_series(|s| s.bytessent)
+    }
+
+    pub fn ping_ms(&self) -> Vec<u64> {
+        self.samples
+            .iter()
+            .map(|s| s.pingtime.map(|t| (t * 1000.0).round() as u64).unwrap_or(0))
+            .collect()
+    }
+
+    pub fn tracked_for(&self) -> Duration {
+        match (self.samples.front(), self.samples.back()) {
+            (Some(first), Some(last)) => last.at.duration_since(first.at),
+            _ => Duration::ZERO,
+        }
+    }
+
+    fn latest_rate(&self, counter: impl Fn(&PeerSample) -> u64) -> Option<f64> {
+        let len = self.samples.len();
+        if len < 2 {
+            return None;
+        }
+        sample_rate(&self.samples[len - 2], &self.samples[len - 1], &counter)
+    }
+
+    fn rate_series(&self, counter: impl Fn(&PeerSample) -> u64) -> Vec<u64> {
+        self.samples
+            .iter()
+            .zip(self.samples.iter().skip(1))
+            .map(|(a, b)| sample_rate(a, b, &counter).unwrap_or(0.0).round() as u64)
+            .collect()
+    }
+}
+
+fn sample_rate(
+    prev: &PeerSample,
+    next: &PeerSample,
+    counter: &impl Fn(&PeerSample) -> u64,
+) -> Option<f64> {
+    let secs = next.at.duration_since(prev.at).as_secs_f64();
+    if secs <= 0.0 {
+        return None;
+    }
+    // Counters only go backwards if the node restarted and reused the id.
+    let delta = counter(next).saturating_sub(counter(prev));
+    Some(delta as f64 / secs)
+}
@@ -0,0 +1,133 @@
+use serde_json::{Map, Value};
+
+use crate::peer_history::PeerHistoryStore;
+use crate::peers_query;
+use crate::rpc_types::PeerInfo;
+
+/// Columns written when the query has no `columns` command; mirrors the Peers table.
+pub const DEFAULT_COLUMNS: [&str; 13] = [
+    "id",
+    "addr",
+    "subver",
+    "connection_type",
+    "network",
+    "inbound",
+    "pingtime",
+    "bytesrecv",
+    "bytessent",
+    "recv_rate",
+    "send_rate",
+    "synced_blocks",
+    "transport_protocol_type",
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            ExportFormat::Csv => ExportFormat::Json,
+            ExportFormat::Json => ExportFormat::Csv,
+        }
+    }
+}
+
+/// Serializes the peers at `indices` (in that order) with the given columns.
+/// An empty column list falls back to [`DEFAULT_COLUMNS`].
+pub fn export(
+    peers: &[PeerInfo],
+    indices: &[usize],
+    columns: &[String],
+    history: &PeerHistoryStore,
+    format: ExportFormat,
+) -> String {
+    let columns: Vec<String> = if columns.is_empty() {
+        DEFAULT_COLUMNS.iter().map(|c| c.to_string()).collect()
+    } else {
+        columns.to_vec()
+    };
+
+    let rows: Vec<Vec<Value>> = indices
+        .iter()
+        .filter_map(|&i| peers.get(i))
+        .map(|peer| {
+            let value = serde_json::to_value(peer).unwrap_or(Value::Null);
+            columns
+                .iter()
+                .map(|col| column_value(peer, &value, col, history))
+                .collect()
+        })
+        .collect();
+
+    match format {
+        ExportFormat::Csv => {
+            let mut out = csv_line(columns.iter().map(|c| csv_field(c)));
+            for row in &rows {
+                out.push_str(&csv_line(row.iter().map(csv_value)));
+            }
+            out
+        }
+        ExportFormat::Json => {
+            let objects: Vec<Value> = rows
+                .into_iter()
+                .map(|row| {
+                    let map: Map<String, Value> = columns.iter().cloned().zip(row).collect();
+                    Value::Object(map)
+                })
+                .collect();
+            let mut out = serde_json::to_string_pretty(&objects).unwrap_or_default();
+            out.push('\n');
+            out
+        }
+    }
+}
+
+fn column_value(peer: &PeerInfo, value: &Value, column: &str, history: &PeerHistoryStore) -> Value {
+    let rate = match column {
+        "recv_rate" => Some(history.get(peer.id).and_then(|h| h.recv_rate())),
+        "send_rate" => Some(history.get(peer.id).and_then(|h| h.send_rate())),
+        _ => None,
+    };
+    match rate {
+        Some(rate) => rate
+            .map(|r| Value::from(r.round() as u64))
+            .unwrap_or(Value::Null),
+        None => peers_query::get_path(value, column)
+            .cloned()
+            .unwrap_or(Value::Null),
+    }
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    let mut line = fields.collect::<Vec<_>>().join(",");
+    line.push('\n');
+    line
+}
+
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => csv_field(s),
+        other => csv_field(&other.to_string()),
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -3,12 +3,15 @@ use std::collections::BTreeSet;
 
 use serde_json::Value;
 
+use crate::peer_history::RATE_COLUMNS;
 use crate::rpc_types::PeerInfo;
 
 #[derive(Clone, Default)]
 pub struct PeerQuery {
     pub filters: Vec<Condition>,
     pub sort: Option<SortSpec>,
+    /// Columns written by the export command; empty means the default table columns.
+    pub columns: Vec<String>,
 }
 
 #[derive(Clone)]
@@ -43,7 +46,9 @@ pub enum Op {
     Contains,
 }
 
-pub fn apply_command(query: &mut PeerQuery, input: &str) -> Result<(), String> {
+/// Applies one query command. `peers` are the ones currently listed, used to check the
+/// names given to `columns`.
+pub fn apply_command(query: &mut PeerQuery, input: &str, peers: &[PeerInfo]) -> Result<(), String> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return Ok(());
@@ -65,6 +70,37 @@ pub fn apply_command(query: &mut PeerQuery, input: &str) -> Result<(), String> {
         return Ok(());
     }
 
+    if lower == "clear columns" {
+        query.columns.clear();
+        return Ok(());
+    }
+
+    if lower.starts_with("columns ") || lower == "columns" {
+        let columns: Vec<String> = trimmed
+            .get(7..)
+            .unwrap_or_default()
+            .split([',', ' '])
+            .filter(|c| !c.is_empty())
+            .map(|c| c.to_string())
+            .collect();
+        if columns.is_empty() {
+            return Err(
+                "columns needs at least one field; use 'clear columns' for the defaults"
+                    .to_string(),
+            );
+        }
+        let unknown: Vec<&str> = columns
+            .iter()
+            .filter(|c| !is_known_column(c, peers))
+            .map(String::as_str)
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!("unknown column: {}", unknown.join(", ")));
+        }
+        query.columns = columns;
+        return Ok(());
+    }
+
     if lower.starts_with("where ") || lower == "where" {
         let body = trimmed.get(5..).unwrap_or_default().trim();
         if body.is_empty() {
@@ -103,7 +139,7 @@ pub fn apply_command(query: &mut PeerQuery, input: &str) -> Result<(), String> {
         return Ok(());
     }
 
-    Err("unknown command: use where/sort/clear".to_string())
+    Err("unknown command: use where/sort/columns/clear".to_string())
 }
 
 pub fn summary(query: &PeerQuery) -> String {
@@ -123,11 +159,14 @@ pub fn summary(query: &PeerQuery) -> String {
             if sort.descending { "desc" } else { "asc" }
         ));
     }
+    if !query.columns.is_empty() {
+        parts.push(format!("columns {}", query.columns.join(",")));
+    }
     parts.join(" | ")
 }
 
 pub fn is_empty(query: &PeerQuery) -> bool {
-    query.filters.is_empty() && query.sort.is_none()
+    query.filters.is_empty() && query.sort.is_none() && query.columns.is_empty()
 }
 
 pub fn known_fields(peers: &[PeerInfo]) -> Vec<String> {
@@ -166,6 +205,7 @@ pub fn completion_candidates(input: &str, fields: &[String]) -> Vec<String> {
         return vec![
             format!("{leading_ws}where "),
             format!("{leading_ws}sort "),
+            format!("{leading_ws}columns "),
             format!("{leading_ws}clear"),
         ];
     }
@@ -186,7 +226,7 @@ pub fn completion_candidates(input: &str, fields: &[String]) -> Vec<String> {
         } else {
             parts.get(1).copied().unwrap_or("")
         };
-        return ["where", "sort"]
+        return ["where", "sort", "columns"]
             .iter()
             .filter(|w| w.starts_with(&prefix.to_ascii_lowercase()))
             .map(|w| format!("{leading_ws}clear {w}"))
@@ -201,6 +241,10 @@ pub fn completion_candidates(input: &str, fields: &[String]) -> Vec<String> {
         return complete_where(leading_ws, trimmed, fields);
     }
 
+    if first == "columns" {
+        return complete_columns(leading_ws, trimmed, fields);
+    }
+
     Vec::new()
 }
 
@@ -293,7 +337,13 @@ fn split_and_clauses(input: &str) -> Vec<String> {
                 && chars[i + 3].eq_ignore_ascii_case(&'d')
                 && chars[i + 4] == ' ';
             if is_sep {
-                out.push(chars[start..i].iter().collect::<String>().trim().to_string());
+                out.push(
+                    chars[start..i]
+                        .iter()
+                        .collect::<String>()
+                        .trim()
+                        .to_string(),
+                );
                 start = i + 5;
                 i += 5;
                 continue;
@@ -384,6 +434,22 @@ fn parse_literal(raw: &str) -> Literal {
     }
 }
 
+/// A rate column, a `getpeerinfo` field this build knows, or a path present on at least
+/// one listed peer.
+fn is_known_column(column: &str, peers: &[PeerInfo]) -> bool {
+    if RATE_COLUMNS.contains(&column) {
+        return true;
+    }
+    let template = serde_json::to_value(PeerInfo::default()).unwrap_or(Value::Null);
+    std::iter::once(template)
+        .chain(
+            peers
+                .iter()
+                .map(|p| serde_json::to_value(p).unwrap_or(Value::Null)),
+        )
+        .any(|value| get_path(&value, column).is_some())
+}
+
 fn collect_paths(value: &Value, prefix: &str, out: &mut BTreeSet<String>) {
     match value {
         Value::Object(map) => {
@@ -408,7 +474,7 @@ fn collect_paths(value: &Value, prefix: &str, out: &mut BTreeSet<String>) {
 fn keyword_prefixes(leading_ws: &str, prefix: &str) -> Vec<String> {
     let p = prefix.to_ascii_lowercase();
     let mut out = Vec::new();
-    for kw in ["where", "sort", "clear"] {
+    for kw in ["where", "sort", "columns", "clear"] {
         if kw.starts_with(&p) {
             out.push(format!("{leading_ws}{kw}"));
         }
@@ -416,7 +482,12 @@ fn keyword_prefixes(leading_ws: &str, prefix: &str) -> Vec<String> {
     out
 }
 
-fn complete_sort(leading_ws: &str, trimmed: &str, parts: Vec<&str>, fields: &[String]) -> Vec<String> {
+fn complete_sort(
+    leading_ws: &str,
+    trimmed: &str,
+    parts: Vec<&str>,
+    fields: &[String],
+) -> Vec<String> {
     if parts.len() == 1 && trimmed.ends_with(' ') {
         return fields
             .iter()
@@ -452,6 +523,19 @@ fn complete_sort(leading_ws: &str, trimmed: &str, parts: Vec<&str>, fields: &[St
     Vec::new()
 }
 
+fn complete_columns(leading_ws: &str, trimmed: &str, fields: &[String]) -> Vec<String> {
+    let body = trimmed.get(7..).unwrap_or_default().trim_start();
+    let split = body.rfind([',', ' ']).map(|i| i + 1).unwrap_or(0);
+    let (done, partial) = body.split_at(split);
+    fields
+        .iter()
+        .map(String::as_str)
+        .chain(RATE_COLUMNS)
+        .filter(|f| f.starts_with(partial))
+        .map(|f| format!("{leading_ws}columns {done}{f}"))
+        .collect()
+}
+
 fn complete_where(leading_ws: &str, trimmed: &str, fields: &[String]) -> Vec<String> {
     let body = trimmed.strip_prefix("where").unwrap_or("").trim_start();
     if body.is_empty() {
@@ -562,10 +646,11 @@ fn matches_condition(value: &Value, cond: &Condition) -> bool {
 
 fn compare_literal(actual: &Value, rhs: &Literal) -> Option<Ordering> {
     match rhs {
-        Literal::Num(n) => actual
-            .as_f64()
-            .and_then(|a| a.partial_cmp(n))
-            .or_else(|| actual.as_str().and_then(|a| a.parse::<f64>().ok()?.partial_cmp(n))),
+        Literal::Num(n) => actual.as_f64().and_then(|a| a.partial_cmp(n)).or_else(|| {
+            actual
+                .as_str()
+                .and_then(|a| a.parse::<f64>().ok()?.partial_cmp(n))
+        }),
         Literal::Bool(b) => actual.as_bool().map(|a| a.cmp(b)),
         Literal::Str(s) => {
             if let Some(a) = actual.as_str() {
@@ -0,0 +1,1952 @@
+//! In-process BIP174 (v0) / BIP370 (v2) PSBT parser.
+//!
+//! Parsing is deliberately forgiving: only an undecodable encoding or a missing magic
+//! fails outright. Everything else (truncated maps, malformed values, fields that are
+//! not allowed in this version, missing required fields) is recorded as an issue on the
+//! field or map it belongs to so the rest of the PSBT can still be inspected.
+
+use std::collections::{BTreeSet, HashSet};
+
+use base64::Engine;
+use sha2::{Digest, Sha256};
+
+const MAGIC: &[u8] = b"psbt\xff";
+const PROPRIETARY: u64 = 0xfc;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MapKind {
+    Global,
+    Input,
+    Output,
+}
+
+pub struct Field {
+    /// Byte offset of the key within the serialized PSBT.
+    pub offset: usize,
+    pub key_type: u64,
+    pub key_data: Vec<u8>,
+    pub value: Vec<u8>,
+    pub name: &'static str,
+    pub summary: String,
+    pub details: Vec<Node>,
+    pub issues: Vec<String>,
+}
+
+pub struct PsbtMap {
+    pub kind: MapKind,
+    pub index: usize,
+    pub offset: usize,
+    pub fields: Vec<Field>,
+    pub issues: Vec<String>,
+}
+
+pub struct Psbt {
+    pub version: u32,
+    pub global: PsbtMap,
+    pub inputs: Vec<PsbtMap>,
+    pub outputs: Vec<PsbtMap>,
+    /// The unsigned transaction of a v0 PSBT.
+    pub tx: Option<Tx>,
+    pub issues: Vec<String>,
+}
+
+pub struct Tx {
+    pub version: i32,
+    pub inputs: Vec<TxIn>,
+    pub outputs: Vec<TxOut>,
+    pub locktime: u32,
+    pub txid: [u8; 32],
+}
+
+pub struct TxIn {
+    pub prev_txid: [u8; 32],
+    pub vout: u32,
+    pub script_sig: Vec<u8>,
+    pub sequence: u32,
+    pub witness: Vec<Vec<u8>>,
+}
+
+pub struct TxOut {
+    pub value: i64,
+    pub script: Vec<u8>,
+}
+
+/// A node in the structured field view.
+pub struct Node {
+    pub label: String,
+    pub value: String,
+    pub children: Vec<Node>,
+    pub issue: Option<String>,
+}
+
+impl Node {
+    pub fn leaf(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Node {
+            label: label.into(),
+            value: value.into(),
+            children: Vec::new(),
+            issue: None,
+        }
+    }
+
+    pub fn branch(label: impl Into<String>, value: impl Into<String>, children: Vec<Node>) -> Self {
+        Node {
+            label: label.into(),
+            value: value.into(),
+            children,
+            issue: None,
+        }
+    }
+}
+
+/// On-disk PSBT encodings: BIP174 base64 text, hex text, or the raw binary that
+/// hardware wallets and Sparrow write to `.psbt` files.
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    #[default]
+    Base64,
+    Hex,
+    Binary,
+}
+
+impl Encoding {
+    pub fn label(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "base64",
+            Encoding::Hex => "hex",
+            Encoding::Binary => "binary",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Encoding::Base64 => "txt",
+            Encoding::Hex => "hex",
+            Encoding::Binary => "psbt",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            Encoding::Base64 => Encoding::Hex,
+            Encoding::Hex => Encoding::Binary,
+            Encoding::Binary => Encoding::Base64,
+        }
+    }
+}
+
+/// Detects the encoding of a PSBT file and returns its contents as base64 (what the
+/// RPC interface expects). Text that is neither hex nor a PSBT is passed through
+/// as-is so the parser can report what is wrong with it.
+pub fn read_file(bytes: &[u8]) -> Result<(String, Encoding), String> {
+    if bytes.starts_with(MAGIC) {
+        return Ok((base64_encode(bytes), Encoding::Binary));
+    }
+    let text = std::str::from_utf8(bytes)
+        .map_err(|_| "not a PSBT: binary file without psbt magic bytes".to_string())?
+        .trim();
+    let compact: String = text.split_whitespace().collect();
+    if compact.len().is_multiple_of(2)
+        && compact.bytes().all(|b| b.is_ascii_hexdigit())
+        && let Ok(raw) = decode_hex(&compact)
+        && raw.starts_with(MAGIC)
+    {
+        return Ok((base64_encode(&raw), Encoding::Hex));
+    }
+    Ok((text.to_string(), Encoding::Base64))
+}
+
+/// Serializes a base64 or hex PSBT string for writing to disk.
+pub fn write_file(text: &str, encoding: Encoding) -> Result<Vec<u8>, String> {
+    let bytes = decode_text(text)?;
+    if !bytes.starts_with(MAGIC) {
+        return Err("missing PSBT magic bytes (70736274ff)".to_string());
+    }
+    Ok(match encoding {
+        Encoding::Base64 => format!("{}\n", base64_encode(&bytes)).into_bytes(),
+        Encoding::Hex => format!("{}\n", hex(&bytes)).into_bytes(),
+        Encoding::Binary => bytes,
+    })
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Decodes a base64 or hex PSBT and parses its maps.
+pub fn parse(text: &str) -> Result<Psbt, String> {
+    let bytes = decode_text(text)?;
+    parse_bytes(&bytes)
+}
+
+pub fn decode_text(text: &str) -> Result<Vec<u8>, String> {
+    let compact: String = text.split_whitespace().collect();
+    if compact.is_empty() {
+        return Err("empty PSBT".to_string());
+    }
+    if compact.len().is_multiple_of(2) && compact.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return decode_hex(&compact);
+    }
+    base64::engine::general_purpose::STANDARD
+        .decode(compact.as_bytes())
+        .map_err(|e| format!("not valid base64 or hex: {}", e))
+}
+
+pub fn parse_bytes(bytes: &[u8]) -> Result<Psbt, String> {
+    if !bytes.starts_with(MAGIC) {
+        return Err("missing PSBT magic bytes (70736274ff)".to_string());
+    }
+
+    let mut reader = Reader::new(bytes, MAGIC.len());
+    let mut psbt = Psbt {
+        version: 0,
+        global: read_map(&mut reader, MapKind::Global, 0),
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+        tx: None,
+        issues: Vec::new(),
+    };
+
+    if let Some(field) = psbt.global.find(0xfb)
+        && field.value.len() == 4
+    {
+        psbt.version = u32::from_le_bytes(field.value[..4].try_into().unwrap());
+    }
+    if let Some(field) = psbt.global.find(0x00) {
+        psbt.tx = parse_tx(&field.value, false).ok();
+    }
+
+    let counts = match psbt.version {
+        0 => psbt
+            .tx
+            .as_ref()
+            .map(|tx| (tx.inputs.len(), tx.outputs.len())),
+        _ => match (
+            psbt.global
+                .find(0x04)
+                .and_then(|f| read_compact_value(&f.value)),
+            psbt.global
+                .find(0x05)
+                .and_then(|f| read_compact_value(&f.value)),
+        ) {
+            (Some(i), Some(o)) => Some((i as usize, o as usize)),
+            _ => None,
+        },
+    };
+
+    for field in &mut psbt.global.fields {
+        decode_field(MapKind::Global, psbt.version, field);
+    }
+    check_map(&mut psbt.global, psbt.version);
+
+    let Some((input_count, output_count)) = counts else {
+        psbt.issues.push(
+            "cannot determine input/output counts; per-input and per-output maps not parsed"
+                .to_string(),
+        );
+        return Ok(psbt);
+    };
+
+    for index in 0..input_count {
+        if reader.is_empty() {
+            psbt.issues.push(format!(
+                "expected {} input maps, found {}",
+                input_count, index
+            ));
+            break;
+        }
+        let mut map = read_map(&mut reader, MapKind::Input, index);
+        for field in &mut map.fields {
+            decode_field(MapKind::Input, psbt.version, field);
+        }
+        check_map(&mut map, psbt.version);
+        check_input_utxo(&mut map, psbt.tx.as_ref());
+        psbt.inputs.push(map);
+    }
+
+    for index in 0..output_count {
+        if reader.is_empty() {
+            psbt.issues.push(format!(
+                "expected {} output maps, found {}",
+                output_count, index
+            ));
+            break;
+        }
+        let mut map = read_map(&mut reader, MapKind::Output, index);
+        for field in &mut map.fields {
+            decode_field(MapKind::Output, psbt.version, field);
+        }
+        check_map(&mut map, psbt.version);
+        psbt.outputs.push(map);
+    }
+
+    if !reader.is_empty() {
+        psbt.issues.push(format!(
+            "{} trailing bytes after the last output map (byte {})",
+            reader.remaining(),
+            reader.pos
+        ));
+    }
+
+    Ok(psbt)
+}
+
+impl PsbtMap {
+    pub fn find(&self, key_type: u64) -> Option<&Field> {
+        self.fields.iter().find(|f| f.key_type == key_type)
+    }
+
+    fn location(&self) -> String {
+        match self.kind {
+            MapKind::Global => "global".to_string(),
+            MapKind::Input => format!("input {}", self.index),
+            MapKind::Output => format!("output {}", self.index),
+        }
+    }
+}
+
+impl Psbt {
+    /// All issues with a human-readable location prefix.
+    pub fn all_issues(&self) -> Vec<String> {
+        let mut out: Vec<String> = self.issues.clone();
+        for map in std::iter::once(&self.global)
+            .chain(&self.inputs)
+            .chain(&self.outputs)
+        {
+            for issue in &map.issues {
+                out.push(format!(
+                    "{} (byte {}): {}",
+                    map.location(),
+                    map.offset,
+                    issue
+                ));
+            }
+            for field in &map.fields {
+                for issue in &field.issues {
+                    out.push(format!(
+                        "{}, {} (0x{:02x}) at byte {}: {}",
+                        map.location(),
+                        field.name,
+                        field.key_type,
+                        field.offset,
+                        issue
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Previous outpoint spent by input `index`, from the unsigned tx (v0) or the
+    /// PREVIOUS_TXID/OUTPUT_INDEX fields (v2).
+    pub fn prevout(&self, index: usize) -> Option<([u8; 32], u32)> {
+        if let Some(tx) = &self.tx {
+            return tx.inputs.get(index).map(|i| (i.prev_txid, i.vout));
+        }
+        let map = self.inputs.get(index)?;
+        let txid: [u8; 32] = map.find(0x0e)?.value.as_slice().try_into().ok()?;
+        let vout = u32::from_le_bytes(map.find(0x0f)?.value.as_slice().try_into().ok()?);
+        Some((txid, vout))
+    }
+
+    /// Amount and scriptPubKey of the output being spent by input `index`, if a
+    /// witness or non-witness UTXO is present.
+    pub fn spent_output(&self, index: usize) -> Option<TxOut> {
+        let map = self.inputs.get(index)?;
+        if let Some(field) = map.find(0x01)
+            && let Ok(out) = parse_txout(&field.value)
+        {
+            return Some(out);
+        }
+        let field = map.find(0x00)?;
+        let prev = parse_tx(&field.value, true).ok()?;
+        let (_, vout) = self.prevout(index)?;
+        let out = prev.outputs.into_iter().nth(vout as usize)?;
+        Some(out)
+    }
+
+    /// Amount and scriptPubKey of output `index`.
+    pub fn output(&self, index: usize) -> Option<TxOut> {
+        if let Some(tx) = &self.tx {
+            let out = tx.outputs.get(index)?;
+            return Some(TxOut {
+                value: out.value,
+                script: out.script.clone(),
+            });
+        }
+        let map = self.outputs.get(index)?;
+        let value = i64::from_le_bytes(map.find(0x03)?.value.as_slice().try_into().ok()?);
+        let script = map.find(0x04)?.value.clone();
+        Some(TxOut { value, script })
+    }
+
+    pub fn tree(&self) -> Vec<Node> {
+        let mut nodes = Vec::new();
+        let issues = self.all_issues();
+
+        let mut summary = vec![Node::leaf("PSBT version", self.version.to_string())];
+        if let Some(tx) = &self.tx {
+            summary.push(Node::leaf("txid", txid_hex(&tx.txid)));
+            summary.push(Node::leaf("tx version", tx.version.to_string()));
+            summary.push(Node::leaf("locktime", tx.locktime.to_string()));
+        }
+        summary.push(Node::leaf("inputs", self.inputs.len().to_string()));
+        summary.push(Node::leaf("outputs", self.outputs.len().to_string()));
+        nodes.push(Node::branch(
+            "Summary",
+            format!(
+                "v{}, {} in / {} out",
+                self.version,
+                self.inputs.len(),
+                self.outputs.len()
+            ),
+            summary,
+        ));
+
+        if !issues.is_empty() {
+            let mut node = Node::branch(
+                "Issues",
+                issues.len().to_string(),
+                issues.iter().map(|i| Node::leaf("", i.clone())).collect(),
+            );
+            node.issue = Some(format!("{} validation issue(s)", issues.len()));
+            nodes.push(node);
+        }
+
+        nodes.push(map_node(&self.global, "Global".to_string(), String::new()));
+        for (i, map) in self.inputs.iter().enumerate() {
+            let mut value = self
+                .prevout(i)
+                .map(|(txid, vout)| format!("{}:{}", txid_hex(&txid), vout))
+                .unwrap_or_default();
+            if let Some(out) = self.spent_output(i) {
+                value.push_str(&format!("  {}", fmt_sats(out.value)));
+            }
+            nodes.push(map_node(map, format!("Input {}", i), value));
+        }
+        for (i, map) in self.outputs.iter().enumerate() {
+            let value = self
+                .output(i)
+                .map(|out| format!("{}  {}", fmt_sats(out.value), script_type(&out.script)))
+                .unwrap_or_default();
+            nodes.push(map_node(map, format!("Output {}", i), value));
+        }
+        nodes
+    }
+}
+
+/// Field-by-field comparison of two PSBTs, from `a` to `b`. Signatures gained or lost
+/// per input come first, then every added (`+`), removed (`-`) or changed (`~`) field.
+pub fn diff(a: &Psbt, b: &Psbt) -> Vec<String> {
+    let mut out = Vec::new();
+    if a.version != b.version {
+        out.push(format!("~ PSBT version {} -> {}", a.version, b.version));
+    }
+    out.push(if same_unsigned_tx(a, b) {
+        "same unsigned transaction".to_string()
+    } else {
+        "! unsigned transactions differ".to_string()
+    });
+
+    out.push(String::new());
+    out.push("Signatures:".to_string());
+    out.extend(signature_changes(a, b));
+
+    out.push(String::new());
+    out.push("Fields:".to_string());
+    let before_fields = out.len();
+    diff_map("global", Some(&a.global), Some(&b.global), &mut out);
+    for i in 0..a.inputs.len().max(b.inputs.len()) {
+        diff_map(
+            &format!("input {}", i),
+            a.inputs.get(i),
+            b.inputs.get(i),
+            &mut out,
+        );
+    }
+    for i in 0..a.outputs.len().max(b.outputs.len()) {
+        diff_map(
+            &format!("output {}", i),
+            a.outputs.get(i),
+            b.outputs.get(i),
+            &mut out,
+        );
+    }
+    if out.len() == before_fields {
+        out.push("  identical".to_string());
+    }
+    out
+}
+
+/// Per-input signatures gained (`+`) or lost (`-`) going from `a` to `b`.
+pub fn signature_changes(a: &Psbt, b: &Psbt) -> Vec<String> {
+    let mut out = Vec::new();
+    for i in 0..a.inputs.len().max(b.inputs.len()) {
+        let before = a.inputs.get(i).map(signatures).unwrap_or_default();
+        let after = b.inputs.get(i).map(signatures).unwrap_or_default();
+        let gained: Vec<&str> = after.difference(&before).map(String::as_str).collect();
+        let lost: Vec<&str> = before.difference(&after).map(String::as_str).collect();
+        if !gained.is_empty() {
+            out.push(format!("+ input {} gained {}", i, gained.join(", ")));
+        }
+        if !lost.is_empty() {
+            out.push(format!("- input {} lost {}", i, lost.join(", ")));
+        }
+    }
+    if out.is_empty() {
+        out.push("  no signatures added or removed".to_string());
+    }
+    out
+}
+
+fn same_unsigned_tx(a: &Psbt, b: &Psbt) -> bool {
+    if let (Some(x), Some(y)) = (&a.tx, &b.tx) {
+        return x.txid == y.txid;
+    }
+    let prevouts = |p: &Psbt| {
+        (0..p.inputs.len())
+            .map(|i| p.prevout(i))
+            .collect::<Vec<_>>()
+    };
+    let outputs = |p: &Psbt| {
+        (0..p.outputs.len())
+            .map(|i| p.output(i).map(|o| (o.value, o.script)))
+            .collect::<Vec<_>>()
+    };
+    prevouts(a) == prevouts(b) && outputs(a) == outputs(b)
+}
+
+/// Signatures (partial or final) present in an input map, one description each.
+fn signatures(map: &PsbtMap) -> BTreeSet<String> {
+    map.fields
+        .iter()
+        .filter_map(|f| {
+            let key = short_hex(&hex(&f.key_data));
+            match f.key_type {
+                0x02 => Some(format!("partial sig {}", key)),
+                0x07 => Some("final scriptSig".to_string()),
+                0x08 => Some("final witness".to_string()),
+                0x13 => Some("taproot key-path sig".to_string()),
+                0x14 => Some(format!("taproot script-path sig {}", key)),
+                0x1c => Some(format!("musig2 partial sig {}", key)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+fn same_key<'a>(fields: &'a [Field], field: &Field) -> Option<&'a Field> {
+    fields
+        .iter()
+        .find(|f| f.key_type == field.key_type && f.key_data == field.key_data)
+}
+
+fn diff_map(label: &str, a: Option<&PsbtMap>, b: Option<&PsbtMap>, out: &mut Vec<String>) {
+    match (a, b) {
+        (None, Some(_)) => out.push(format!("+ {} added", label)),
+        (Some(_), None) => out.push(format!("- {} removed", label)),
+        _ => {}
+    }
+    let (a, b) = (
+        a.map(|m| m.fields.as_slice()).unwrap_or_default(),
+        b.map(|m| m.fields.as_slice()).unwrap_or_default(),
+    );
+    let describe = |f: &Field| {
+        if f.key_data.is_empty() {
+            format!("{} {}", label, f.name)
+        } else {
+            format!("{} {} {}", label, f.name, short_hex(&hex(&f.key_data)))
+        }
+    };
+    for f in a {
+        match same_key(b, f) {
+            None => out.push(format!("- {}: {}", describe(f), f.summary)),
+            Some(g) if g.value != f.value => {
+                out.push(format!("~ {}: {} -> {}", describe(f), f.summary, g.summary));
+            }
+            Some(_) => {}
+        }
+    }
+    for f in b {
+        if same_key(a, f).is_none() {
+            out.push(format!("+ {}: {}", describe(f), f.summary));
+        }
+    }
+}
+
+fn map_node(map: &PsbtMap, label: String, value: String) -> Node {
+    let fields = map
+        .fields
+        .iter()
+        .map(|f| {
+            let mut children: Vec<Node> = f.details.iter().map(clone_node).collect();
+            if !f.key_data.is_empty() {
+                children.push(Node::leaf("key data", hex(&f.key_data)));
+            }
+            children.push(Node::leaf(
+                "value",
+                format!("{} ({} bytes)", hex(&f.value), f.value.len()),
+            ));
+            let label = if f.name == "unknown" {
+                format!("unknown type 0x{:02x}", f.key_type)
+            } else {
+                f.name.to_string()
+            };
+            let mut node = Node::branch(label, f.summary.clone(), children);
+            if !f.issues.is_empty() {
+                node.issue = Some(f.issues.join("; "));
+            }
+            node
+        })
+        .collect();
+    let value = if value.is_empty() {
+        format!("{} fields", map.fields.len())
+    } else {
+        value
+    };
+    let mut node = Node::branch(label, value, fields);
+    if !map.issues.is_empty() {
+        node.issue = Some(map.issues.join("; "));
+    }
+    node
+}
+
+fn clone_node(node: &Node) -> Node {
+    Node {
+        label: node.label.clone(),
+        value: node.value.clone(),
+        children: node.children.iter().map(clone_node).collect(),
+        issue: node.issue.clone(),
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Self {
+        Reader { data, pos }
+    }
+
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        if self.remaining() < n {
+            return Err(format!(
+                "need {} bytes at offset {}, only {} left",
+                n,
+                self.pos,
+                self.remaining()
+            ));
+        }
+        let out = &self.data[self.pos..self.pos + n];
+        self.pos += n;
+        Ok(out)
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn i64(&mut self) -> Result<i64, String> {
+        Ok(i64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn compact(&mut self) -> Result<u64, String> {
+        match self.u8()? {
+            0xfd => Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()) as u64),
+            0xfe => Ok(self.u32()? as u64),
+            0xff => Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap())),
+            n => Ok(n as u64),
+        }
+    }
+
+    fn var_bytes(&mut self) -> Result<&'a [u8], String> {
+        let len = self.compact()?;
+        self.take(usize::try_from(len).map_err(|_| "length overflows".to_string())?)
+    }
+}
+
+fn read_map(reader: &mut Reader, kind: MapKind, index: usize) -> PsbtMap {
+    let mut map = PsbtMap {
+        kind,
+        index,
+        offset: reader.pos,
+        fields: Vec::new(),
+        issues: Vec::new(),
+    };
+
+    loop {
+        let offset = reader.pos;
+        let key = match reader.var_bytes() {
+            Ok(key) => key,
+            Err(e) => {
+                map.issues.push(format!("truncated key: {}", e));
+                reader.pos = reader.data.len();
+                break;
+            }
+        };
+        if key.is_empty() {
+            break;
+        }
+        let mut key_reader = Reader::new(key, 0);
+        let Ok(key_type) = key_reader.compact() else {
+            map.issues
+                .push(format!("malformed key type at byte {}", offset));
+            reader.pos = reader.data.len();
+            break;
+        };
+        let key_data = key[key_reader.pos..].to_vec();
+        let value = match reader.var_bytes() {
+            Ok(value) => value.to_vec(),
+            Err(e) => {
+                map.issues
+                    .push(format!("truncated value for key at byte {}: {}", offset, e));
+                reader.pos = reader.data.len();
+                break;
+            }
+        };
+        map.fields.push(Field {
+            offset,
+            key_type,
+            key_data,
+            value,
+            name: field_name(kind, key_type),
+            summary: String::new(),
+            details: Vec::new(),
+            issues: Vec::new(),
+        });
+    }
+
+    let mut seen = HashSet::new();
+    for field in &mut map.fields {
+        if !seen.insert((field.key_type, field.key_data.clone())) {
+            field.issues.push("duplicate key".to_string());
+        }
+    }
+    map
+}
+
+fn field_name(kind: MapKind, key_type: u64) -> &'static str {
+    match (kind, key_type) {
+        (MapKind::Global, 0x00) => "PSBT_GLOBAL_UNSIGNED_TX",
+        (MapKind::Global, 0x01) => "PSBT_GLOBAL_XPUB",
+        (MapKind::Global, 0x02) => "PSBT_GLOBAL_TX_VERSION",
+        (MapKind::Global, 0x03) => "PSBT_GLOBAL_FALLBACK_LOCKTIME",
+        (MapKind::Global, 0x04) => "PSBT_GLOBAL_INPUT_COUNT",
+        (MapKind::Global, 0x05) => "PSBT_GLOBAL_OUTPUT_COUNT",
+        (MapKind::Global, 0x06) => "PSBT_GLOBAL_TX_MODIFIABLE",
+        (MapKind::Global, 0xfb) => "PSBT_GLOBAL_VERSION",
+        (MapKind::Global, PROPRIETARY) => "PSBT_GLOBAL_PROPRIETARY",
+        (MapKind::Input, 0x00) => "PSBT_IN_NON_WITNESS_UTXO",
+        (MapKind::Input, 0x01) => "PSBT_IN_WITNESS_UTXO",
+        (MapKind::Input, 0x02) => "PSBT_IN_PARTIAL_SIG",
+        (MapKind::Input, 0x03) => "PSBT_IN_SIGHASH_TYPE",
+        (MapKind::Input, 0x04) => "PSBT_IN_REDEEM_SCRIPT",
+        (MapKind::Input, 0x05) => "PSBT_IN_WITNESS_SCRIPT",
+        (MapKind::Input, 0x06) => "PSBT_IN_BIP32_DERIVATION",
+        (MapKind::Input, 0x07) => "PSBT_IN_FINAL_SCRIPTSIG",
+        (MapKind::Input, 0x08) => "PSBT_IN_FINAL_SCRIPTWITNESS",
+        (MapKind::Input, 0x09) => "PSBT_IN_POR_COMMITMENT",
+        (MapKind::Input, 0x0a) => "PSBT_IN_RIPEMD160",
+        (MapKind::Input, 0x0b) => "PSBT_IN_SHA256",
+        (MapKind::Input, 0x0c) => "PSBT_IN_HASH160",
+        (MapKind::Input, 0x0d) => "PSBT_IN_HASH256",
+        (MapKind::Input, 0x0e) => "PSBT_IN_PREVIOUS_TXID",
+        (MapKind::Input, 0x0f) => "PSBT_IN_OUTPUT_INDEX",
+        (MapKind::Input, 0x10) => "PSBT_IN_SEQUENCE",
+        (MapKind::Input, 0x11) => "PSBT_IN_REQUIRED_TIME_LOCKTIME",
+        (MapKind::Input, 0x12) => "PSBT_IN_REQUIRED_HEIGHT_LOCKTIME",
+        (MapKind::Input, 0x13) => "PSBT_IN_TAP_KEY_SIG",
+        (MapKind::Input, 0x14) => "PSBT_IN_TAP_SCRIPT_SIG",
+        (MapKind::Input, 0x15) => "PSBT_IN_TAP_LEAF_SCRIPT",
+        (MapKind::Input, 0x16) => "PSBT_IN_TAP_BIP32_DERIVATION",
+        (MapKind::Input, 0x17) => "PSBT_IN_TAP_INTERNAL_KEY",
+        (MapKind::Input, 0x18) => "PSBT_IN_TAP_MERKLE_ROOT",
+        (MapKind::Input, 0x1a) => "PSBT_IN_MUSIG2_PARTICIPANT_PUBKEYS",
+        (MapKind::Input, 0x1b) => "PSBT_IN_MUSIG2_PUB_NONCE",
+        (MapKind::Input, 0x1c) => "PSBT_IN_MUSIG2_PARTIAL_SIG",
+        (MapKind::Input, PROPRIETARY) => "PSBT_IN_PROPRIETARY",
+        (MapKind::Output, 0x00) => "PSBT_OUT_REDEEM_SCRIPT",
+        (MapKind::Output, 0x01) => "PSBT_OUT_WITNESS_SCRIPT",
+        (MapKind::Output, 0x02) => "PSBT_OUT_BIP32_DERIVATION",
+        (MapKind::Output, 0x03) => "PSBT_OUT_AMOUNT",
+        (MapKind::Output, 0x04) => "PSBT_OUT_SCRIPT",
+        (MapKind::Output, 0x05) => "PSBT_OUT_TAP_INTERNAL_KEY",
+        (MapKind::Output, 0x06) => "PSBT_OUT_TAP_TREE",
+        (MapKind::Output, 0x07) => "PSBT_OUT_TAP_BIP32_DERIVATION",
+        (MapKind::Output, 0x08) => "PSBT_OUT_MUSIG2_PARTICIPANT_PUBKEYS",
+        (MapKind::Output, PROPRIETARY) => "PSBT_OUT_PROPRIETARY",
+        _ => "unknown",
+    }
+}
+
+/// Whether a known key type carries key data. `None` for unknown types.
+fn expects_key_data(kind: MapKind, key_type: u64) -> Option<bool> {
+    if field_name(kind, key_type) == "unknown" {
+        return None;
+    }
+    Some(match kind {
+        MapKind::Global => matches!(key_type, 0x01 | PROPRIETARY),
+        MapKind::Input => matches!(
+            key_type,
+            0x02 | 0x06 | 0x0a..=0x0d | 0x14..=0x16 | 0x1a..=0x1c | PROPRIETARY
+        ),
+        MapKind::Output => matches!(key_type, 0x02 | 0x07 | 0x08 | PROPRIETARY),
+    })
+}
+
+/// Whether a known key type is defined for `version` (v0 and v2 differ in which
+/// fields carry the transaction).
+fn allowed_in_version(kind: MapKind, key_type: u64, version: u32) -> bool {
+    match (kind, version) {
+        (MapKind::Global, 0) => !matches!(key_type, 0x02..=0x06),
+        (MapKind::Global, _) => key_type != 0x00,
+        (MapKind::Input, 0) => !matches!(key_type, 0x0e..=0x12),
+        (MapKind::Output, 0) => !matches!(key_type, 0x03 | 0x04),
+        _ => true,
+    }
+}
+
+fn decode_field(kind: MapKind, version: u32, field: &mut Field) {
+    match expects_key_data(kind, field.key_type) {
+        Some(false) if !field.key_data.is_empty() => {
+            field.issues.push("unexpected key data".to_string())
+        }
+        Some(true) if field.key_data.is_empty() => {
+            field.issues.push("missing key data".to_string())
+        }
+        _ => {}
+    }
+    if !allowed_in_version(kind, field.key_type, version) {
+        field
+            .issues
+            .push(format!("not allowed in PSBT version {}", version));
+    }
+
+    match decode_value(kind, field.key_type, &field.key_data, &field.value) {
+        Ok((summary, details)) => {
+            field.summary = summary;
+            field.details = details;
+        }
+        Err(e) => {
+            field.summary = format!("{} bytes", field.value.len());
+            field.issues.push(e);
+        }
+    }
+}
+
+type Decoded = (String, Vec<Node>);
+
+fn decode_value(kind: MapKind, key_type: u64, key: &[u8], value: &[u8]) -> Result<Decoded, String> {
+    if key_type == PROPRIETARY {
+        return decode_proprietary(key, value);
+    }
+    match (kind, key_type) {
+        (MapKind::Global, 0x00) => {
+            let tx = parse_tx(value, false).map_err(|e| format!("invalid transaction: {}", e))?;
+            if tx
+                .inputs
+                .iter()
+                .any(|i| !i.script_sig.is_empty() || !i.witness.is_empty())
+            {
+                return Err("unsigned tx must have empty scriptSigs and witnesses".to_string());
+            }
+            Ok((
+                format!(
+                    "txid {}, {} in / {} out",
+                    short_hex(&txid_hex(&tx.txid)),
+                    tx.inputs.len(),
+                    tx.outputs.len()
+                ),
+                tx_nodes(&tx),
+            ))
+        }
+        (MapKind::Global, 0x01) => {
+            if key.len() != 78 {
+                return Err(format!("xpub key data must be 78 bytes, got {}", key.len()));
+            }
+            let origin = key_origin(value)?;
+            let xpub = base58check(key);
+            Ok((
+                format!("{} {}", origin, short_hex(&xpub)),
+                vec![
+                    Node::leaf("xpub", xpub),
+                    Node::leaf("depth", key[4].to_string()),
+                    Node::leaf("origin", origin),
+                ],
+            ))
+        }
+        (MapKind::Global, 0x02 | 0x03 | 0xfb) | (MapKind::Input, 0x0f) => {
+            let n = u32_value(value)?;
+            Ok((n.to_string(), Vec::new()))
+        }
+        (MapKind::Input, 0x10) => {
+            let n = u32_value(value)?;
+            Ok((format!("0x{:08x}", n), Vec::new()))
+        }
+        (MapKind::Output, 0x03) => {
+            let sats = i64::from_le_bytes(
+                value
+                    .try_into()
+                    .map_err(|_| format!("expected 8 bytes, got {}", value.len()))?,
+            );
+            if sats < 0 {
+                return Err(format!("negative amount {}", sats));
+            }
+            Ok((fmt_sats(sats), Vec::new()))
+        }
+        (MapKind::Global, 0x04 | 0x05) => {
+            let n = read_compact_value(value).ok_or("invalid compact size")?;
+            Ok((n.to_string(), Vec::new()))
+        }
+        (MapKind::Global, 0x06) => {
+            let [flags] = value else {
+                return Err(format!("expected 1 byte, got {}", value.len()));
+            };
+            let mut names = Vec::new();
+            if flags & 1 != 0 {
+                names.push("inputs");
+            }
+            if flags & 2 != 0 {
+                names.push("outputs");
+            }
+            if flags & 4 != 0 {
+                names.push("has SIGHASH_SINGLE");
+            }
+            Ok((format!("0x{:02x} {}", flags, names.join(", ")), Vec::new()))
+        }
+        (MapKind::Input, 0x00) => {
+            let tx = parse_tx(value, true).map_err(|e| format!("invalid transaction: {}", e))?;
+            Ok((
+                format!("txid {}", short_hex(&txid_hex(&tx.txid))),
+                tx_nodes(&tx),
+            ))
+        }
+        (MapKind::Input, 0x01) => {
+            let out = parse_txout(value)?;
+            Ok((
+                format!("{}  {}", fmt_sats(out.value), script_type(&out.script)),
+                vec![
+                    Node::leaf("amount", fmt_sats(out.value)),
+                    script_node("scriptPubKey", &out.script),
+                ],
+            ))
+        }
+        (MapKind::Input, 0x02) => {
+            check_pubkey(key)?;
+            let (sighash, sig) = value.split_last().ok_or("empty signature")?;
+            check_der(sig)?;
+            Ok((
+                format!("{} {}", short_hex(&hex(key)), sighash_name(*sighash as u32)),
+                vec![
+                    Node::leaf("pubkey", hex(key)),
+                    Node::leaf("signature", hex(sig)),
+                    Node::leaf("sighash", sighash_name(*sighash as u32)),
+                ],
+            ))
+        }
+        (MapKind::Input, 0x03) => {
+            let n = u32_value(value)?;
+            Ok((sighash_name(n), Vec::new()))
+        }
+        (MapKind::Input, 0x04 | 0x05 | 0x07) | (MapKind::Output, 0x00 | 0x01) => Ok((
+            format!("{} ({} bytes)", script_type(value), value.len()),
+            vec![script_node("script", value)],
+        )),
+        (MapKind::Input, 0x06) | (MapKind::Output, 0x02) => {
+            check_pubkey(key)?;
+            let origin = key_origin(value)?;
+            Ok((
+                format!("{} {}", short_hex(&hex(key)), origin),
+                vec![Node::leaf("pubkey", hex(key)), Node::leaf("origin", origin)],
+            ))
+        }
+        (MapKind::Input, 0x08) => {
+            let mut r = Reader::new(value, 0);
+            let stack = read_witness(&mut r)?;
+            expect_end(&r)?;
+            Ok((
+                format!("{} stack items", stack.len()),
+                stack
+                    .iter()
+                    .enumerate()
+                    .map(|(i, item)| Node::leaf(format!("#{}", i), hex(item)))
+                    .collect(),
+            ))
+        }
+        (MapKind::Input, 0x09) => {
+            let text = String::from_utf8(value.to_vec()).map_err(|_| "not valid UTF-8")?;
+            Ok((text, Vec::new()))
+        }
+        (MapKind::Input, 0x0a..=0x0d) => decode_preimage(key_type, key, value),
+        (MapKind::Input, 0x0e) => {
+            let txid: [u8; 32] = value
+                .try_into()
+                .map_err(|_| format!("expected 32 bytes, got {}", value.len()))?;
+            Ok((txid_hex(&txid), Vec::new()))
+        }
+        (MapKind::Input, 0x11) => {
+            let n = u32_value(value)?;
+            if n < 500_000_000 {
+                return Err(format!("time locktime {} is below 500000000", n));
+            }
+            Ok((
+                format!("{} ({})", n, crate::format::fmt_unix_time(n as u64)),
+                Vec::new(),
+            ))
+        }
+        (MapKind::Input, 0x12) => {
+            let n = u32_value(value)?;
+            if n == 0 || n >= 500_000_000 {
+                return Err(format!("height locktime {} out of range", n));
+            }
+            Ok((format!("height {}", n), Vec::new()))
+        }
+        (MapKind::Input, 0x13) => {
+            let sighash = schnorr_sighash(value)?;
+            Ok((
+                format!("schnorr sig {}", sighash),
+                vec![
+                    Node::leaf("signature", hex(value)),
+                    Node::leaf("sighash", sighash),
+                ],
+            ))
+        }
+        (MapKind::Input, 0x14) => {
+            if key.len() != 64 {
+                return Err(format!(
+                    "key data must be xonly pubkey + leaf hash (64 bytes), got {}",
+                    key.len()
+                ));
+            }
+            let sighash = schnorr_sighash(value)?;
+            Ok((
+                format!("{} {}", short_hex(&hex(&key[..32])), sighash),
+                vec![
+                    Node::leaf("xonly pubkey", hex(&key[..32])),
+                    Node::leaf("leaf hash", hex(&key[32..])),
+                    Node::leaf("signature", hex(value)),
+                    Node::leaf("sighash", sighash),
+                ],
+            ))
+        }
+        (MapKind::Input, 0x15) => {
+            if key.len() < 33 || !(key.len() - 33).is_multiple_of(32) || (key.len() - 33) / 32 > 128
+            {
+                return Err(format!("invalid control block length {}", key.len()));
+            }
+            let (leaf_version, script) = value.split_last().ok_or("empty leaf script")?;
+            let depth = (key.len() - 33) / 32;
+            Ok((
+                format!(
+                    "leaf 0x{:02x}, depth {}, {} bytes",
+                    leaf_version,
+                    depth,
+                    script.len()
+                ),
+                vec![
+                    Node::leaf("leaf version", format!("0x{:02x}", leaf_version)),
+                    Node::leaf("internal key", hex(&key[1..33])),
+                    Node::leaf("output key parity", (key[0] & 1).to_string()),
+                    Node::branch(
+                        "merkle path",
+                        depth.to_string(),
+                        key[33..]
+                            .chunks(32)
+                            .enumerate()
+                            .map(|(i, h)| Node::leaf(format!("#{}", i), hex(h)))
+                            .collect(),
+                    ),
+                    script_node("script", script),
+                ],
+            ))
+        }
+        (MapKind::Input, 0x16) | (MapKind::Output, 0x07) => {
+            if key.len() != 32 {
+                return Err(format!("xonly pubkey must be 32 bytes, got {}", key.len()));
+            }
+            let mut r = Reader::new(value, 0);
+            let count = r.compact()? as usize;
+            let hashes = r.take(count.checked_mul(32).ok_or("too many leaf hashes")?)?;
+            let origin = key_origin(&value[r.pos..])?;
+            Ok((
+                format!("{} {} ({} leaves)", short_hex(&hex(key)), origin, count),
+                vec![
+                    Node::leaf("xonly pubkey", hex(key)),
+                    Node::leaf("origin", origin),
+                    Node::branch(
+                        "leaf hashes",
+                        count.to_string(),
+                        hashes.chunks(32).map(|h| Node::leaf("", hex(h))).collect(),
+                    ),
+                ],
+            ))
+        }
+        (MapKind::Input, 0x17 | 0x18) | (MapKind::Output, 0x05) => {
+            if value.len() != 32 {
+                return Err(format!("expected 32 bytes, got {}", value.len()));
+            }
+            Ok((hex(value), Vec::new()))
+        }
+        (MapKind::Input, 0x1a) | (MapKind::Output, 0x08) => {
+            check_compressed_pubkey(key)?;
+            if value.is_empty() || !value.len().is_multiple_of(33) {
+                return Err(format!(
+                    "participant list length {} is not a multiple of 33",
+                    value.len()
+                ));
+            }
+            Ok((
+                format!("{} participants", value.len() / 33),
+                std::iter::once(Node::leaf("aggregate key", hex(key)))
+                    .chain(value.chunks(33).map(|k| Node::leaf("participant", hex(k))))
+                    .collect(),
+            ))
+        }
+        (MapKind::Input, 0x1b | 0x1c) => {
+            if key.len() != 66 && key.len() != 98 {
+                return Err(format!(
+                    "key data must be 66 or 98 bytes, got {}",
+                    key.len()
+                ));
+            }
+            let expected = if key_type == 0x1b { 66 } else { 32 };
+            if value.len() != expected {
+                return Err(format!("expected {} bytes, got {}", expected, value.len()));
+            }
+            let mut details = vec![
+                Node::leaf("participant", hex(&key[..33])),
+                Node::leaf("aggregate key", hex(&key[33..66])),
+            ];
+            if key.len() == 98 {
+                details.push(Node::leaf("leaf hash", hex(&key[66..])));
+            }
+            details.push(Node::leaf(
+                if key_type == 0x1b {
+                    "nonce"
+                } else {
+                    "partial sig"
+                },
+                hex(value),
+            ));
+            Ok((short_hex(&hex(&key[..33])), details))
+        }
+        (MapKind::Output, 0x04) => Ok((
+            format!("{} ({} bytes)", script_type(value), value.len()),
+            vec![script_node("scriptPubKey", value)],
+        )),
+        (MapKind::Output, 0x06) => {
+            let mut r = Reader::new(value, 0);
+            let mut leaves = Vec::new();
+            while !r.is_empty() {
+                let depth = r.u8()?;
+                let leaf_version = r.u8()?;
+                let script = r.var_bytes()?;
+                if depth > 128 {
+                    return Err(format!("leaf depth {} exceeds 128", depth));
+                }
+                leaves.push(Node::branch(
+                    format!("depth {}", depth),
+                    format!("leaf 0x{:02x}, {} bytes", leaf_version, script.len()),
+                    vec![script_node("script", script)],
+                ));
+            }
+            Ok((format!("{} leaves", leaves.len()), leaves))
+        }
+        _ => Ok((format!("{} bytes", value.len()), Vec::new())),
+    }
+}
+
+fn decode_proprietary(key: &[u8], value: &[u8]) -> Result<Decoded, String> {
+    let mut r = Reader::new(key, 0);
+    let identifier = r.var_bytes()?;
+    let subtype = r.compact()?;
+    let rest = &key[r.pos..];
+    let ident = String::from_utf8(identifier.to_vec()).unwrap_or_else(|_| hex(identifier));
+    Ok((
+        format!("{} subtype {} ({} bytes)", ident, subtype, value.len()),
+        vec![
+            Node::leaf("identifier", ident),
+            Node::leaf("subtype", subtype.to_string()),
+            Node::leaf("subkey data", hex(rest)),
+        ],
+    ))
+}
+
+fn decode_preimage(key_type: u64, key: &[u8], value: &[u8]) -> Result<Decoded, String> {
+    let expected = if matches!(key_type, 0x0a | 0x0c) {
+        20
+    } else {
+        32
+    };
+    if key.len() != expected {
+        return Err(format!(
+            "hash must be {} bytes, got {}",
+            expected,
+            key.len()
+        ));
+    }
+    let verified = match key_type {
+        0x0b => Some(Sha256::digest(value).as_slice() == key),
+        0x0d => Some(sha256d(value).as_slice() == key),
+        _ => None,
+    };
+    if verified == Some(false) {
+        return Err("preimage does not hash to key".to_string());
+    }
+    Ok((
+        format!("{} ({} byte preimage)", short_hex(&hex(key)), value.len()),
+        vec![
+            Node::leaf("hash", hex(key)),
+            Node::leaf("preimage", hex(value)),
+            Node::leaf(
+                "verified",
+                match verified {
+                    Some(_) => "yes",
+                    None => "not checked",
+                },
+            ),
+        ],
+    ))
+}
+
+fn check_map(map: &mut PsbtMap, version: u32) {
+    let required: &[(u64, &str)] = match (map.kind, version) {
+        (MapKind::Global, 0) => &[(0x00, "PSBT_GLOBAL_UNSIGNED_TX")],
+        (MapKind::Global, _) => &[
+            (0x02, "PSBT_GLOBAL_TX_VERSION"),
+            (0x04, "PSBT_GLOBAL_INPUT_COUNT"),
+            (0x05, "PSBT_GLOBAL_OUTPUT_COUNT"),
+        ],
+        (MapKind::Input, 0) => &[],
+        (MapKind::Input, _) => &[
+            (0x0e, "PSBT_IN_PREVIOUS_TXID"),
+            (0x0f, "PSBT_IN_OUTPUT_INDEX"),
+        ],
+        (MapKind::Output, 0) => &[],
+        (MapKind::Output, _) => &[(0x03, "PSBT_OUT_AMOUNT"), (0x04, "PSBT_OUT_SCRIPT")],
+    };
+    for (key_type, name) in required {
+        if map.find(*key_type).is_none() {
+            map.issues.push(format!("missing required {}", name));
+        }
+    }
+    if map.kind == MapKind::Global && version != 0 && version != 2 {
+        map.issues
+            .push(format!("unsupported PSBT version {}", version));
+    }
+}
+
+fn check_input_utxo(map: &mut PsbtMap, unsigned: Option<&Tx>) {
+    let prevout = match unsigned {
+        Some(tx) => tx.inputs.get(map.index).map(|i| (i.prev_txid, i.vout)),
+        None => map
+            .find(0x0e)
+            .and_then(|f| <[u8; 32]>::try_from(f.value.as_slice()).ok())
+            .zip(map.find(0x0f).and_then(|f| u32_value(&f.value).ok())),
+    };
+    let Some((txid, vout)) = prevout else {
+        return;
+    };
+    let witness_utxo = map.find(0x01).and_then(|f| parse_txout(&f.value).ok());
+    let Some(field) = map.fields.iter_mut().find(|f| f.key_type == 0x00) else {
+        return;
+    };
+    let Ok(prev) = parse_tx(&field.value, true) else {
+        return;
+    };
+    if prev.txid != txid {
+        field.issues.push(format!(
+            "txid {} does not match the spent outpoint {}",
+            txid_hex(&prev.txid),
+            txid_hex(&txid)
+        ));
+        return;
+    }
+    match prev.outputs.get(vout as usize) {
+        None => field
+            .issues
+            .push(format!("spent output index {} does not exist", vout)),
+        Some(out) => {
+            if let Some(w) = witness_utxo
+                && (w.value != out.value || w.script != out.script)
+            {
+                field
+                    .issues
+                    .push("disagrees with PSBT_IN_WITNESS_UTXO".to_string());
+            }
+        }
+    }
+}
+
+pub fn parse_tx(bytes: &[u8], allow_witness: bool) -> Result<Tx, String> {
+    let mut r = Reader::new(bytes, 0);
+    let version = r.u32()? as i32;
+    // The global unsigned tx is always read without witness data, as Core does: a
+    // transaction with no inputs and one output starts with the same `00 01`.
+    let segwit = allow_witness && bytes.len() > 6 && bytes[4] == 0x00 && bytes[5] == 0x01;
+    if segwit {
+        r.take(2)?;
+    }
+    let body_start = r.pos;
+
+    let input_count = r.compact()?;
+    let mut inputs = Vec::new();
+    for _ in 0..input_count {
+        let prev_txid: [u8; 32] = r.take(32)?.try_into().unwrap();
+        let vout = r.u32()?;
+        let script_sig = r.var_bytes()?.to_vec();
+        let sequence = r.u32()?;
+        inputs.push(TxIn {
+            prev_txid,
+            vout,
+            script_sig,
+            sequence,
+            witness: Vec::new(),
+        });
+    }
+    let output_count = r.compact()?;
+    let mut outputs = Vec::new();
+    for _ in 0..output_count {
+        let value = r.i64()?;
+        let script = r.var_bytes()?.to_vec();
+        outputs.push(TxOut { value, script });
+    }
+    let body_end = r.pos;
+
+    if segwit {
+        for input in &mut inputs {
+            input.witness = read_witness(&mut r)?;
+        }
+    }
+    let locktime = r.u32()?;
+    expect_end(&r)?;
+
+    let mut legacy = Vec::with_capacity(bytes.len());
+    legacy.extend_from_slice(&bytes[..4]);
+    legacy.extend_from_slice(&bytes[body_start..body_end]);
+    legacy.extend_from_slice(&locktime.to_le_bytes());
+
+    Ok(Tx {
+        version,
+        inputs,
+        outputs,
+        locktime,
+        txid: sha256d(&legacy),
+    })
+}
+
+fn parse_txout(bytes: &[u8]) -> Result<TxOut, String> {
+    let mut r = Reader::new(bytes, 0);
+    let value = r.i64()?;
+    let script = r.var_bytes()?.to_vec();
+    expect_end(&r)?;
+    Ok(TxOut { value, script })
+}
+
+fn read_witness(r: &mut Reader) -> Result<Vec<Vec<u8>>, String> {
+    let count = r.compact()?;
+    let mut stack = Vec::new();
+    for _ in 0..count {
+        stack.push(r.var_bytes()?.to_vec());
+    }
+    Ok(stack)
+}
+
+fn expect_end(r: &Reader) -> Result<(), String> {
+    if r.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("{} unexpected trailing bytes", r.remaining()))
+    }
+}
+
+fn read_compact_value(bytes: &[u8]) -> Option<u64> {
+    let mut r = Reader::new(bytes, 0);
+    let n = r.compact().ok()?;
+    r.is_empty().then_some(n)
+}
+
+fn u32_value(value: &[u8]) -> Result<u32, String> {
+    let bytes: [u8; 4] = value
+        .try_into()
+        .map_err(|_| format!("expected 4 bytes, got {}", value.len()))?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn check_pubkey(key: &[u8]) -> Result<(), String> {
+    match (key.len(), key.first()) {
+        (33, Some(0x02 | 0x03)) | (65, Some(0x04)) => Ok(()),
+        (len, _) => Err(format!("invalid pubkey ({} bytes)", len)),
+    }
+}
+
+fn check_compressed_pubkey(key: &[u8]) -> Result<(), String> {
+    match (key.len(), key.first()) {
+        (33, Some(0x02 | 0x03)) => Ok(()),
+        (len, _) => Err(format!("invalid compressed pubkey ({} bytes)", len)),
+    }
+}
+
+/// Structural DER check (BIP66), without validating the curve values.
+fn check_der(sig: &[u8]) -> Result<(), String> {
+    let ok = sig.len() >= 8
+        && sig.len() <= 72
+        && sig[0] == 0x30
+        && sig[1] as usize == sig.len() - 2
+        && sig[2] == 0x02
+        && {
+            let r_len = sig[3] as usize;
+            5 + r_len < sig.len()
+                && sig[4 + r_len] == 0x02
+                && 6 + r_len + sig[5 + r_len] as usize == sig.len()
+        };
+    if ok {
+        Ok(())
+    } else {
+        Err("signature is not strict DER".to_string())
+    }
+}
+
+fn schnorr_sighash(sig: &[u8]) -> Result<String, String> {
+    match sig.len() {
+        64 => Ok("DEFAULT".to_string()),
+        65 if sig[64] != 0 => Ok(sighash_name(sig[64] as u32)),
+        65 => Err("65-byte schnorr signature must not use sighash 0x00".to_string()),
+        len => Err(format!(
+            "schnorr signature must be 64 or 65 bytes, got {}",
+            len
+        )),
+    }
+}
+
+fn sighash_name(n: u32) -> String {
+    let base = match n & 0x1f {
+        0 if n == 0 => "DEFAULT",
+        1 => "ALL",
+        2 => "NONE",
+        3 => "SINGLE",
+        _ => return format!("0x{:02x}", n),
+    };
+    if n & 0x80 != 0 {
+        format!("{}|ANYONECANPAY", base)
+    } else {
+        base.to_string()
+    }
+}
+
+fn key_origin(value: &[u8]) -> Result<String, String> {
+    if value.len() < 4 || !value.len().is_multiple_of(4) {
+        return Err(format!("key origin length {} is not 4 + 4n", value.len()));
+    }
+    let mut out = format!("[{}]m", hex(&value[..4]));
+    for chunk in value[4..].chunks(4) {
+        let index = u32::from_le_bytes(chunk.try_into().unwrap());
+        if index & 0x8000_0000 != 0 {
+            out.push_str(&format!("/{}'", index & 0x7fff_ffff));
+        } else {
+            out.push_str(&format!("/{}", index));
+        }
+    }
+    Ok(out)
+}
+
+fn tx_nodes(tx: &Tx) -> Vec<Node> {
+    let inputs = tx
+        .inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let mut children = vec![
+                Node::leaf(
+                    "prevout",
+                    format!("{}:{}", txid_hex(&input.prev_txid), input.vout),
+                ),
+                Node::leaf("sequence", format!("0x{:08x}", input.sequence)),
+            ];
+            if !input.script_sig.is_empty() {
+                children.push(script_node("scriptSig", &input.script_sig));
+            }
+            if !input.witness.is_empty() {
+                children.push(Node::leaf("witness items", input.witness.len().to_string()));
+            }
+            Node::branch(
+                format!("vin {}", i),
+                format!("{}:{}", short_hex(&txid_hex(&input.prev_txid)), input.vout),
+                children,
+            )
+        })
+        .collect();
+    let outputs = tx
+        .outputs
+        .iter()
+        .enumerate()
+        .map(|(i, out)| {
+            Node::branch(
+                format!("vout {}", i),
+                format!("{}  {}", fmt_sats(out.value), script_type(&out.script)),
+                vec![script_node("scriptPubKey", &out.script)],
+            )
+        })
+        .collect();
+    vec![
+        Node::leaf("txid", txid_hex(&tx.txid)),
+        Node::leaf("version", tx.version.to_string()),
+        Node::leaf("locktime", tx.locktime.to_string()),
+        Node::branch("inputs", tx.inputs.len().to_string(), inputs),
+        Node::branch("outputs", tx.outputs.len().to_string(), outputs),
+    ]
+}
+
+fn script_node(label: &str, script: &[u8]) -> Node {
+    Node::leaf(label, format!("{} {}", script_type(script), hex(script)))
+}
+
+pub fn script_type(script: &[u8]) -> &'static str {
+    match script {
+        [0x76, 0xa9, 0x14, .., 0x88, 0xac] if script.len() == 25 => "p2pkh",
+        [0xa9, 0x14, .., 0x87] if script.len() == 23 => "p2sh",
+        [0x00, 0x14, ..] if script.len() == 22 => "p2wpkh",
+        [0x00, 0x20, ..] if script.len() == 34 => "p2wsh",
+        [0x51, 0x20, ..] if script.len() == 34 => "p2tr",
+        [0x51, 0x02, 0x4e, 0x73] => "p2a",
+        [0x6a, ..] => "op_return",
+        [0x21, .., 0xac] if script.len() == 35 => "p2pk",
+        [0x41, .., 0xac] if script.len() == 67 => "p2pk",
+        [] => "empty",
+        _ => "script",
+    }
+}
+
+pub fn fmt_sats(sats: i64) -> String {
+    format!("{:.8} BTC", sats as f64 / 100_000_000.0)
+}
+
+pub fn txid_hex(txid: &[u8; 32]) -> String {
+    let mut reversed = *txid;
+    reversed.reverse();
+    hex(&reversed)
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn decode_hex(s: &str) -> Result<Vec<u8>, String> {
+    if !s.len().is_multiple_of(2) {
+        return Err("odd-length hex".to_string());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| format!("invalid hex at {}", i)))
+        .collect()
+}
+
+fn short_hex(s: &str) -> String {
+    if s.len() <= 20 {
+        s.to_string()
+    } else {
+        format!("{}…{}", &s[..8], &s[s.len() - 8..])
+    }
+}
+
+pub fn sha256d(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+fn base58check(payload: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    let mut data = payload.to_vec();
+    data.extend_from_slice(&sha256d(payload)[..4]);
+
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in &data {
+        let mut carry = byte as u32;
+        for digit in &mut digits {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+    let zeros = data.iter().take_while(|&&b| b == 0).count();
+    std::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char))
+        .collect()
+}
+
+/// A visible row of the field tree; `path` identifies the node across re-renders.
+pub struct TreeRow<'a> {
+    pub depth: usize,
+    pub path: String,
+    pub node: &'a Node,
+    pub expanded: bool,
+}
+
+pub fn flatten<'a>(nodes: &'a [Node], expanded: &HashSet<String>) -> Vec<TreeRow<'a>> {
+    let mut rows = Vec::new();
+    flatten_into(nodes, expanded, "", 0, &mut rows);
+    rows
+}
+
+fn flatten_into<'a>(
+    nodes: &'a [Node],
+    expanded: &HashSet<String>,
+    prefix: &str,
+    depth: usize,
+    rows: &mut Vec<TreeRow<'a>>,
+) {
+    for (i, node) in nodes.iter().enumerate() {
+        let path = if prefix.is_empty() {
+            i.to_string()
+        } else {
+            format!("{}/{}", prefix, i)
+        };
+        let is_expanded = expanded.contains(&path);
+        rows.push(TreeRow {
+            depth,
+            path: path.clone(),
+            node,
+            expanded: is_expanded,
+        });
+        if is_expanded {
+            flatten_into(&node.children, expanded, &path, depth + 1, rows);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Valid vectors from BIP174.
+
+    /// One P2PKH input with a non-witness UTXO; outputs are empty.
+    const P2PKH_INPUT: &str = "cHNidP8BAHUCAAAAASaBcTce3/KF6Tet7qSze3gADAVmy7OtZGQXE8pCFxv2AAAAAAD+////AtPf9QUAAAAAGXapFNDFmQPFusKGh2DpD9UhpGZap2UgiKwA4fUFAAAAABepFDVF5uM7gyxHBQ8k0+65PJwDlIvHh7MuEwAAAQD9pQEBAAAAAAECiaPHHqtNIOA3G7ukzGmPopXJRjr6Ljl/hTPMti+VZ+UBAAAAFxYAFL4Y0VKpsBIDna89p95PUzSe7LmF/////4b4qkOnHf8USIk6UwpyN+9rRgi7st0tAXHmOuxqSJC0AQAAABcWABT+Pp7xp0XpdNkCxDVZQ6vLNL1TU/////8CAMLrCwAAAAAZdqkUhc/xCX/Z4Ai7NK9wnGIZeziXikiIrHL++E4sAAAAF6kUM5cluiHv1irHU6m80GfWx6ajnQWHAkcwRAIgJxK+IuAnDzlPVoMR3HyppolwuAJf3TskAinwf4pfOiQCIAGLONfc0xTnNMkna9b7QPZzMlvEuqFEyADS8vAtsnZcASED0uFWdJQbrUqZY3LLh+GFbTZSYG2YVi/jnF6efkE/IQUCSDBFAiEA0SuFLYXc2WHS9fSrZgZU327tzHlMDDPOXMMJ/7X85Y0CIGczio4OFyXBl/saiK9Z9R5E5CVbIBZ8hoQDHAXR8lkqASECI7cr7vCWXRC+B3jv7NYfysb3mk6haTkzgHNEZPhPKrMAAAAAAAAA";
+    /// A P2PKH and a P2SH-P2WPKH input; the first is signed and finalized.
+    const FINALIZED_INPUT: &str = "cHNidP8BAKACAAAAAqsJSaCMWvfEm4IS9Bfi8Vqz9cM9zxU4IagTn4d6W3vkAAAAAAD+////qwlJoIxa98SbghL0F+LxWrP1wz3PFTghqBOfh3pbe+QBAAAAAP7///8CYDvqCwAAAAAZdqkUdopAu9dAy+gdmI5x3ipNXHE5ax2IrI4kAAAAAAAAGXapFG9GILVT+glechue4O/p+gOcykWXiKwAAAAAAAEHakcwRAIgR1lmF5fAGwNrJZKJSGhiGDR9iYZLcZ4ff89X0eURZYcCIFMJ6r9Wqk2Ikf/REf3xM286KdqGbX+EhtdVRs7tr5MZASEDXNxh/HupccC1AaZGoqg7ECy0OIEhfKaC3Ibi1z+ogpIAAQEgAOH1BQAAAAAXqRQ1RebjO4MsRwUPJNPuuTycA5SLx4cBBBYAFIXRNTfy4mVAWjTbr6nj3aAfuCMIAAAA";
+    /// The same two inputs unsigned, with the redeem script and output key paths filled.
+    const OUTPUTS_FILLED: &str = "cHNidP8BAKACAAAAAqsJSaCMWvfEm4IS9Bfi8Vqz9cM9zxU4IagTn4d6W3vkAAAAAAD+////qwlJoIxa98SbghL0F+LxWrP1wz3PFTghqBOfh3pbe+QBAAAAAP7///8CYDvqCwAAAAAZdqkUdopAu9dAy+gdmI5x3ipNXHE5ax2IrI4kAAAAAAAAGXapFG9GILVT+glechue4O/p+gOcykWXiKwAAAAAAAEA3wIAAAABJoFxNx7f8oXpN63upLN7eAAMBWbLs61kZBcTykIXG/YAAAAAakcwRAIgcLIkUSPmv0dNYMW1DAQ9TGkaXSQ18Jo0p2YqncJReQoCIAEynKnazygL3zB0DsA5BCJCLIHLRYOUV663b8Eu3ZWzASECZX0RjTNXuOD0ws1G23s59tnDjZpwq8ubLeXcjb/kzjH+////AtPf9QUAAAAAGXapFNDFmQPFusKGh2DpD9UhpGZap2UgiKwA4fUFAAAAABepFDVF5uM7gyxHBQ8k0+65PJwDlIvHh7MuEwAAAQEgAOH1BQAAAAAXqRQ1RebjO4MsRwUPJNPuuTycA5SLx4cBBBYAFIXRNTfy4mVAWjTbr6nj3aAfuCMIACICAurVlmh8qAYEPtw94RbN8p1eklfBls0FXPaYyNAr8k6ZELSmumcAAACAAAAAgAIAAIAAIgIDlPYr6d8ZlSxVh3aK63aYBhrSxKJciU9H2MFitNchPQUQtKa6ZwAAAIABAACAAgAAgAA=";
+    /// A P2SH-P2WSH 2-of-2 multisig input with redeem script, witness script and key paths.
+    const P2SH_P2WSH_MULTISIG: &str = "cHNidP8BAFUCAAAAASeaIyOl37UfxF8iD6WLD8E+HjNCeSqF1+Ejz48wU5QGAAAAAAD/////AQDh9QUAAAAAGXapFMjTOZR1pJzVXNPWZEbu8XB7PPQ2iKwAAAAAAAEBIADh9QUAAAAAF6kURmHNRjjvaycwt6YW+EkIaFdvWUuHAQQiACBcBB4Kg6ZUOxBwYI1LVq6dSCJvlRgZl7vnXqE5ihLQYgEFR1IhAoVzDrmuGUwjeVkRmJ4XdRXqbPbTvXkuzL8R6fVwDcHbIQOi1bm4jyv+q1AHMpWJtIo9KNmYxUV6TRS8dNtEKkkM7VKuIgYChXMOua4ZTCN5WRGYnhd1Fepsa9O9eS7MvxHp9XANwdsQ2QxqTwAAAIAAAACAAAAAgCIGA6LVubiPK/6rUAcylYm0ij0o2ZjFRXpNFLx020QqSQztENkMak8AAACAAAAAgAEAAIAAAA==";
+    /// A global xpub with two signed P2WPKH inputs.
+    const GLOBAL_XPUB: &str = "cHNidP8BAJ0BAAAAAnEOp2q0XFy2Q45gflnMA3YmmBgFrp4N/ZCJASq7C+U1AQAAAAD/////GQmU1qizyMgsy8+y+6QQaqBmObhyqNRHRlwNQliNbWcAAAAAAP////8CAOH1BQAAAAAZdqkUtrwsDuVlWoQ9ea/t0MzD991kNAmIrGBa9AUAAAAAFgAUEYjvjkzgRJ6qyPsUHL9aEXbmoIgAAAAATwEEiLIeA55TDKyAAAAAPbyKXJdp8DGxfnf+oVGGAyIaGP0Y8rmlTGyMGsdcvDUC8jBYSxVdHH8c1FEgplPEjWULQxtnxbLBPyfXFCA3wWkQJ1acUDEAAIAAAACAAAAAgAABAR8A4fUFAAAAABYAFDO5gvkbKPFgySC0q5XljOUN2jpKIgIDMJaA8zx9446mpHzU7NZvH1pJdHxv+4gI7QkDkkPjrVxHMEQCIC1wTO2DDFapCTRL10K2hS3M0QPpY7rpLTjnUlTSu0JFAiAthsQ3GV30bAztoITyopHD2i1kBw92v5uQsZXn7yj3cgEiBgMwloDzPH3jjqakfNTs1m8fWkl0fG/7iAjtCQOSQ+OtXBgnVpxQVAAAgAAAAIAAAACAAAAAAAEAAAAAAQEfAOH1BQAAAAAWABQ4j7lEMH63fvRRl9CwskXgefAR3iICAsd3Fh9z0LfHK57nveZQKT0T8JW8dlatH1Jdpf0uELEQRzBEAiBMsftfhpyULg4mEAV2ElQ5F5rojcqKncO6CPeVOYj6pgIgUh9JynkcJ9cOJzybFGFphZCTYeJb4nTqIA1+CoulyiYBIgYCx3cWH3PQt8crnue95lApPRPwlbx2Vq0fUl2l/S4QsRAYJ1acUFQAAIAAAACAAAAAgAAAAAAAAAAAAAAiAgLSDKUC7iiWhtIYFb1DqAY3sGmOH7zb5MrtRF9sGgqQ7xgnVpxQVAAAgAAAAIAAAACAAAAAAAQAAAAA";
+
+    /// Valid vector from BIP370: one input and two outputs with only the required fields.
+    const V2_REQUIRED_ONLY: &str = "cHNidP8BAgQCAAAAAQQBAQEFAQIB+wQCAAAAAAEOIAsK2SFBnByHGXNdctxzn56p4GONH+TB7vD5lECEgV/IAQ8EAAAAAAABAwgACK8vAAAAAAEEFgAUxDD2TEdW2jENvRoIVXLvKZkmJywAAQMIi73rCwAAAAABBBYAFE3Rk6yWSlasG54cyoRU/i9HT4UTAA==";
+
+    type Map = Vec<(Vec<u8>, Vec<u8>)>;
+
+    fn compact(n: usize) -> Vec<u8> {
+        match n {
+            0..0xfd => vec![n as u8],
+            0xfd..=0xffff => [vec![0xfd], (n as u16).to_le_bytes().to_vec()].concat(),
+            _ => [vec![0xfe], (n as u32).to_le_bytes().to_vec()].concat(),
+        }
+    }
+
+    /// The maps of a parsed PSBT as raw key/value pairs, global first.
+    fn maps(psbt: &Psbt) -> Vec<Map> {
+        std::iter::once(&psbt.global)
+            .chain(&psbt.inputs)
+            .chain(&psbt.outputs)
+            .map(|map| {
+                map.fields
+                    .iter()
+                    .map(|f| {
+                        let key = [compact(f.key_type as usize), f.key_data.clone()].concat();
+                        (key, f.value.clone())
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn encode(maps: &[Map]) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        for map in maps {
+            for (key, value) in map {
+                out.extend(compact(key.len()));
+                out.extend(key);
+                out.extend(compact(value.len()));
+                out.extend(value);
+            }
+            out.push(0x00);
+        }
+        out
+    }
+
+    fn vector_maps(text: &str) -> Vec<Map> {
+        maps(&parse(text).unwrap())
+    }
+
+    fn issues(maps: &[Map]) -> Vec<String> {
+        parse_bytes(&encode(maps)).unwrap().all_issues()
+    }
+
+    fn assert_issue(maps: &[Map], expected: &str) {
+        let issues = issues(maps);
+        assert!(
+            issues.iter().any(|i| i.contains(expected)),
+            "expected an issue containing {:?}, got {:?}",
+            expected,
+            issues
+        );
+    }
+
+    /// A v0 unsigned tx with the given inputs (txid, vout, scriptSig) and output scripts.
+    fn unsigned_tx(inputs: &[([u8; 32], u32, Vec<u8>)], outputs: &[Vec<u8>]) -> Vec<u8> {
+        let mut tx = 2u32.to_le_bytes().to_vec();
+        tx.extend(compact(inputs.len()));
+        for (txid, vout, script_sig) in inputs {
+            tx.extend(txid);
+            tx.extend(vout.to_le_bytes());
+            tx.extend(compact(script_sig.len()));
+            tx.extend(script_sig);
+            tx.extend(0xffff_fffdu32.to_le_bytes());
+        }
+        tx.extend(compact(outputs.len()));
+        for script in outputs {
+            tx.extend(100_000i64.to_le_bytes());
+            tx.extend(compact(script.len()));
+            tx.extend(script);
+        }
+        tx.extend(0u32.to_le_bytes());
+        tx
+    }
+
+    fn p2wpkh_script() -> Vec<u8> {
+        [vec![0x00, 0x14], vec![0x11; 20]].concat()
+    }
+
+    #[test]
+    fn bip174_valid_vectors_parse_without_issues() {
+        for (name, text, inputs, outputs) in [
+            ("P2PKH_INPUT", P2PKH_INPUT, 1, 2),
+            ("FINALIZED_INPUT", FINALIZED_INPUT, 2, 2),
+            ("OUTPUTS_FILLED", OUTPUTS_FILLED, 2, 2),
+            ("P2SH_P2WSH_MULTISIG", P2SH_P2WSH_MULTISIG, 1, 1),
+            ("GLOBAL_XPUB", GLOBAL_XPUB, 2, 2),
+        ] {
+            let psbt = parse(text).unwrap();
+            assert_eq!(psbt.version, 0, "{}", name);
+            assert_eq!(psbt.all_issues(), Vec::<String>::new(), "{}", name);
+            assert_eq!(
+                (psbt.inputs.len(), psbt.outputs.len()),
+                (inputs, outputs),
+                "{}",
+                name
+            );
+            // Re-encoding the parsed maps gives back the same bytes.
+            assert_eq!(encode(&maps(&psbt)), decode_text(text).unwrap(), "{}", name);
+        }
+    }
+
+    #[test]
+    fn bip174_vectors_decode_their_fields() {
+        let psbt = parse(P2PKH_INPUT).unwrap();
+        let tx = psbt.tx.as_ref().unwrap();
+        assert_eq!(
+            txid_hex(&tx.inputs[0].prev_txid),
+            "f61b1742ca13176464adb3cb66050c00787bb3a4eead37e985f2df1e37718126"
+        );
+        assert_eq!(tx.outputs[0].value, 99_999_699);
+        assert_eq!(script_type(&tx.outputs[0].script), "p2pkh");
+        assert_eq!(script_type(&tx.outputs[1].script), "p2sh");
+
+        let psbt = parse(GLOBAL_XPUB).unwrap();
+        let xpub = psbt.global.find(0x01).unwrap();
+        assert!(
+            xpub.details[0].value.starts_with("xpub"),
+            "{}",
+            xpub.details[0].value
+        );
+        assert_eq!(psbt.inputs[0].find(0x02).unwrap().details[2].value, "ALL");
+    }
+
+    #[test]
+    fn bip370_valid_vector_parses_without_issues() {
+        let psbt = parse(V2_REQUIRED_ONLY).unwrap();
+        assert_eq!(psbt.version, 2);
+        assert!(psbt.tx.is_none());
+        assert_eq!(psbt.all_issues(), Vec::<String>::new());
+        assert_eq!((psbt.inputs.len(), psbt.outputs.len()), (1, 2));
+        let (txid, vout) = psbt.prevout(0).unwrap();
+        assert_eq!(
+            txid_hex(&txid),
+            "c85f81844094f9f0eec1e41f8d63e0a99e9f73dc725d7319871c9c4121d90a0b"
+        );
+        assert_eq!(vout, 0);
+        assert_eq!(psbt.output(0).unwrap().value, 800_000_000);
+        assert_eq!(script_type(&psbt.output(1).unwrap().script), "p2wpkh");
+    }
+
+    #[test]
+    fn unsigned_tx_without_inputs_is_not_read_as_segwit() {
+        // What `createpsbt [] [...]` gives: bytes 4-5 are `00 01`, 0 inputs and 1 output.
+        let tx = unsigned_tx(&[], &[p2wpkh_script()]);
+        assert_eq!(&tx[4..6], &[0x00, 0x01]);
+        let psbt = parse_bytes(&encode(&[vec![(vec![0x00], tx)], vec![]])).unwrap();
+        assert_eq!(psbt.all_issues(), Vec::<String>::new());
+        let tx = psbt.tx.unwrap();
+        assert_eq!((tx.inputs.len(), tx.outputs.len()), (0, 1));
+        assert_eq!(tx.outputs[0].value, 100_000);
+    }
+
+    #[test]
+    fn bip174_invalid_network_transaction() {
+        let tx = "0200000001268171371edff285e937adeea4b37b78000c0566cbb3ad64641713ca42171bf6000000006a473044022070b2245123e6bf474d60c5b50c043d4c691a5d2435f09a34a7662a9dc251790a022001329ca9dacf280bdf30740ec0390422422c81cb45839457aeb76fc12edd95b3012102657d118d3357b8e0f4c2cd46db7b39f6d9c38d9a70abcb9b2de5dc8dbfe4ce31feffffff02d3dff505000000001976a914d0c59903c5bac2868760e90fd521a4665aa7652088ac00e1f5050000000017a9143545e6e33b832c47050f24d3eeb93c9c03948bc787b32e1300";
+        match parse(tx) {
+            Err(e) => assert!(e.contains("magic"), "{}", e),
+            Ok(_) => panic!("a network transaction parsed as a PSBT"),
+        }
+    }
+
+    #[test]
+    fn bip174_invalid_missing_outputs() {
+        let mut maps = vector_maps(P2PKH_INPUT);
+        maps.truncate(2);
+        assert_issue(&maps, "expected 2 output maps, found 0");
+    }
+
+    #[test]
+    fn bip174_invalid_filled_script_sig() {
+        let psbt = parse(P2PKH_INPUT).unwrap();
+        let input = &psbt.tx.as_ref().unwrap().inputs[0];
+        let tx = unsigned_tx(
+            &[(input.prev_txid, input.vout, vec![0x51])],
+            &[p2wpkh_script()],
+        );
+        let mut maps = vector_maps(P2PKH_INPUT);
+        maps[0] = vec![(vec![0x00], tx)];
+        maps.truncate(3);
+        assert_issue(
+            &maps,
+            "unsigned tx must have empty scriptSigs and witnesses",
+        );
+    }
+
+    #[test]
+    fn bip174_invalid_unsigned_tx_with_witness_serialization() {
+        let mut maps = vector_maps(P2PKH_INPUT);
+        let tx = maps[0][0].1.clone();
+        let witness_tx = [
+            &tx[..4],
+            &[0x00, 0x01],
+            &tx[4..tx.len() - 4],
+            &[0x00],
+            &tx[tx.len() - 4..],
+        ]
+        .concat();
+        maps[0][0].1 = witness_tx;
+        assert_issue(
+            &maps,
+            "PSBT_GLOBAL_UNSIGNED_TX (0x00) at byte 5: invalid transaction",
+        );
+    }
+
+    #[test]
+    fn bip174_invalid_missing_unsigned_tx() {
+        let mut maps = vector_maps(P2PKH_INPUT);
+        maps[0].clear();
+        let psbt = parse_bytes(&encode(&maps)).unwrap();
+        let issues = psbt.all_issues();
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.contains("missing required PSBT_GLOBAL_UNSIGNED_TX"))
+        );
+        assert!(
+            issues
+                .iter()
+                .any(|i| i.contains("cannot determine input/output counts"))
+        );
+    }
+
+    #[test]
+    fn bip174_invalid_unsigned_tx_with_key_data() {
+        let mut maps = vector_maps(P2PKH_INPUT);
+        maps[0][0].0.push(0x00);
+        assert_issue(&maps, "unexpected key data");
+    }
+
+    #[test]
+    fn bip174_invalid_duplicate_keys() {
+        let mut maps = vector_maps(P2PKH_INPUT);
+        let utxo = maps[1][0].clone();
+        maps[1].push(utxo);
+        assert_issue(&maps, "duplicate key");
+    }
+
+    #[test]
+    fn bip174_invalid_partial_sig_pubkey_length() {
+        let mut maps = vector_maps(GLOBAL_XPUB);
+        let sig = maps[1].iter_mut().find(|(k, _)| k[0] == 0x02).unwrap();
+        sig.0.pop();
+        let issues = issues(&maps);
+        assert!(
+            issues.iter().any(|i| i.contains("PSBT_IN_PARTIAL_SIG")),
+            "{:?}",
+            issues
+        );
+    }
+
+    #[test]
+    fn bip174_invalid_non_witness_utxo_for_another_outpoint() {
+        let mut maps = vector_maps(OUTPUTS_FILLED);
+        maps[0] = vector_maps(P2PKH_INPUT)[0].clone();
+        maps.truncate(4);
+        maps.push(Vec::new());
+        maps[2].clear();
+        assert_issue(&maps, "does not match the spent outpoint");
+    }
+
+    #[test]
+    fn bip370_invalid_v2_with_unsigned_tx() {
+        let mut maps = vector_maps(V2_REQUIRED_ONLY);
+        let tx = vector_maps(P2PKH_INPUT)[0][0].clone();
+        maps[0].push(tx);
+        assert_issue(&maps, "not allowed in PSBT version 2");
+    }
+
+    #[test]
+    fn bip370_invalid_v0_with_v2_fields() {
+        let mut maps = vector_maps(P2PKH_INPUT);
+        maps[0].push((vec![0x02], 2u32.to_le_bytes().to_vec()));
+        assert_issue(&maps, "not allowed in PSBT version 0");
+    }
+
+    #[test]
+    fn bip370_invalid_missing_required_fields() {
+        for (map, key_type, name) in [
+            (0, 0x02, "PSBT_GLOBAL_TX_VERSION"),
+            (1, 0x0e, "PSBT_IN_PREVIOUS_TXID"),
+            (1, 0x0f, "PSBT_IN_OUTPUT_INDEX"),
+            (2, 0x03, "PSBT_OUT_AMOUNT"),
+            (3, 0x04, "PSBT_OUT_SCRIPT"),
+        ] {
+            let mut maps = vector_maps(V2_REQUIRED_ONLY);
+            maps[map].retain(|(key, _)| key[0] != key_type);
+            assert_issue(&maps, &format!("missing required {}", name));
+        }
+        let mut maps = vector_maps(V2_REQUIRED_ONLY);
+        maps[0].retain(|(key, _)| key[0] != 0x04);
+        assert_issue(&maps, "missing required PSBT_GLOBAL_INPUT_COUNT");
+    }
+}
@@ -0,0 +1,209 @@
+use serde_json::{Map, Value, json};
+
+pub const CHANGE_TYPES: [&str; 5] = ["default", "legacy", "p2sh-segwit", "bech32", "bech32m"];
+
+#[derive(Clone, Default)]
+pub struct FormOutput {
+    pub address: String,
+    pub amount: String,
+    pub subtract_fee: bool,
+}
+
+/// State of the `walletcreatefundedpsbt` form in the PSBT tab.
+pub struct CreateForm {
+    pub open: bool,
+    pub outputs: Vec<FormOutput>,
+    pub inputs: String,
+    pub add_inputs: bool,
+    pub fee_rate: String,
+    pub change_type: usize,
+    pub replaceable: bool,
+    pub locktime: String,
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum FormRow {
+    Address(usize),
+    Amount(usize),
+    Inputs,
+    AddInputs,
+    FeeRate,
+    ChangeType,
+    Replaceable,
+    Locktime,
+    Submit,
+}
+
+impl Default for CreateForm {
+    fn default() -> Self {
+        CreateForm {
+            open: false,
+            outputs: vec![FormOutput::default()],
+            inputs: String::new(),
+            add_inputs: true,
+            fee_rate: String::new(),
+            change_type: 0,
+            replaceable: true,
+            locktime: String::new(),
+            selected: 0,
+            error: None,
+        }
+    }
+}
+
+impl CreateForm {
+    pub fn rows(&self) -> Vec<FormRow> {
+        let mut rows = Vec::new();
+        for i in 0..self.outputs.len() {
+            rows.push(FormRow::Address(i));
+            rows.push(FormRow::Amount(i));
+        }
+        rows.extend([
+            FormRow::Inputs,
+            FormRow::AddInputs,
+            FormRow::FeeRate,
+            FormRow::ChangeType,
+            FormRow::Replaceable,
+            FormRow::Locktime,
+            FormRow::Submit,
+        ]);
+        rows
+    }
+
+    pub fn selected_row(&self) -> FormRow {
+        let rows = self.rows();
+        rows[self.selected.min(rows.len() - 1)]
+    }
+
+    /// The editable text behind a row, if it is a free-text field.
+    pub fn text_mut(&mut self, row: FormRow) -> Option<&mut String> {
+        match row {
+            FormRow::Address(i) => self.outputs.get_mut(i).map(|o| &mut o.address),
+            FormRow::Amount(i) => self.outputs.get_mut(i).map(|o| &mut o.amount),
+            FormRow::Inputs => Some(&mut self.inputs),
+            FormRow::FeeRate => Some(&mut self.fee_rate),
+            FormRow::Locktime => Some(&mut self.locktime),
+            _ => None,
+        }
+    }
+
+    /// Toggles or cycles a non-text row. Returns false if the row isn't one.
+    pub fn toggle(&mut self, row: FormRow) -> bool {
+        match row {
+            FormRow::AddInputs => self.add_inputs = !self.add_inputs,
+            FormRow::Replaceable => self.replaceable = !self.replaceable,
+            FormRow::ChangeType => self.change_type = (self.change_type + 1) % CHANGE_TYPES.len(),
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn add_output(&mut self) {
+        self.outputs.push(FormOutput::default());
+        self.selected = (self.outputs.len() - 1) * 2;
+    }
+
+    pub fn remove_output(&mut self, index: usize) {
+        if self.outputs.len() > 1 && index < self.outputs.len() {
+            self.outputs.remove(index);
+            self.selected = self.selected.min(self.rows().len() - 1);
+        }
+    }
+
+    /// Builds the positional `walletcreatefundedpsbt` params:
+    /// `[inputs, outputs, locktime, options, bip32derivs]`.
+    pub fn build_params(&self) -> Result<Value, String> {
+        let mut outputs = Vec::new();
+        let mut subtract = Vec::new();
+        for (i, out) in self.outputs.iter().enumerate() {
+            let address = out.address.trim();
+            if address.is_empty() {
+                return Err(format!("output {}: address is required", i));
+            }
+            let amount =
+                parse_amount(out.amount.trim()).map_err(|e| format!("output {}: {}", i, e))?;
+            outputs.push(json!({ address: amount }));
+            if out.subtract_fee {
+                subtract.push(i);
+            }
+        }
+
+        let mut inputs = Vec::new();
+        for outpoint in self
+            .inputs
+            .split([',', ' '])
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+        {
+            let (txid, vout) = outpoint
+                .split_once(':')
+                .ok_or_else(|| format!("input {}: expected txid:vout", outpoint))?;
+            if txid.len() != 64 || !txid.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return Err(format!(
+                    "input {}: txid must be 64 hex characters",
+                    outpoint
+                ));
+            }
+            let vout: u32 = vout
+                .parse()
+                .map_err(|_| format!("input {}: invalid vout", outpoint))?;
+            inputs.push(json!({ "txid": txid, "vout": vout }));
+        }
+
+        let locktime: u32 = match self.locktime.trim() {
+            "" => 0,
+            s => s
+                .parse()
+                .map_err(|_| "locktime must be a non-negative integer")?,
+        };
+
+        let mut options = Map::new();
+        if !inputs.is_empty() {
+            options.insert("add_inputs".into(), Value::Bool(self.add_inputs));
+        }
+        if !self.fee_rate.trim().is_empty() {
+            let rate: f64 = self
+                .fee_rate
+                .trim()
+                .parse()
+                .map_err(|_| "fee rate must be a number (sat/vB)")?;
+            if rate <= 0.0 {
+                return Err("fee rate must be positive".to_string());
+            }
+            options.insert("fee_rate".into(), json!(rate));
+        }
+        if self.change_type > 0 {
+            options.insert("change_type".into(), json!(CHANGE_TYPES[self.change_type]));
+        }
+        if !subtract.is_empty() {
+            options.insert("subtractFeeFromOutputs".into(), json!(subtract));
+        }
+        options.insert("replaceable".into(), Value::Bool(self.replaceable));
+
+        Ok(json!([inputs, outputs, locktime, options, true]))
+    }
+}
+
+/// Validates a BTC amount and returns it as a string so no precision is lost in JSON.
+fn parse_amount(s: &str) -> Result<String, String> {
+    if s.is_empty() {
+        return Err("amount is required".to_string());
+    }
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    let digits_ok = !whole.is_empty() || !frac.is_empty();
+    if !digits_ok
+        || !whole.bytes().all(|b| b.is_ascii_digit())
+        || !frac.bytes().all(|b| b.is_ascii_digit())
+    {
+        return Err(format!("invalid amount {:?}", s));
+    }
+    if frac.len() > 8 {
+        return Err("amount has more than 8 decimal places".to_string());
+    }
+    if s.parse::<f64>().unwrap_or(0.0) <= 0.0 {
+        return Err("amount must be positive".to_string());
+    }
+    Ok(s.to_string())
+}
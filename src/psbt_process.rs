@@ -0,0 +1,198 @@
+use serde_json::{Value, json};
+
+pub const SIGHASH_TYPES: [&str; 7] = [
+    "DEFAULT",
+    "ALL",
+    "NONE",
+    "SINGLE",
+    "ALL|ANYONECANPAY",
+    "NONE|ANYONECANPAY",
+    "SINGLE|ANYONECANPAY",
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProcessMode {
+    /// `walletprocesspsbt` against the selected wallet.
+    #[default]
+    Wallet,
+    /// `descriptorprocesspsbt` with descriptors that aren't loaded as a wallet.
+    Descriptors,
+}
+
+pub struct SavedDescriptor {
+    pub descriptor: String,
+    pub selected: bool,
+}
+
+/// State of the process options form in the PSBT tab (`p`).
+pub struct ProcessForm {
+    pub open: bool,
+    pub mode: ProcessMode,
+    pub descriptors: Vec<SavedDescriptor>,
+    /// A descriptor typed into the form; saved on submit unless it holds private keys.
+    pub new_descriptor: String,
+    pub sign: bool,
+    pub sighash: usize,
+    pub bip32derivs: bool,
+    pub finalize: bool,
+    pub selected: usize,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ProcessRow {
+    Mode,
+    Descriptor(usize),
+    NewDescriptor,
+    Sign,
+    Sighash,
+    Bip32Derivs,
+    Finalize,
+    Submit,
+}
+
+impl Default for ProcessForm {
+    fn default() -> Self {
+        ProcessForm {
+            open: false,
+            mode: ProcessMode::default(),
+            descriptors: Vec::new(),
+            new_descriptor: String::new(),
+            sign: true,
+            sighash: 0,
+            bip32derivs: true,
+            finalize: false,
+            selected: 0,
+            error: None,
+        }
+    }
+}
+
+impl ProcessForm {
+    pub fn rows(&self) -> Vec<ProcessRow> {
+        let mut rows = vec![ProcessRow::Mode];
+        match self.mode {
+            ProcessMode::Wallet => rows.push(ProcessRow::Sign),
+            ProcessMode::Descriptors => {
+                rows.extend((0..self.descriptors.len()).map(ProcessRow::Descriptor));
+                rows.push(ProcessRow::NewDescriptor);
+            }
+        }
+        rows.extend([
+            ProcessRow::Sighash,
+            ProcessRow::Bip32Derivs,
+            ProcessRow::Finalize,
+            ProcessRow::Submit,
+        ]);
+        rows
+    }
+
+    pub fn selected_row(&self) -> ProcessRow {
+        let rows = self.rows();
+        rows[self.selected.min(rows.len() - 1)]
+    }
+
+    pub fn text_mut(&mut self, row: ProcessRow) -> Option<&mut String> {
+        match row {
+            ProcessRow::NewDescriptor => Some(&mut self.new_descriptor),
+            _ => None,
+        }
+    }
+
+    /// Toggles or cycles a non-text row. Returns false if the row isn't one.
+    pub fn toggle(&mut self, row: ProcessRow) -> bool {
+        match row {
+            ProcessRow::Mode => {
+                self.mode = match self.mode {
+                    ProcessMode::Wallet => ProcessMode::Descriptors,
+                    ProcessMode::Descriptors => ProcessMode::Wallet,
+                };
+            }
+            ProcessRow::Descriptor(i) => {
+                if let Some(d) = self.descriptors.get_mut(i) {
+                    d.selected = !d.selected;
+                }
+            }
+            ProcessRow::Sign => self.sign = !self.sign,
+            ProcessRow::Sighash => self.sighash = (self.sighash + 1) % SIGHASH_TYPES.len(),
+            ProcessRow::Bip32Derivs => self.bip32derivs = !self.bip32derivs,
+            ProcessRow::Finalize => self.finalize = !self.finalize,
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn remove_descriptor(&mut self, index: usize) {
+        if index < self.descriptors.len() {
+            self.descriptors.remove(index);
+            self.selected = self.selected.min(self.rows().len() - 1);
+        }
+    }
+
+    /// Positional `walletprocesspsbt` params: `[psbt, sign, sighashtype, bip32derivs, finalize]`.
+    pub fn wallet_params(&self, psbt: &str) -> Value {
+        json!([
+            psbt,
+            self.sign,
+            SIGHASH_TYPES[self.sighash],
+            self.bip32derivs,
+            self.finalize
+        ])
+    }
+
+    /// Positional `descriptorprocesspsbt` params:
+    /// `[psbt, descriptors, sighashtype, bip32derivs, finalize]`.
+    pub fn descriptor_params(&self, psbt: &str) -> Result<Value, String> {
+        let mut descriptors: Vec<&str> = self
+            .descriptors
+            .iter()
+            .filter(|d| d.selected)
+            .map(|d| d.descriptor.as_str())
+            .collect();
+        let new = self.new_descriptor.trim();
+        if !new.is_empty() {
+            descriptors.push(new);
+        }
+        if descriptors.is_empty() {
+            return Err("select a saved descriptor or enter a new one".to_string());
+        }
+        Ok(json!([
+            psbt,
+            descriptors,
+            SIGHASH_TYPES[self.sighash],
+            self.bip32derivs,
+            self.finalize
+        ]))
+    }
+
+    /// Moves the typed descriptor into the saved list (selected). Descriptors with
+    /// private keys are kept out of it so they never reach disk.
+    pub fn save_new_descriptor(&mut self) -> bool {
+        let new = self.new_descriptor.trim().to_string();
+        if new.is_empty() || has_private_key(&new) {
+            return false;
+        }
+        if let Some(existing) = self.descriptors.iter_mut().find(|d| d.descriptor == new) {
+            existing.selected = true;
+        } else {
+            self.descriptors.push(SavedDescriptor {
+                descriptor: new,
+                selected: true,
+            });
+        }
+        self.new_descriptor.clear();
+        true
+    }
+}
+
+/// Whether a descriptor contains an extended or WIF private key.
+pub fn has_private_key(descriptor: &str) -> bool {
+    descriptor.contains("prv") || {
+        // WIF keys are 51-52 base58 characters starting with 5, K, L (mainnet) or 9, c.
+        descriptor
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .any(|token| {
+                (51..=52).contains(&token.len()) && token.starts_with(['5', 'K', 'L', '9', 'c'])
+            })
+    }
+}
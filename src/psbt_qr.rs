@@ -0,0 +1,466 @@
+//! Animated QR export of a PSBT for air-gapped signers.
+//!
+//! Two multipart encodings are supported:
+//! - BC-UR `ur:crypto-psbt` (BCR-2020-005/006): the PSBT is wrapped in a CBOR byte
+//!   string and split into fountain-code "pure" fragments, each encoded as minimal
+//!   bytewords with a CRC32 checksum. Frames loop, so a missed frame comes around again.
+//! - BBQr (Coinkite): base32 of the raw PSBT behind an 8-character `B$2P` header with
+//!   the part count and index in base36.
+
+use std::time::Instant;
+
+use qrcode::{Color, EcLevel, QrCode};
+
+/// Payload bytes per frame, from sparse (small, easy to scan) to dense.
+pub const DENSITIES: [usize; 6] = [40, 80, 120, 200, 300, 450];
+pub const MAX_FPS: u8 = 20;
+
+const BYTEWORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
+pub enum QrFormat {
+    #[default]
+    Ur,
+    Bbqr,
+}
+
+impl QrFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            QrFormat::Ur => "BC-UR crypto-psbt",
+            QrFormat::Bbqr => "BBQr",
+        }
+    }
+
+    pub fn toggle(self) -> Self {
+        match self {
+            QrFormat::Ur => QrFormat::Bbqr,
+            QrFormat::Bbqr => QrFormat::Ur,
+        }
+    }
+}
+
+/// State of the QR export overlay in the PSBT tab.
+pub struct QrView {
+    pub open: bool,
+    pub format: QrFormat,
+    /// Index into [`DENSITIES`].
+    pub density: usize,
+    pub fps: u8,
+    pub paused: bool,
+    pub frames: Vec<String>,
+    pub frame: usize,
+    pub last_advance: Instant,
+    pub error: Option<String>,
+    psbt: Vec<u8>,
+}
+
+impl Default for QrView {
+    fn default() -> Self {
+        QrView {
+            open: false,
+            format: QrFormat::default(),
+            density: 2,
+            fps: 4,
+            paused: false,
+            frames: Vec::new(),
+            frame: 0,
+            last_advance: Instant::now(),
+            error: None,
+            psbt: Vec::new(),
+        }
+    }
+}
+
+impl QrView {
+    /// Opens the view for a base64 or hex PSBT string.
+    pub fn open(&mut self, psbt: &str) {
+        self.open = true;
+        self.paused = false;
+        match crate::psbt::decode_text(psbt) {
+            Ok(bytes) => {
+                self.psbt = bytes;
+                self.rebuild();
+            }
+            Err(e) => {
+                self.psbt.clear();
+                self.frames.clear();
+                self.error = Some(e);
+            }
+        }
+    }
+
+    /// Re-splits the PSBT after a format or density change.
+    pub fn rebuild(&mut self) {
+        if self.psbt.is_empty() {
+            return;
+        }
+        let max_fragment = DENSITIES[self.density.min(DENSITIES.len() - 1)];
+        let frames = match self.format {
+            QrFormat::Ur => Ok(ur_frames(&self.psbt, max_fragment)),
+            QrFormat::Bbqr => bbqr_frames(&self.psbt, max_fragment),
+        };
+        match frames {
+            Ok(frames) => {
+                self.frames = frames;
+                self.error = None;
+            }
+            Err(e) => {
+                self.frames.clear();
+                self.error = Some(e);
+            }
+        }
+        self.frame = 0;
+        self.last_advance = Instant::now();
+    }
+
+    /// Moves to the next frame once the frame interval has elapsed.
+    pub fn tick(&mut self) {
+        if self.paused || self.frames.len() < 2 {
+            return;
+        }
+        let interval = std::time::Duration::from_millis(1000 / self.fps.max(1) as u64);
+        if self.last_advance.elapsed() >= interval {
+            self.step(1);
+            self.last_advance = Instant::now();
+        }
+    }
+
+    pub fn step(&mut self, delta: isize) {
+        if self.frames.is_empty() {
+            return;
+        }
+        let len = self.frames.len() as isize;
+        self.frame = (self.frame as isize + delta).rem_euclid(len) as usize;
+    }
+
+    pub fn current(&self) -> Option<&str> {
+        self.frames.get(self.frame).map(String::as_str)
+    }
+
+    pub fn max_fragment(&self) -> usize {
+        DENSITIES[self.density.min(DENSITIES.len() - 1)]
+    }
+}
+
+/// BC-UR `crypto-psbt` parts. A PSBT that fits in one fragment becomes a single-part
+/// UR; otherwise each pure fountain fragment becomes `ur:crypto-psbt/<seq>-<len>/...`.
+pub fn ur_frames(psbt: &[u8], max_fragment: usize) -> Vec<String> {
+    let mut message = Vec::new();
+    cbor_head(&mut message, 2, psbt.len() as u64);
+    message.extend_from_slice(psbt);
+
+    if message.len() <= max_fragment {
+        return vec![format!("ur:crypto-psbt/{}", bytewords_minimal(&message)).to_uppercase()];
+    }
+
+    let fragment_len = nominal_fragment_len(message.len(), 10, max_fragment);
+    let checksum = crc32fast::hash(&message);
+    let fragments: Vec<&[u8]> = message.chunks(fragment_len).collect();
+    let seq_len = fragments.len();
+    fragments
+        .iter()
+        .enumerate()
+        .map(|(i, fragment)| {
+            let mut padded = fragment.to_vec();
+            padded.resize(fragment_len, 0);
+            let mut part = vec![0x85];
+            cbor_head(&mut part, 0, (i + 1) as u64);
+            cbor_head(&mut part, 0, seq_len as u64);
+            cbor_head(&mut part, 0, message.len() as u64);
+            cbor_head(&mut part, 0, checksum as u64);
+            cbor_head(&mut part, 2, padded.len() as u64);
+            part.extend_from_slice(&padded);
+            format!(
+                "ur:crypto-psbt/{}-{}/{}",
+                i + 1,
+                seq_len,
+                bytewords_minimal(&part)
+            )
+            .to_uppercase()
+        })
+        .collect()
+}
+
+/// Smallest fragment length that splits `len` bytes into equal parts of at most `max`.
+fn nominal_fragment_len(len: usize, min: usize, max: usize) -> usize {
+    let max_count = (len / min).max(1);
+    (1..=max_count)
+        .map(|count| len.div_ceil(count))
+        .find(|&fragment| fragment <= max)
+        .unwrap_or_else(|| len.div_ceil(max_count))
+}
+
+/// Writes a CBOR initial byte plus argument for `major` type (0 = uint, 2 = bytes).
+fn cbor_head(out: &mut Vec<u8>, major: u8, value: u64) {
+    let major = major << 5;
+    match value {
+        0..=23 => out.push(major | value as u8),
+        24..=0xff => out.extend([major | 24, value as u8]),
+        0x100..=0xffff => {
+            out.push(major | 25);
+            out.extend((value as u16).to_be_bytes());
+        }
+        0x1_0000..=0xffff_ffff => {
+            out.push(major | 26);
+            out.extend((value as u32).to_be_bytes());
+        }
+        _ => {
+            out.push(major | 27);
+            out.extend(value.to_be_bytes());
+        }
+    }
+}
+
+/// Minimal bytewords (first and last letter of each word) with the CRC32 appended.
+fn bytewords_minimal(data: &[u8]) -> String {
+    let checksum = crc32fast::hash(data).to_be_bytes();
+    data.iter()
+        .chain(checksum.iter())
+        .map(|&b| {
+            let word = BYTEWORDS[b as usize].as_bytes();
+            format!("{}{}", word[0] as char, word[3] as char)
+        })
+        .collect()
+}
+
+/// BBQr parts: `B$` + encoding `2` (base32) + file type `P` (PSBT) + part count and
+/// zero-based index as two base36 digits each, followed by a slice of the base32 data.
+pub fn bbqr_frames(psbt: &[u8], max_fragment: usize) -> Result<Vec<String>, String> {
+    let data = base32(psbt);
+    // Every part but the last must hold whole 8-character base32 groups.
+    let chars_per_part = (max_fragment * 8 / 5 / 8).max(1) * 8;
+    let parts: Vec<&str> = data
+        .as_bytes()
+        .chunks(chars_per_part)
+        .map(|c| std::str::from_utf8(c).unwrap_or_default())
+        .collect();
+    if parts.len() > 36 * 36 - 1 {
+        return Err(format!(
+            "PSBT needs {} BBQr parts; the format allows at most 1295, increase the density",
+            parts.len()
+        ));
+    }
+    let total = base36(parts.len());
+    Ok(parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| format!("B$2P{}{}{}", total, base36(i), part))
+        .collect())
+}
+
+fn base36(n: usize) -> String {
+    const DIGITS: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    format!("{}{}", DIGITS[n / 36 % 36] as char, DIGITS[n % 36] as char)
+}
+
+/// RFC 4648 base32 without padding.
+fn base32(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+    let mut out = String::with_capacity(data.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in data {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Renders `text` as a QR code using Unicode half blocks, two modules per character
+/// row, with a two-module quiet zone. Light modules are drawn as filled blocks so the
+/// code reads correctly on a dark terminal background.
+pub fn render(text: &str) -> Result<Vec<String>, String> {
+    let code = QrCode::with_error_correction_level(text.as_bytes(), EcLevel::L)
+        .map_err(|e| format!("QR encoding failed: {}", e))?;
+    let width = code.width();
+    let colors = code.to_colors();
+    const QUIET: usize = 2;
+    let size = width + QUIET * 2;
+    let light = |x: usize, y: usize| {
+        if x < QUIET || y < QUIET || x >= width + QUIET || y >= width + QUIET {
+            return true;
+        }
+        colors[(y - QUIET) * width + (x - QUIET)] == Color::Light
+    };
+
+    Ok((0..size)
+        .step_by(2)
+        .map(|y| {
+            (0..size)
+                .map(|x| match (light(x, y), y + 1 < size && light(x, y + 1)) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect()
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `make_message` from the bc-ur reference tests: bytes drawn from Xoshiro256**
+    /// seeded with the SHA-256 of `seed`.
+    fn make_message(seed: &str, len: usize) -> Vec<u8> {
+        use sha2::{Digest, Sha256};
+        let digest = Sha256::digest(seed.as_bytes());
+        let mut s = [0u64; 4];
+        for (i, chunk) in digest.chunks(8).enumerate() {
+            s[i] = u64::from_be_bytes(chunk.try_into().unwrap());
+        }
+        let mut next = || {
+            let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+            let t = s[1] << 17;
+            s[2] ^= s[0];
+            s[3] ^= s[1];
+            s[1] ^= s[2];
+            s[0] ^= s[3];
+            s[2] ^= t;
+            s[3] = s[3].rotate_left(45);
+            result
+        };
+        (0..len)
+            .map(|_| (next() as f64 / (u64::MAX as f64 + 1.0) * 256.0) as u8)
+            .collect()
+    }
+
+    #[test]
+    fn bytewords_and_checksum_match_bc_ur() {
+        assert_eq!(
+            bytewords_minimal(&[0, 1, 2, 128, 255]),
+            "aeadaolazmjendeoti"
+        );
+        // CRC-32/ISO-HDLC, as bc-ur uses for both bytewords and fountain parts.
+        assert_eq!(crc32fast::hash(b"Hello, world!"), 0xebe6c6e6);
+        assert_eq!(crc32fast::hash(b"Wolf"), 0x598c84dc);
+    }
+
+    #[test]
+    fn cbor_heads_match_rfc8949() {
+        for (major, value, expected) in [
+            (0, 0, "00"),
+            (0, 23, "17"),
+            (0, 24, "1818"),
+            (0, 100, "1864"),
+            (0, 1000, "1903e8"),
+            (0, 1_000_000, "1a000f4240"),
+            (0, 1_000_000_000_000, "1b000000e8d4a51000"),
+            (2, 4, "44"),
+            (2, 259, "590103"),
+        ] {
+            let mut out = Vec::new();
+            cbor_head(&mut out, major, value);
+            assert_eq!(crate::psbt::hex(&out), expected, "{} {}", major, value);
+        }
+    }
+
+    #[test]
+    fn fragment_length_matches_bc_ur() {
+        assert_eq!(nominal_fragment_len(12345, 1005, 1955), 1764);
+        assert_eq!(nominal_fragment_len(12345, 1005, 30000), 12345);
+        assert_eq!(nominal_fragment_len(259, 10, 30), 29);
+    }
+
+    #[test]
+    fn ur_frames_match_bc_ur_multipart_vector() {
+        // bc-ur's encoder vector: the 256-byte "Wolf" message in 30-byte fragments. It is
+        // published as `ur:bytes`; crypto-psbt wraps the same CBOR byte string.
+        let expected = [
+            "1-9/lpadascfadaxcywenbpljkhdcahkadaemejtswhhylkepmykhhtsytsnoyoyaxaedsuttydmmhhpktpmsrjtdkgslpgh",
+            "2-9/lpaoascfadaxcywenbpljkhdcagwdpfnsboxgwlbaawzuefywkdplrsrjynbvygabwjldapfcsgmghhkhstlrdcxaefz",
+            "3-9/lpaxascfadaxcywenbpljkhdcahelbknlkuejnbadmssfhfrdpsbiegecpasvssovlgeykssjykklronvsjksopdzmol",
+            "4-9/lpaaascfadaxcywenbpljkhdcasotkhemthydawydtaxneurlkosgwcekonertkbrlwmplssjtammdplolsbrdzcrtas",
+            "5-9/lpahascfadaxcywenbpljkhdcatbbdfmssrkzmcwnezelennjpfzbgmuktrhtejscktelgfpdlrkfyfwdajldejokbwf",
+            "6-9/lpamascfadaxcywenbpljkhdcackjlhkhybssklbwefectpfnbbectrljectpavyrolkzczcpkmwidmwoxkilghdsowp",
+            "7-9/lpatascfadaxcywenbpljkhdcavszmwnjkwtclrtvaynhpahrtoxmwvwatmedibkaegdosftvandiodagdhthtrlnnhy",
+            "8-9/lpayascfadaxcywenbpljkhdcadmsponkkbbhgsoltjntegepmttmoonftnbuoiyrehfrtsabzsttorodklubbuyaetk",
+            "9-9/lpasascfadaxcywenbpljkhdcajskecpmdckihdyhphfotjojtfmlnwmadspaxrkytbztpbauotbgtgtaeaevtgavtny",
+        ]
+        .map(|part| format!("ur:crypto-psbt/{}", part).to_uppercase());
+        assert_eq!(ur_frames(&make_message("Wolf", 256), 30), expected);
+    }
+
+    #[test]
+    fn small_psbt_is_a_single_part_ur() {
+        // `fe` is the CBOR head 0x45 (5-byte string), then the bytes and checksum.
+        assert_eq!(
+            ur_frames(&[0, 1, 2, 128, 255], 30),
+            ["UR:CRYPTO-PSBT/FEAEADAOLAZMFXWYZEPA"]
+        );
+    }
+
+    #[test]
+    fn base32_matches_rfc4648() {
+        for (data, expected) in [
+            ("", ""),
+            ("f", "MY"),
+            ("fo", "MZXQ"),
+            ("foo", "MZXW6"),
+            ("foob", "MZXW6YQ"),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI"),
+        ] {
+            assert_eq!(base32(data.as_bytes()), expected);
+        }
+    }
+
+    #[test]
+    fn bbqr_frames_follow_the_spec_header() {
+        assert_eq!(
+            [base36(1), base36(35), base36(36), base36(1295)],
+            ["01", "0Z", "10", "ZZ"]
+        );
+
+        let psbt = make_message("Wolf", 100);
+        let frames = bbqr_frames(&psbt, 40).unwrap();
+        let headers: Vec<&str> = frames.iter().map(|f| &f[..8]).collect();
+        assert_eq!(headers, ["B$2P0300", "B$2P0301", "B$2P0302"]);
+        // Every part but the last holds whole 8-character groups (5 bytes each).
+        assert_eq!(
+            frames.iter().map(|f| f.len() - 8).collect::<Vec<_>>(),
+            [64, 64, 32]
+        );
+        let joined: String = frames.iter().map(|f| &f[8..]).collect();
+        assert_eq!(joined, base32(&psbt));
+
+        let single = bbqr_frames(b"foobar", 40).unwrap();
+        assert_eq!(single, ["B$2P0100MZXW6YTBOI"]);
+
+        assert!(bbqr_frames(&[0; 1296 * 5], 5).is_err());
+        assert_eq!(bbqr_frames(&[0; 1295 * 5], 5).unwrap().len(), 1295);
+    }
+}
@@ -0,0 +1,300 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Confirmation target used for the `estimatesmartfee` comparison.
+pub const ESTIMATE_TARGET: u64 = 6;
+/// A fee rate this many times the node's estimate is flagged as unusually high.
+pub const HIGH_FEE_MULTIPLE: f64 = 3.0;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Ownership {
+    External,
+    /// Ours but not change, e.g. a send-to-self.
+    Mine,
+    Change,
+    /// No wallet lookup was possible.
+    Unknown,
+}
+
+impl Ownership {
+    pub fn label(self) -> &'static str {
+        match self {
+            Ownership::External => "external",
+            Ownership::Mine => "to self",
+            Ownership::Change => "change",
+            Ownership::Unknown => "unknown",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct InputSummary {
+    pub outpoint: String,
+    pub amount_sat: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct OutputSummary {
+    pub address: Option<String>,
+    pub amount_sat: u64,
+    pub ownership: Ownership,
+}
+
+/// Human-readable overview of a PSBT, shown before signing or broadcasting it.
+#[derive(Clone)]
+pub struct PsbtSummary {
+    pub inputs: Vec<InputSummary>,
+    pub outputs: Vec<OutputSummary>,
+    /// Sum of the inputs, if every input carries UTXO data.
+    pub total_in_sat: Option<u64>,
+    pub total_out_sat: u64,
+    pub fee_sat: Option<u64>,
+    pub vsize: Option<u64>,
+    /// sat/vB
+    pub fee_rate: Option<f64>,
+    /// sat/vB from `estimatesmartfee`
+    pub estimate: Option<f64>,
+    /// The role that has to act next according to `analyzepsbt`.
+    pub next: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+/// Everything the summary is built from; fetched by the event loop.
+pub struct SummaryInputs {
+    pub decoded: Value,
+    pub analyzed: Value,
+    /// `getaddressinfo` results by address; missing when there's no wallet.
+    pub address_info: HashMap<String, Value>,
+    /// BTC/kvB
+    pub estimate: Option<f64>,
+    /// sat/vB
+    pub max_fee_rate: f64,
+}
+
+fn to_sat(btc: f64) -> u64 {
+    (btc * 100_000_000.0).round() as u64
+}
+
+fn btc_field(value: &Value, key: &str) -> Option<u64> {
+    value.get(key).and_then(|v| v.as_f64()).map(to_sat)
+}
+
+pub fn build(data: &SummaryInputs) -> PsbtSummary {
+    let decoded = &data.decoded;
+    let empty = Vec::new();
+    let tx = decoded.get("tx");
+    let vin = tx
+        .and_then(|t| t.get("vin"))
+        .and_then(|v| v.as_array())
+        .unwrap_or(&empty);
+    let vout = tx
+        .and_then(|t| t.get("vout"))
+        .and_then(|v| v.as_array())
+        .unwrap_or(&empty);
+    let psbt_inputs = decoded
+        .get("inputs")
+        .and_then(|v| v.as_array())
+        .unwrap_or(&empty);
+    let mut warnings = Vec::new();
+
+    let mut inputs = Vec::new();
+    for (i, txin) in vin.iter().enumerate() {
+        let txid = txin.get("txid").and_then(|v| v.as_str()).unwrap_or("?");
+        let index = txin.get("vout").and_then(|v| v.as_u64());
+        let meta = psbt_inputs.get(i);
+        let amount_sat = meta.and_then(|m| input_amount(m, index));
+        if amount_sat.is_none() {
+            warnings.push(format!(
+                "input {}: no UTXO data; fee can't be checked (run utxoupdatepsbt or process with a wallet)",
+                i
+            ));
+        }
+        if let Some(meta) = meta {
+            sighash_warnings(i, meta, &mut warnings);
+        }
+        inputs.push(InputSummary {
+            outpoint: match index {
+                Some(n) => format!("{}:{}", txid, n),
+                None => txid.to_string(),
+            },
+            amount_sat,
+        });
+    }
+
+    let have_wallet = !data.address_info.is_empty();
+    let outputs: Vec<OutputSummary> = vout
+        .iter()
+        .map(|out| {
+            let address = out
+                .get("scriptPubKey")
+                .and_then(|s| s.get("address"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let info = address.as_ref().and_then(|a| data.address_info.get(a));
+            let ownership = match info {
+                Some(info) if info.get("ischange").and_then(|v| v.as_bool()) == Some(true) => {
+                    Ownership::Change
+                }
+                Some(info) if info.get("ismine").and_then(|v| v.as_bool()) == Some(true) => {
+                    Ownership::Mine
+                }
+                Some(_) => Ownership::External,
+                None if have_wallet && address.is_none() => Ownership::External,
+                None => Ownership::Unknown,
+            };
+            OutputSummary {
+                address,
+                amount_sat: btc_field(out, "value").unwrap_or(0),
+                ownership,
+            }
+        })
+        .collect();
+    if !have_wallet && !outputs.is_empty() {
+        warnings.push("no wallet lookups: change outputs can't be identified".to_string());
+    }
+
+    let total_in_sat = inputs
+        .iter()
+        .map(|i| i.amount_sat)
+        .sum::<Option<u64>>()
+        .filter(|_| !inputs.is_empty());
+    let total_out_sat = outputs.iter().map(|o| o.amount_sat).sum();
+    let fee_sat = btc_field(decoded, "fee")
+        .or_else(|| total_in_sat.and_then(|t| t.checked_sub(total_out_sat)));
+    let analyzed = &data.analyzed;
+    let vsize = analyzed.get("estimated_vsize").and_then(|v| v.as_u64());
+    let fee_rate = match (fee_sat, vsize) {
+        (Some(fee), Some(vsize)) if vsize > 0 => Some(fee as f64 / vsize as f64),
+        _ => analyzed
+            .get("estimated_feerate")
+            .and_then(|v| v.as_f64())
+            .map(|r| r * 100_000.0),
+    };
+    let estimate = data.estimate.map(|r| r * 100_000.0);
+
+    if let Some(rate) = fee_rate {
+        if let Some(est) = estimate
+            && rate > est * HIGH_FEE_MULTIPLE
+        {
+            warnings.push(format!(
+                "fee rate {:.2} sat/vB is {:.1}x the node's {}-block estimate ({:.2} sat/vB)",
+                rate,
+                rate / est,
+                ESTIMATE_TARGET,
+                est
+            ));
+        }
+        if rate > data.max_fee_rate {
+            warnings.push(format!(
+                "fee rate {:.2} sat/vB exceeds --maxfeerate ({} sat/vB); broadcast will be refused",
+                rate, data.max_fee_rate
+            ));
+        }
+    }
+    if let Some(fee) = fee_sat
+        && total_out_sat > 0
+        && fee > total_out_sat / 10
+    {
+        warnings.push(format!(
+            "fee is {:.1}% of the output total",
+            fee as f64 * 100.0 / total_out_sat as f64
+        ));
+    }
+    warnings.dedup();
+    if let Some(error) = analyzed.get("error").and_then(|v| v.as_str()) {
+        warnings.push(format!("analyzepsbt: {}", error));
+    }
+
+    PsbtSummary {
+        inputs,
+        outputs,
+        total_in_sat,
+        total_out_sat,
+        fee_sat,
+        vsize,
+        fee_rate,
+        estimate,
+        next: analyzed
+            .get("next")
+            .and_then(|v| v.as_str())
+            .map(str::to_string),
+        warnings,
+    }
+}
+
+/// The spent amount from the input's witness or non-witness UTXO.
+fn input_amount(meta: &Value, vout: Option<u64>) -> Option<u64> {
+    if let Some(utxo) = meta.get("witness_utxo") {
+        return btc_field(utxo, "amount");
+    }
+    let prev = meta.get("non_witness_utxo")?;
+    let out = prev.get("vout")?.as_array()?.get(vout? as usize)?;
+    btc_field(out, "value")
+}
+
+/// Flags sighash types other than ALL/DEFAULT, both requested and in existing signatures.
+fn sighash_warnings(index: usize, meta: &Value, warnings: &mut Vec<String>) {
+    if let Some(requested) = meta.get("sighash").and_then(|v| v.as_str())
+        && requested != "ALL"
+        && requested != "DEFAULT"
+    {
+        warnings.push(format!("input {}: requests sighash {}", index, requested));
+    }
+    let mut signatures: Vec<(&str, bool)> = Vec::new();
+    if let Some(partial) = meta.get("partial_signatures").and_then(|v| v.as_object()) {
+        signatures.extend(
+            partial
+                .values()
+                .filter_map(|v| v.as_str())
+                .map(|s| (s, false)),
+        );
+    }
+    if let Some(sig) = meta.get("taproot_key_path_sig").and_then(|v| v.as_str()) {
+        signatures.push((sig, true));
+    }
+    if let Some(sigs) = meta
+        .get("taproot_script_path_sigs")
+        .and_then(|v| v.as_array())
+    {
+        signatures.extend(
+            sigs.iter()
+                .filter_map(|s| s.get("sig").and_then(|v| v.as_str()))
+                .map(|s| (s, true)),
+        );
+    }
+    for (sig, taproot) in signatures {
+        // Schnorr signatures are 64 bytes for SIGHASH_DEFAULT; otherwise the type is the last byte.
+        if taproot && sig.len() == 128 {
+            continue;
+        }
+        let Some(byte) = sig
+            .get(sig.len().saturating_sub(2)..)
+            .and_then(|b| u8::from_str_radix(b, 16).ok())
+        else {
+            continue;
+        };
+        if byte != 0x01 {
+            warnings.push(format!(
+                "input {}: signed with sighash {}",
+                index,
+                sighash_name(byte)
+            ));
+        }
+    }
+}
+
+fn sighash_name(byte: u8) -> String {
+    let base = match byte & 0x1f {
+        0x00 => "DEFAULT",
+        0x01 => "ALL",
+        0x02 => "NONE",
+        0x03 => "SINGLE",
+        _ => return format!("0x{:02x}", byte),
+    };
+    if byte & 0x80 != 0 {
+        format!("{}|ANYONECANPAY", base)
+    } else {
+        base.to_string()
+    }
+}
@@ -0,0 +1,172 @@
+//! The raw args prompt: comma-separated JSON values or `name=value` pairs, also used by
+//! macro call steps.
+
+/// Parses the arg input: comma-separated JSON values sent positionally, or `name=value`
+/// pairs sent as named params. Leading positional values may precede the pairs and are
+/// passed in `args`; a named value that isn't valid JSON is taken as a string.
+pub fn parse(input: &str) -> Result<serde_json::Value, String> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        return Ok(serde_json::json!([]));
+    }
+    let segments = split(trimmed);
+    if !segments.iter().any(|s| named_arg(s).is_some()) {
+        let wrapped = format!("[{}]", trimmed);
+        return serde_json::from_str(&wrapped).map_err(|e| format!("Invalid args: {}", e));
+    }
+
+    let mut positional = Vec::new();
+    let mut named = serde_json::Map::new();
+    for segment in segments {
+        match named_arg(segment) {
+            Some((name, value)) => {
+                let value = value.trim();
+                let value = serde_json::from_str(value)
+                    .unwrap_or_else(|_| serde_json::Value::String(value.to_string()));
+                if named.insert(name.to_string(), value).is_some() {
+                    return Err(format!("Invalid args: {} is given twice", name));
+                }
+            }
+            None if named.is_empty() => positional.push(
+                serde_json::from_str(segment.trim())
+                    .map_err(|e| format!("Invalid args: {}: {}", segment.trim(), e))?,
+            ),
+            None => {
+                return Err(format!(
+                    "Invalid args: positional {} after name=value pairs",
+                    segment.trim()
+                ));
+            }
+        }
+    }
+    if !positional.is_empty() {
+        if named.contains_key("args") {
+            return Err("Invalid args: args is given twice".to_string());
+        }
+        named.insert("args".to_string(), serde_json::Value::Array(positional));
+    }
+    Ok(serde_json::Value::Object(named))
+}
+
+/// The characters of `input` outside JSON string literals, with their byte offsets; the
+/// quotes themselves are left out.
+pub fn unquoted_chars(input: &str) -> impl Iterator<Item = (usize, char)> + '_ {
+    let (mut in_string, mut escaped) = (false, false);
+    input.char_indices().filter(move |&(_, c)| {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            return false;
+        }
+        if c == '"' {
+            in_string = true;
+            return false;
+        }
+        true
+    })
+}
+
+/// Splits on commas outside of strings, arrays and objects.
+fn split(input: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in unquoted_chars(input) {
+        match c {
+            '[' | '{' => depth += 1,
+            ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                segments.push(&input[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&input[start..]);
+    segments
+}
+
+/// `name=value` with a bare parameter name, as opposed to a JSON value.
+fn named_arg(segment: &str) -> Option<(&str, &str)> {
+    let (name, value) = segment.trim_start().split_once('=')?;
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    valid.then_some((name, value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn positional_values() {
+        assert_eq!(parse("").unwrap(), json!([]));
+        assert_eq!(
+            parse(r#""abc", 6, true, null"#).unwrap(),
+            json!(["abc", 6, true, null])
+        );
+        assert!(parse("abc").unwrap_err().starts_with("Invalid args"));
+    }
+
+    #[test]
+    fn named_values() {
+        assert_eq!(
+            parse("conf_target=6, estimate_mode=economical").unwrap(),
+            json!({"conf_target": 6, "estimate_mode": "economical"})
+        );
+        assert_eq!(
+            parse(r#"label="", include_watchonly=true"#).unwrap(),
+            json!({"label": "", "include_watchonly": true})
+        );
+    }
+
+    #[test]
+    fn leading_positionals_go_in_args() {
+        assert_eq!(
+            parse(r#""sendtoaddress", 1, verbose=true"#).unwrap(),
+            json!({"args": ["sendtoaddress", 1], "verbose": true})
+        );
+        assert_eq!(
+            parse("verbose=true, 1").unwrap_err(),
+            "Invalid args: positional 1 after name=value pairs"
+        );
+        assert_eq!(
+            parse("1, args=[2]").unwrap_err(),
+            "Invalid args: args is given twice"
+        );
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        assert_eq!(
+            parse("verbose=true, verbose=false").unwrap_err(),
+            "Invalid args: verbose is given twice"
+        );
+    }
+
+    #[test]
+    fn commas_inside_strings_and_objects_stay_in_the_value() {
+        assert_eq!(
+            parse(r#"comment="a, b", options={"a": 1, "b": [2, 3]}"#).unwrap(),
+            json!({"comment": "a, b", "options": {"a": 1, "b": [2, 3]}})
+        );
+        assert_eq!(
+            parse(r#""a, b", {"c": 1, "d": 2}"#).unwrap(),
+            json!(["a, b", {"c": 1, "d": 2}])
+        );
+    }
+
+    #[test]
+    fn equals_inside_strings_is_not_a_name() {
+        assert_eq!(parse(r#""a=b""#).unwrap(), json!(["a=b"]));
+        assert_eq!(
+            parse(r#""x=1", label="a=b""#).unwrap(),
+            json!({"args": ["x=1"], "label": "a=b"})
+        );
+        assert_eq!(parse("label=a=b").unwrap(), json!({"label": "a=b"}));
+    }
+}
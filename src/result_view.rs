@@ -0,0 +1,125 @@
+//! Reading RPC results through openrpc.json result schemas: which shape of a `oneOf`
+//! result came back, friendlier units for values, and the "Returns" help outline.
+
+use serde_json::Value;
+
+use crate::format::{fmt_btc, fmt_sat_per_vb, fmt_unix_time};
+use crate::wallet_schema::Schema;
+
+/// Picks the `oneOf` alternative that fits the value best, e.g. the verbosity 1 or 2
+/// shape of `getblock`, which only differ in what `tx` holds.
+pub fn resolve<'a>(schema: &'a Schema, value: &Value) -> &'a Schema {
+    schema
+        .one_of
+        .iter()
+        .map(|alt| (fit(alt, value, 3), alt))
+        .filter(|(score, _)| *score > 0)
+        .max_by_key(|(score, _)| *score)
+        .map_or(schema, |(_, alt)| alt)
+}
+
+/// How well a value matches a schema: 0 for a type mismatch, otherwise higher the more
+/// of its nested fields (down to `depth` levels) match too.
+fn fit(schema: &Schema, value: &Value, depth: usize) -> usize {
+    if !schema.one_of.is_empty() {
+        return schema
+            .one_of
+            .iter()
+            .map(|alt| fit(alt, value, depth))
+            .max()
+            .unwrap_or(0);
+    }
+    let json_type = match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    match schema.schema_type.as_deref() {
+        Some(t) if t != json_type => return 0,
+        _ if depth == 0 => return 1,
+        _ => {}
+    }
+    1 + match value {
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(k, v)| Some(fit(schema.properties.get(k)?, v, depth - 1)))
+            .sum(),
+        Value::Array(items) => match (&schema.items, items.first()) {
+            (Some(item_schema), Some(first)) => fit(item_schema, first, depth - 1),
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+pub fn annotation(value: &Value, schema: &Schema) -> Option<String> {
+    if schema.unit.as_deref() == Some("unix-time") {
+        return value.as_u64().map(|t| format!("{} UTC", fmt_unix_time(t)));
+    }
+    let description = schema.description.as_deref()?;
+    let amount = value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))?;
+    if description.contains("BTC/kvB") || description.contains("BTC/kB") {
+        Some(fmt_sat_per_vb(amount))
+    } else if description
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| word == "BTC")
+    {
+        Some(fmt_btc(amount))
+    } else {
+        None
+    }
+}
+
+/// `00000000…5ac9e8f2` for 64-character hex strings (hashes, txids, x-only keys).
+pub fn abbreviate_hash(s: &str) -> Option<String> {
+    (s.len() == 64 && s.bytes().all(|b| b.is_ascii_hexdigit()))
+        .then(|| format!("{}…{}", &s[..8], &s[56..]))
+}
+
+/// One row of the "Returns" section of method help.
+pub struct OutlineEntry {
+    pub depth: usize,
+    pub name: String,
+    pub type_label: String,
+    pub description: Option<String>,
+}
+
+/// Flattens a result schema into an indented outline: object fields by name, array
+/// items as `[]`, free-form keys as `<key>` and `oneOf` shapes as their own rows.
+pub fn outline(schema: &Schema) -> Vec<OutlineEntry> {
+    let mut out = Vec::new();
+    outline_into(schema, String::new(), 0, &mut out);
+    out
+}
+
+fn outline_into(schema: &Schema, name: String, depth: usize, out: &mut Vec<OutlineEntry>) {
+    let mut type_label = schema.type_label();
+    if let Some(unit) = &schema.unit {
+        type_label = format!("{}, {}", type_label, unit);
+    }
+    out.push(OutlineEntry {
+        depth,
+        name,
+        type_label,
+        description: schema.description.clone(),
+    });
+    for alt in &schema.one_of {
+        outline_into(alt, String::new(), depth + 1, out);
+    }
+    for (key, field) in &schema.properties {
+        outline_into(field, key.clone(), depth + 1, out);
+    }
+    if let Some(extra) = schema.additional_schema()
+        && (extra.schema_type.is_some() || !extra.one_of.is_empty())
+    {
+        outline_into(&extra, "<key>".to_string(), depth + 1, out);
+    }
+    if let Some(items) = &schema.items {
+        outline_into(items, "[]".to_string(), depth + 1, out);
+    }
+}
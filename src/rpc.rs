@@ -7,12 +7,17 @@ use reqwest::Client;
 use serde::de::DeserializeOwned;
 use serde_json::{Value, json};
 
+use crate::method_class::{MethodClass, classify};
 use crate::rpc_types::*;
 
 pub struct RpcClient {
     url: String,
     auth: Auth,
     client: Client,
+    /// Speak JSON-RPC 2.0 (Bitcoin Core v28+) instead of the legacy 1.0 dialect.
+    jsonrpc2: bool,
+    /// Refuse `call_raw` for methods that aren't read-only (`--read-only`).
+    read_only: bool,
 }
 
 enum Auth {
@@ -47,9 +52,25 @@ impl RpcClient {
             url,
             auth,
             client,
+            jsonrpc2: false,
+            read_only: false,
         }
     }
 
+    pub fn jsonrpc2(mut self, enabled: bool) -> Self {
+        self.jsonrpc2 = enabled;
+        self
+    }
+
+    pub fn read_only(mut self, enabled: bool) -> Self {
+        self.read_only = enabled;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
     async fn auth_header(&self) -> Result<String, String> {
         match &self.auth {
             Auth::UserPass { user, pass } => Ok(format!(
@@ -69,7 +90,7 @@ impl RpcClient {
         tracing::debug!(method, %params, url, "rpc request");
         let auth = self.auth_header().await?;
         let body = json!({
-            "jsonrpc": "1.0",
+            "jsonrpc": if self.jsonrpc2 { "2.0" } else { "1.0" },
             "id": method,
             "method": method,
             "params": params,
@@ -94,23 +115,26 @@ impl RpcClient {
             .await
             .map_err(|e| format!("Failed to read response: {}", e))?;
 
-        if !status.is_success() {
-            tracing::error!(method, %status, "rpc error");
-            return Err(format!("RPC error ({}): {}", status, text));
-        }
-
-        let parsed: Value =
-            serde_json::from_str(&text).map_err(|e| format!("Invalid JSON: {}", e))?;
+        // Legacy replies carry errors with HTTP 404/500, so the body is checked first.
+        let parsed: Value = match serde_json::from_str(&text) {
+            Ok(parsed) => parsed,
+            Err(_) if !status.is_success() => {
+                tracing::error!(method, %status, "rpc error");
+                return Err(format!("RPC error ({}): {}", status, text));
+            }
+            Err(e) => return Err(format!("Invalid JSON: {}", e)),
+        };
 
-        if let Some(err) = parsed.get("error")
-            && !err.is_null()
-        {
-            tracing::error!(method, %err, "rpc error response");
-            return Err(format!("RPC error: {}", err));
+        let result = if self.jsonrpc2 {
+            parse_v2_response(method, parsed)
+        } else {
+            parse_v1_response(parsed)
+        };
+        match &result {
+            Ok(_) => tracing::debug!(method, "rpc response ok"),
+            Err(err) => tracing::error!(method, %err, "rpc error response"),
         }
-
-        tracing::debug!(method, "rpc response ok");
-        Ok(parsed["result"].clone())
+        result
     }
 
     async fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, String> {
@@ -124,6 +148,14 @@ impl RpcClient {
         params: Value,
         wallet: Option<&str>,
     ) -> Result<Value, String> {
+        let class = classify(method);
+        if self.read_only && class != MethodClass::ReadOnly {
+            return Err(format!(
+                "{} is blocked by --read-only: it {}",
+                method,
+                class.label()
+            ));
+        }
         let url = match wallet {
             Some(name) if !name.is_empty() => {
                 let mut wallet_url = reqwest::Url::parse(&self.url)
@@ -166,6 +198,23 @@ impl RpcClient {
         self.call("getnettotals", json!([])).await
     }
 
+    pub async fn get_addrman_info(
+        &self,
+    ) -> Result<std::collections::BTreeMap<String, AddrmanCounts>, String> {
+        self.call("getaddrmaninfo", json!([])).await
+    }
+
+    pub async fn get_node_addresses(
+        &self,
+        count: u64,
+        network: Option<&str>,
+    ) -> Result<Vec<NodeAddress>, String> {
+        match network {
+            Some(net) => self.call("getnodeaddresses", json!([count, net])).await,
+            None => self.call("getnodeaddresses", json!([count])).await,
+        }
+    }
+
     pub async fn get_chain_tips(&self) -> Result<Vec<ChainTip>, String> {
         self.call("getchaintips", json!([])).await
     }
@@ -210,6 +259,62 @@ impl RpcClient {
     }
 }
 
+fn parse_v1_response(mut parsed: Value) -> Result<Value, String> {
+    match parsed.get("error") {
+        Some(err) if !err.is_null() => Err(format_rpc_error(err)),
+        _ => Ok(parsed["result"].take()),
+    }
+}
+
+/// JSON-RPC 2.0 replies must echo the version and id and carry exactly one of
+/// `result` and `error`, with a well-formed error object.
+fn parse_v2_response(method: &str, parsed: Value) -> Result<Value, String> {
+    let Value::Object(mut obj) = parsed else {
+        return Err("Invalid JSON-RPC 2.0 response: not an object".to_string());
+    };
+    if obj.get("jsonrpc").and_then(|v| v.as_str()) != Some("2.0") {
+        return Err(
+            "Node replied with JSON-RPC 1.0; --jsonrpc2 needs Bitcoin Core v28 or newer"
+                .to_string(),
+        );
+    }
+    if obj.get("id").and_then(|v| v.as_str()) != Some(method) {
+        return Err(format!(
+            "Invalid JSON-RPC 2.0 response: id {} does not match the request",
+            obj.get("id").unwrap_or(&Value::Null)
+        ));
+    }
+    match (obj.remove("result"), obj.remove("error")) {
+        (Some(result), None) => Ok(result),
+        (None, Some(err)) => {
+            let well_formed = err.get("code").is_some_and(|c| c.is_i64())
+                && err.get("message").is_some_and(|m| m.is_string());
+            if well_formed {
+                Err(format_rpc_error(&err))
+            } else {
+                Err(format!("Invalid JSON-RPC 2.0 error object: {}", err))
+            }
+        }
+        (Some(_), Some(_)) => {
+            Err("Invalid JSON-RPC 2.0 response: both result and error are set".to_string())
+        }
+        (None, None) => {
+            Err("Invalid JSON-RPC 2.0 response: neither result nor error is set".to_string())
+        }
+    }
+}
+
+/// `RPC error -8: message` for well-formed errors, the raw JSON otherwise.
+fn format_rpc_error(err: &Value) -> String {
+    match (
+        err.get("code").and_then(|c| c.as_i64()),
+        err.get("message").and_then(|m| m.as_str()),
+    ) {
+        (Some(code), Some(message)) => format!("RPC error {}: {}", code, message),
+        _ => format!("RPC error: {}", err),
+    }
+}
+
 pub fn default_cookie_path(network_subdir: Option<&str>) -> PathBuf {
     let mut path = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
     path.push(".bitcoin");
@@ -0,0 +1,293 @@
+//! Macros: script files of RPC calls run in order, capturing results into variables and
+//! checking them with assertions. Run from the macro palette or with `--run-macro`.
+//!
+//! ```text
+//! # Fund a fresh wallet on regtest
+//! createwallet "smoke"
+//! wallet smoke
+//! getnewaddress -> addr
+//! generatetoaddress 101, $addr
+//! getbalances -> trusted = .mine.trusted
+//! assert $trusted > 0
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde_json::Value;
+
+use crate::jq;
+use crate::method_class::{MethodClass, classify};
+use crate::raw_args::unquoted_chars;
+
+/// Result summaries longer than this are cut in step output.
+const MAX_DETAIL: usize = 200;
+
+pub enum StepKind {
+    /// `wallet <name>`: later calls go to this wallet; `wallet` alone goes back to none.
+    Wallet(Option<String>),
+    /// `set <var> = <json>`
+    Set(String, Value),
+    /// `<method> [args] [-> <var> [= <filter>]]`, args written as in the raw args prompt.
+    Call {
+        method: String,
+        args: String,
+        capture: Option<(String, Option<String>)>,
+    },
+    /// `assert <filter>`: passes when the filter, run on the last result, gives a value
+    /// other than `false` or `null`.
+    Assert(String),
+}
+
+pub struct Step {
+    pub line: usize,
+    pub text: String,
+    pub kind: StepKind,
+}
+
+pub struct Macro {
+    pub name: String,
+    /// The first comment line.
+    pub description: String,
+    pub steps: Vec<Step>,
+}
+
+impl Macro {
+    pub fn load(path: &Path) -> Result<Macro, String> {
+        let text =
+            std::fs::read_to_string(path).map_err(|e| format!("read {}: {}", path.display(), e))?;
+        let name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let description = text
+            .lines()
+            .find_map(|l| l.trim().strip_prefix('#'))
+            .map(|d| d.trim().to_string())
+            .unwrap_or_default();
+        Ok(Macro {
+            name,
+            description,
+            steps: parse(&text)?,
+        })
+    }
+
+    /// The most harmful class among the macro's calls.
+    pub fn class(&self) -> MethodClass {
+        self.steps
+            .iter()
+            .filter_map(|s| match &s.kind {
+                StepKind::Call { method, .. } => Some(classify(method)),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(MethodClass::ReadOnly)
+    }
+}
+
+/// Macro files (`*.macro`) in `dir`, sorted by name.
+pub fn list(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| Some(e.ok()?.path()))
+        .filter(|p| p.extension().is_some_and(|ext| ext == "macro"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+pub fn parse(text: &str) -> Result<Vec<Step>, String> {
+    let mut steps = Vec::new();
+    for (i, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let kind = parse_step(line).map_err(|e| format!("line {}: {}", i + 1, e))?;
+        steps.push(Step {
+            line: i + 1,
+            text: line.to_string(),
+            kind,
+        });
+    }
+    Ok(steps)
+}
+
+fn parse_step(line: &str) -> Result<StepKind, String> {
+    let (word, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+    let rest = rest.trim();
+    match word {
+        "wallet" => Ok(StepKind::Wallet(
+            (!rest.is_empty()).then(|| rest.to_string()),
+        )),
+        "set" => {
+            let (var, value) = rest.split_once('=').ok_or("expected set <var> = <json>")?;
+            let var = variable_name(var.trim())?;
+            let value = serde_json::from_str(value.trim())
+                .map_err(|e| format!("{} is not JSON: {}", value.trim(), e))?;
+            Ok(StepKind::Set(var, value))
+        }
+        "assert" if rest.is_empty() => Err("assert needs a filter".to_string()),
+        "assert" => Ok(StepKind::Assert(rest.to_string())),
+        method if method.contains("->") => Err("put a space between the method and ->".to_string()),
+        method => {
+            let arrow = unquoted_chars(rest)
+                .filter(|&(i, c)| c == '-' && rest[i + 1..].starts_with('>'))
+                .last();
+            let (args, capture) = match arrow.map(|(i, _)| (&rest[..i], &rest[i + 2..])) {
+                Some((args, target)) => {
+                    let (var, filter) = match target.split_once('=') {
+                        Some((var, filter)) => (var, Some(filter.trim().to_string())),
+                        None => (target, None),
+                    };
+                    (args.trim(), Some((variable_name(var.trim())?, filter)))
+                }
+                None => (rest, None),
+            };
+            Ok(StepKind::Call {
+                method: method.to_string(),
+                args: args.to_string(),
+                capture,
+            })
+        }
+    }
+}
+
+fn variable_name(name: &str) -> Result<String, String> {
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(name.to_string())
+    } else {
+        Err(format!("'{}' is not a variable name", name))
+    }
+}
+
+/// Variables, the current wallet and the last call's result while a macro runs.
+#[derive(Default)]
+pub struct MacroState {
+    pub vars: BTreeMap<String, Value>,
+    pub wallet: Option<String>,
+    pub last: Value,
+}
+
+impl MacroState {
+    /// Replaces each `$var` outside string literals in call args with the variable's JSON.
+    pub fn substitute(&self, args: &str) -> Result<String, String> {
+        let mut out = String::new();
+        let mut copied = 0;
+        let dollars: Vec<usize> = unquoted_chars(args)
+            .filter(|&(_, c)| c == '$')
+            .map(|(i, _)| i)
+            .collect();
+        for pos in dollars {
+            let after = &args[pos + 1..];
+            let end = after
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .unwrap_or(after.len());
+            let name = &after[..end];
+            if name.is_empty() {
+                continue;
+            }
+            let value = self
+                .vars
+                .get(name)
+                .ok_or_else(|| format!("${} is not defined", name))?;
+            out.push_str(&args[copied..pos]);
+            out.push_str(&value.to_string());
+            copied = pos + 1 + end;
+        }
+        out.push_str(&args[copied..]);
+        Ok(out)
+    }
+
+    /// Records a call's result, storing it (or what `filter` picks from it) in `var`.
+    /// Returns what to show for the step.
+    pub fn record(
+        &mut self,
+        result: Value,
+        capture: Option<&(String, Option<String>)>,
+    ) -> Result<String, String> {
+        self.last = result;
+        let Some((var, filter)) = capture else {
+            return Ok(summarize(&self.last));
+        };
+        let value = match filter {
+            Some(filter) => {
+                let mut outputs = jq::apply_with_vars(filter, &self.last, &self.vars)?;
+                match outputs.len() {
+                    0 => return Err(format!("{} gave no output", filter)),
+                    1 => outputs.remove(0),
+                    _ => Value::Array(outputs),
+                }
+            }
+            None => self.last.clone(),
+        };
+        let detail = format!("{} = {}", var, summarize(&value));
+        self.vars.insert(var.clone(), value);
+        Ok(detail)
+    }
+
+    pub fn check(&self, filter: &str) -> Result<String, String> {
+        let outputs = jq::apply_with_vars(filter, &self.last, &self.vars)?;
+        match outputs.first() {
+            Some(Value::Bool(false) | Value::Null) | None => Err(format!(
+                "assertion failed, got {}",
+                summarize(&Value::Array(outputs))
+            )),
+            Some(_) => Ok("ok".to_string()),
+        }
+    }
+}
+
+/// What happened in one step, for the TUI run view and `--run-macro` output.
+pub struct StepReport {
+    pub line: usize,
+    pub text: String,
+    pub ok: bool,
+    pub detail: String,
+    pub duration_ms: u64,
+}
+
+/// Compact JSON, cut to `MAX_DETAIL` characters.
+pub fn summarize(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() > MAX_DETAIL {
+        text.chars().take(MAX_DETAIL - 1).collect::<String>() + "…"
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arrow_inside_a_string_is_an_argument() {
+        let steps = parse(r#"setlabel $addr, "a -> b" -> out"#).unwrap();
+        let StepKind::Call { args, capture, .. } = &steps[0].kind else {
+            panic!("not a call");
+        };
+        assert_eq!(args, r#"$addr, "a -> b""#);
+        assert_eq!(capture.as_ref().unwrap().0, "out");
+
+        let steps = parse(r#"setlabel $addr, "a -> b""#).unwrap();
+        let StepKind::Call { args, capture, .. } = &steps[0].kind else {
+            panic!("not a call");
+        };
+        assert_eq!(args, r#"$addr, "a -> b""#);
+        assert!(capture.is_none());
+    }
+
+    #[test]
+    fn variables_inside_strings_are_left_alone() {
+        let mut state = MacroState::default();
+        state.vars.insert("addr".to_string(), Value::from("bcrt1q"));
+        let args = state.substitute(r#"$addr, "costs $5 for $addr""#).unwrap();
+        assert_eq!(args, r#""bcrt1q", "costs $5 for $addr""#);
+        assert!(state.substitute("$missing").is_err());
+    }
+}
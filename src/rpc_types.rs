@@ -160,6 +160,29 @@ pub struct PeerInfo {
     pub extra: HashMap<String, Value>,
 }
 
+#[derive(Deserialize, Clone, Default)]
+pub struct AddrmanCounts {
+    #[serde(default)]
+    pub new: u64,
+    #[serde(default)]
+    pub tried: u64,
+    #[serde(default)]
+    pub total: u64,
+}
+
+#[derive(Deserialize, Clone, Default)]
+pub struct NodeAddress {
+    #[serde(default)]
+    pub time: u64,
+    #[serde(default)]
+    pub services: u64,
+    pub address: String,
+    #[serde(default)]
+    pub port: u16,
+    #[serde(default)]
+    pub network: String,
+}
+
 #[derive(Deserialize, Clone, Default)]
 pub struct BlockStats {
     pub height: u64,
@@ -0,0 +1,206 @@
+//! The `--signer` command: an HWI-compatible program run once per request.
+
+/// Splits a `--signer` command line into words the way a POSIX shell would, without
+/// expansions: single quotes keep everything literal, double quotes allow `\"` and
+/// `\\`, and a backslash outside quotes escapes the next character. This lets paths
+/// with spaces be quoted, e.g. `'/opt/my signer/hwi' --emulators`.
+pub fn split(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated ' in --signer command".to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => {
+                                return Err("unterminated \" in --signer command".to_string());
+                            }
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated \" in --signer command".to_string()),
+                    }
+                }
+            }
+            '\\' => {
+                if let Some(c) = chars.next() {
+                    word.get_or_insert_with(String::new).push(c);
+                }
+            }
+            c if c.is_whitespace() => words.extend(word.take()),
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Runs an HWI-compatible command and parses its JSON output. An `error` field in the
+/// output is reported as a failure.
+pub async fn run(
+    command: &str,
+    args: &[&str],
+    stdin: Option<String>,
+) -> Result<serde_json::Value, String> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    let words = split(command)?;
+    let (program, prefix) = words.split_first().ok_or("empty --signer command")?;
+    let mut child = tokio::process::Command::new(program)
+        .args(prefix)
+        .args(args)
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| format!("failed to run signer {}: {}", program, e))?;
+
+    if let (Some(input), Some(mut pipe)) = (stdin, child.stdin.take()) {
+        pipe.write_all(input.as_bytes())
+            .await
+            .map_err(|e| format!("failed to write to signer: {}", e))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("signer failed: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "signer exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let value: serde_json::Value = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("signer returned invalid JSON: {}", e))?;
+    if let Some(error) = value.get("error") {
+        return Err(format!(
+            "signer error: {}",
+            error
+                .as_str()
+                .map(str::to_string)
+                .unwrap_or_else(|| error.to_string())
+        ));
+    }
+    Ok(value)
+}
+
+/// Signs the way bitcoind does: `<cmd> --stdin --fingerprint <fp> --chain <chain>` with
+/// `signtx <psbt>` on stdin. Returns the command's output and the PSBT it sent back.
+pub async fn sign_tx(
+    command: &str,
+    psbt: &str,
+    fingerprint: &str,
+    chain: &str,
+) -> Result<(serde_json::Value, String), String> {
+    let value = run(
+        command,
+        &["--stdin", "--fingerprint", fingerprint, "--chain", chain],
+        Some(format!("signtx {}\n", psbt)),
+    )
+    .await?;
+    let signed = value
+        .get("psbt")
+        .and_then(|v| v.as_str())
+        .map(str::to_string)
+        .ok_or("signer returned no psbt")?;
+    Ok((value, signed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // BIP174 vectors for the same transaction: unsigned, and with input 0 finalized.
+    const OUTPUTS_FILLED: &str = "cHNidP8BAKACAAAAAqsJSaCMWvfEm4IS9Bfi8Vqz9cM9zxU4IagTn4d6W3vkAAAAAAD+////qwlJoIxa98SbghL0F+LxWrP1wz3PFTghqBOfh3pbe+QBAAAAAP7///8CYDvqCwAAAAAZdqkUdopAu9dAy+gdmI5x3ipNXHE5ax2IrI4kAAAAAAAAGXapFG9GILVT+glechue4O/p+gOcykWXiKwAAAAAAAEA3wIAAAABJoFxNx7f8oXpN63upLN7eAAMBWbLs61kZBcTykIXG/YAAAAAakcwRAIgcLIkUSPmv0dNYMW1DAQ9TGkaXSQ18Jo0p2YqncJReQoCIAEynKnazygL3zB0DsA5BCJCLIHLRYOUV663b8Eu3ZWzASECZX0RjTNXuOD0ws1G23s59tnDjZpwq8ubLeXcjb/kzjH+////AtPf9QUAAAAAGXapFNDFmQPFusKGh2DpD9UhpGZap2UgiKwA4fUFAAAAABepFDVF5uM7gyxHBQ8k0+65PJwDlIvHh7MuEwAAAQEgAOH1BQAAAAAXqRQ1RebjO4MsRwUPJNPuuTycA5SLx4cBBBYAFIXRNTfy4mVAWjTbr6nj3aAfuCMIACICAurVlmh8qAYEPtw94RbN8p1eklfBls0FXPaYyNAr8k6ZELSmumcAAACAAAAAgAIAAIAAIgIDlPYr6d8ZlSxVh3aK63aYBhrSxKJciU9H2MFitNchPQUQtKa6ZwAAAIABAACAAgAAgAA=";
+    const FINALIZED_INPUT: &str = "cHNidP8BAKACAAAAAqsJSaCMWvfEm4IS9Bfi8Vqz9cM9zxU4IagTn4d6W3vkAAAAAAD+////qwlJoIxa98SbghL0F+LxWrP1wz3PFTghqBOfh3pbe+QBAAAAAP7///8CYDvqCwAAAAAZdqkUdopAu9dAy+gdmI5x3ipNXHE5ax2IrI4kAAAAAAAAGXapFG9GILVT+glechue4O/p+gOcykWXiKwAAAAAAAEHakcwRAIgR1lmF5fAGwNrJZKJSGhiGDR9iYZLcZ4ff89X0eURZYcCIFMJ6r9Wqk2Ikf/REf3xM286KdqGbX+EhtdVRs7tr5MZASEDXNxh/HupccC1AaZGoqg7ECy0OIEhfKaC3Ibi1z+ogpIAAQEgAOH1BQAAAAAXqRQ1RebjO4MsRwUPJNPuuTycA5SLx4cBBBYAFIXRNTfy4mVAWjTbr6nj3aAfuCMIAAAA";
+
+    /// `contrib/stub-signer.py` run through `python3`, with extra environment variables.
+    fn stub(env: &[(&str, &str)]) -> String {
+        let script = concat!(env!("CARGO_MANIFEST_DIR"), "/contrib/stub-signer.py");
+        let vars: Vec<String> = env.iter().map(|(k, v)| format!("{}='{}'", k, v)).collect();
+        format!("env {} python3 '{}'", vars.join(" "), script)
+    }
+
+    #[tokio::test]
+    async fn stub_signer_enumerates_and_signs() {
+        let devices = run(&stub(&[]), &["enumerate"], None).await.unwrap();
+        assert_eq!(devices[0]["fingerprint"], "00000001");
+
+        let signed_path = std::env::temp_dir().join(format!(
+            "bitcoin-tui signer test {}.psbt",
+            std::process::id()
+        ));
+        std::fs::write(&signed_path, FINALIZED_INPUT).unwrap();
+        let command = stub(&[("STUB_SIGNER_PSBT", signed_path.to_str().unwrap())]);
+        let result = sign_tx(&command, OUTPUTS_FILLED, "00000001", "regtest").await;
+        let _ = std::fs::remove_file(&signed_path);
+        let (output, signed) = result.unwrap();
+        assert_eq!(output["psbt"], FINALIZED_INPUT);
+
+        let before = crate::psbt::parse(OUTPUTS_FILLED).unwrap();
+        let after = crate::psbt::parse(&signed).unwrap();
+        assert_eq!(
+            crate::psbt::signature_changes(&before, &after),
+            ["+ input 0 gained final scriptSig"]
+        );
+    }
+
+    #[tokio::test]
+    async fn stub_signer_errors_are_reported() {
+        let err = sign_tx(&stub(&[]), OUTPUTS_FILLED, "deadbeef", "regtest")
+            .await
+            .unwrap_err();
+        assert_eq!(err, "signer error: no device with fingerprint deadbeef");
+
+        let err = run(
+            &stub(&[("STUB_SIGNER_FAIL", "device locked")]),
+            &["enumerate"],
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err, "signer error: device locked");
+    }
+
+    #[test]
+    fn split_follows_shell_quoting() {
+        assert_eq!(split("hwi  --emulators").unwrap(), ["hwi", "--emulators"]);
+        assert_eq!(
+            split("'/opt/my signer/hwi' --chain\\ x").unwrap(),
+            ["/opt/my signer/hwi", "--chain x"]
+        );
+        assert_eq!(
+            split(r#"python3 "a \"b\" \c" ''"#).unwrap(),
+            ["python3", r#"a "b" \c"#, ""]
+        );
+        assert_eq!(split("  ").unwrap(), Vec::<String>::new());
+        assert!(split("'hwi").is_err());
+        assert!(split("\"hwi\\").is_err());
+    }
+}
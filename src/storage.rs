@@ -0,0 +1,122 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Per-node state directory, e.g. `~/.local/share/bitcoin-tui/127.0.0.1-8332`.
+pub fn profile_dir(profile: &str) -> Option<PathBuf> {
+    let name: String = profile
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let mut path = dirs::data_local_dir()?;
+    path.push("bitcoin-tui");
+    path.push(name);
+    Some(path)
+}
+
+/// Where the macro palette looks for `*.macro` files, e.g.
+/// `~/.config/bitcoin-tui/macros` on Linux.
+pub fn macro_dir() -> Option<PathBuf> {
+    let mut path = dirs::config_dir()?;
+    path.push("bitcoin-tui");
+    path.push("macros");
+    Some(path)
+}
+
+/// Opens `path` for writing, readable only by the user: the files can hold call
+/// arguments and results.
+fn open_private(path: &Path, append: bool) -> Result<std::fs::File, String> {
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).write(true);
+    if append {
+        options.append(true);
+    } else {
+        options.truncate(true);
+    }
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let file = options
+        .open(path)
+        .map_err(|e| format!("open {}: {}", path.display(), e))?;
+    // Files written before they were private keep their mode otherwise.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("chmod {}: {}", path.display(), e))?;
+    }
+    Ok(file)
+}
+
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Option<T> {
+    let content = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+pub fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("create {}: {}", parent.display(), e))?;
+    }
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    open_private(path, false)?
+        .write_all(json.as_bytes())
+        .map_err(|e| format!("write {}: {}", path.display(), e))
+}
+
+pub fn append_jsonl<T: Serialize>(path: &Path, items: &[T]) -> Result<(), String> {
+    if items.is_empty() {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("create {}: {}", parent.display(), e))?;
+    }
+    let mut file = open_private(path, true)?;
+    for item in items {
+        let line = serde_json::to_string(item).map_err(|e| e.to_string())?;
+        writeln!(file, "{}", line).map_err(|e| format!("write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Reads the newest `max` records, compacting the file when it has grown well past that.
+pub fn read_jsonl_tail<T: Serialize + DeserializeOwned>(path: &Path, max: usize) -> Vec<T> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return Vec::new();
+    };
+    let lines: Vec<&str> = content.lines().filter(|l| !l.trim().is_empty()).collect();
+    let start = lines.len().saturating_sub(max);
+    let items: Vec<T> = lines[start..]
+        .iter()
+        .filter_map(|l| serde_json::from_str(l).ok())
+        .collect();
+
+    if lines.len() > max * 2 {
+        let _ = write_jsonl(path, &items);
+    }
+    items
+}
+
+pub fn write_jsonl<T: Serialize>(path: &Path, items: &[T]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("create {}: {}", parent.display(), e))?;
+    }
+    let mut out = String::new();
+    for item in items {
+        out.push_str(&serde_json::to_string(item).map_err(|e| e.to_string())?);
+        out.push('\n');
+    }
+    open_private(path, false)?
+        .write_all(out.as_bytes())
+        .map_err(|e| format!("write {}: {}", path.display(), e))
+}
@@ -0,0 +1,118 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::App;
+use crate::call_history::{HistoryRow, args_text};
+use crate::format::fmt_relative_time;
+
+const MAX_ARGS_WIDTH: usize = 48;
+
+pub fn render(app: &App, frame: &mut Frame, area: Rect) {
+    let rows = app.history_rows();
+    let height = (rows.len() as u16 + 3).clamp(5, area.height.saturating_sub(4));
+    let width = area.width.saturating_sub(8).min(110);
+
+    let popup = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Call History")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let history = &app.call_history;
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let (call, record) = match *row {
+                HistoryRow::Favorite(i) => (history.favorites[i].clone(), None),
+                HistoryRow::Call(i) => (history.calls[i].favorite(), Some(&history.calls[i])),
+            };
+            let star = if history.is_favorite(&call) {
+                "★ "
+            } else {
+                "  "
+            };
+            let mut spans = vec![Span::styled(star, Style::default().fg(Color::Yellow))];
+            match record {
+                Some(record) => {
+                    let (mark, color) = if record.success {
+                        ("✓ ", Color::Green)
+                    } else {
+                        ("✗ ", Color::Red)
+                    };
+                    spans.push(Span::styled(mark, Style::default().fg(color)));
+                    spans.push(Span::styled(
+                        format!("{:>10}  ", fmt_relative_time(record.time)),
+                        dim,
+                    ));
+                }
+                None => spans.push(Span::styled(format!("{:<14}", "favorite"), dim)),
+            }
+            spans.push(Span::styled(
+                call.method.clone(),
+                Style::default().fg(Color::Cyan),
+            ));
+            let mut args = args_text(&call.params);
+            if args.chars().count() > MAX_ARGS_WIDTH {
+                args = args.chars().take(MAX_ARGS_WIDTH - 1).collect::<String>() + "…";
+            }
+            if !args.is_empty() {
+                spans.push(Span::raw(format!(" {}", args)));
+            }
+            if let Some(record) = record {
+                spans.push(Span::styled(format!("  {} ms", record.duration_ms), dim));
+            }
+            if let Some(wallet) = &call.wallet {
+                spans.push(Span::styled(format!("  [{}]", wallet), dim));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    if items.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled("  No calls yet", dim)),
+            chunks[0],
+        );
+    } else {
+        let mut state = ListState::default();
+        state.select(Some(app.history.selected.min(items.len() - 1)));
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol(">> "),
+            chunks[0],
+            &mut state,
+        );
+    }
+
+    if let Some(status) = &app.history.status {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                status.clone(),
+                Style::default().fg(Color::Yellow),
+            )),
+            chunks[1],
+        );
+    }
+}
@@ -140,14 +140,20 @@ fn render_recent_blocks(app: &App, frame: &mut Frame, area: Rect) {
     };
 
     let right = |s: String| Line::from(s).alignment(Alignment::Right);
-    let header = Row::new(["Height", "Txs", "Size", "Weight", "Fee", "Age", "Pool"].map(|s| {
-        Cell::from(Line::from(s).alignment(if s == "Pool" {
-            Alignment::Left
-        } else {
-            Alignment::Right
-        }))
-    }))
-    .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD));
+    let header = Row::new(
+        ["Height", "Txs", "Size", "Weight", "Fee", "Age", "Pool"].map(|s| {
+            Cell::from(Line::from(s).alignment(if s == "Pool" {
+                Alignment::Left
+            } else {
+                Alignment::Right
+            }))
+        }),
+    )
+    .style(
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    );
 
     const MAX_WEIGHT: f64 = 4_000_000.0;
     let max_rows = area.height.saturating_sub(4) as usize;
@@ -216,11 +222,9 @@ fn render_block_chart(app: &App, frame: &mut Frame, area: Rect) {
     const BAR_GAP: u16 = 2;
     let inner = block.inner(area);
     let per_bar = BAR_WIDTH + BAR_GAP;
-    let bars_fit = if per_bar > 0 {
-        ((inner.width + BAR_GAP) / per_bar).max(1) as usize
-    } else {
-        1
-    };
+    let bars_fit = (inner.width + BAR_GAP)
+        .checked_div(per_bar)
+        .map_or(1, |n| n.max(1) as usize);
     let start = app.recent_blocks.len().saturating_sub(bars_fit);
     let visible_blocks = &app.recent_blocks[start..];
 
@@ -254,7 +258,9 @@ fn render_block_chart(app: &App, frame: &mut Frame, area: Rect) {
 }
 
 fn render_chain_details(app: &App, frame: &mut Frame, area: Rect) {
-    let block = Block::default().borders(Borders::ALL).title("Chain Details");
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Chain Details");
     let Some(info) = &app.blockchain else {
         frame.render_widget(Paragraph::new("Connecting...").block(block), area);
         return;
@@ -265,7 +271,11 @@ fn render_chain_details(app: &App, frame: &mut Frame, area: Rect) {
         kv("Disk", fmt_bytes(info.size_on_disk), Color::White),
         kv(
             "IBD",
-            if info.initialblockdownload { "yes" } else { "no" },
+            if info.initialblockdownload {
+                "yes"
+            } else {
+                "no"
+            },
             if info.initialblockdownload {
                 Color::Yellow
             } else {
@@ -291,9 +301,7 @@ fn render_chain_details(app: &App, frame: &mut Frame, area: Rect) {
 }
 
 fn render_chain_tips(app: &App, frame: &mut Frame, area: Rect) {
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title("Chain Tips");
+    let block = Block::default().borders(Borders::ALL).title("Chain Tips");
     let Some(tips) = &app.chaintips else {
         frame.render_widget(Paragraph::new("Connecting...").block(block), area);
         return;
@@ -307,7 +315,7 @@ fn render_chain_tips(app: &App, frame: &mut Frame, area: Rect) {
 
     let max_rows = area.height.saturating_sub(4) as usize;
     let mut sorted: Vec<_> = tips.iter().collect();
-    sorted.sort_by(|a, b| b.height.cmp(&a.height));
+    sorted.sort_by_key(|t| std::cmp::Reverse(t.height));
 
     let rows: Vec<Row> = sorted
         .iter()
@@ -347,7 +355,6 @@ fn render_chain_tips(app: &App, frame: &mut Frame, area: Rect) {
     frame.render_widget(table, area);
 }
 
-
 fn render_network_compact(app: &App, frame: &mut Frame, area: Rect) {
     let block = Block::default().borders(Borders::ALL).title("Network");
 
@@ -383,11 +390,7 @@ fn render_network_compact(app: &App, frame: &mut Frame, area: Rect) {
         kv("Version", info.version.to_string(), Color::White),
         kv("Protocol", fmt_number(info.protocolversion), Color::White),
         kv("Relay Fee", fmt_sat_per_vb(info.relayfee), Color::White),
-        kv(
-            "Services",
-            info.localservicesnames.join(", "),
-            Color::White,
-        ),
+        kv("Services", info.localservicesnames.join(", "), Color::White),
     ];
 
     let mut traffic_lines: Vec<Line> = vec![Line::raw("")];
@@ -527,24 +530,25 @@ fn render_mempool_compact(app: &App, frame: &mut Frame, area: Rect) {
             fmt_sat_per_vb(info.mempoolminfee.as_f64().unwrap_or(0.0)),
             Color::White,
         ),
-        kv("Unbroadcast", fmt_number(info.unbroadcastcount), Color::White),
+        kv(
+            "Unbroadcast",
+            fmt_number(info.unbroadcastcount),
+            Color::White,
+        ),
     ];
 
     frame.render_widget(Paragraph::new(lines).block(block), area);
 }
 
 fn render_gauges(app: &App, frame: &mut Frame, area: Rect) {
-    let cols =
-        Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(area);
+    let cols = Layout::horizontal([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)]).split(area);
     render_sync_gauge(app, frame, cols[0]);
     render_mem_gauge(app, frame, cols[1]);
 }
 
 fn render_sync_gauge(app: &App, frame: &mut Frame, area: Rect) {
     let Some(info) = &app.blockchain else {
-        let block = Block::default()
-            .borders(Borders::ALL)
-            .title("Sync / Chain");
+        let block = Block::default().borders(Borders::ALL).title("Sync / Chain");
         frame.render_widget(Paragraph::new("Connecting...").block(block), area);
         return;
     };
@@ -585,13 +589,11 @@ fn render_mem_gauge(app: &App, frame: &mut Frame, area: Rect) {
     } else {
         Color::LightRed
     };
-    let block = Block::default()
-        .borders(Borders::ALL)
-        .title(format!(
-            "Mempool Memory {} / {}",
-            fmt_bytes(info.usage),
-            fmt_bytes(info.maxmempool)
-        ));
+    let block = Block::default().borders(Borders::ALL).title(format!(
+        "Mempool Memory {} / {}",
+        fmt_bytes(info.usage),
+        fmt_bytes(info.maxmempool)
+    ));
     let gauge = Gauge::default()
         .block(block)
         .gauge_style(Style::default().fg(fill_color).bg(Color::Black))
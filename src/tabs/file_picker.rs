@@ -0,0 +1,149 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::file_picker::{FileMode, FilePicker};
+
+/// Renders `picker` as a centered popup. `extra_help` is appended to the key hints so
+/// tabs can advertise their own picker keys.
+pub fn render(
+    picker: &FilePicker,
+    title: &str,
+    extra_help: Vec<Span<'static>>,
+    editing_name: bool,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let popup = Layout::vertical([Constraint::Length(area.height.saturating_sub(6))])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8))])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+
+    frame.render_widget(Clear, popup);
+
+    let title = match picker.mode {
+        FileMode::Load => format!("Load {}: {}", title, picker.dir.display()),
+        FileMode::Save => format!(
+            "Save {}: {} (file: {})",
+            title,
+            picker.dir.display(),
+            picker.save_name
+        ),
+    };
+
+    let items: Vec<ListItem> = picker
+        .entries
+        .iter()
+        .map(|entry| {
+            let prefix = if entry.is_dir {
+                "d "
+            } else if picker.marked.contains(&entry.path) {
+                "* "
+            } else {
+                "f "
+            };
+            let style = if entry.is_dir {
+                Style::default().fg(Color::Cyan)
+            } else if picker.marked.contains(&entry.path) {
+                Style::default().fg(Color::Yellow)
+            } else if picker.is_highlighted(&entry.path) {
+                Style::default().fg(Color::Green)
+            } else {
+                Style::default()
+            };
+            ListItem::new(format!("{}{}", prefix, entry.name)).style(style)
+        })
+        .collect();
+
+    let mut help = vec![
+        Span::styled("j/k", Style::default().fg(Color::DarkGray)),
+        Span::raw(" move  "),
+        Span::styled("Enter", Style::default().fg(Color::DarkGray)),
+        Span::raw(" open/select  "),
+        Span::styled("Esc", Style::default().fg(Color::DarkGray)),
+        Span::raw(" close"),
+    ];
+    if picker.multi_select && picker.mode == FileMode::Load {
+        help.push(Span::raw("  "));
+        help.push(Span::styled("Space", Style::default().fg(Color::DarkGray)));
+        help.push(Span::raw(if picker.marked.is_empty() {
+            " mark".to_string()
+        } else {
+            format!(" mark ({} marked, Enter loads them)", picker.marked.len())
+        }));
+    }
+    if let Some(ext) = picker.highlight_ext {
+        help.push(Span::raw("  "));
+        help.push(Span::styled(".", Style::default().fg(Color::DarkGray)));
+        help.push(Span::raw(if picker.filter_only {
+            " show all files".to_string()
+        } else {
+            format!(" only .{}", ext)
+        }));
+    }
+    if picker.mode == FileMode::Save {
+        help.push(Span::raw("  "));
+        help.push(Span::styled("w", Style::default().fg(Color::DarkGray)));
+        help.push(Span::raw(" write here  "));
+        help.push(Span::styled("e", Style::default().fg(Color::DarkGray)));
+        help.push(Span::raw(" edit filename"));
+    }
+    if !extra_help.is_empty() {
+        help.push(Span::raw("  "));
+        help.extend(extra_help);
+    }
+    if editing_name {
+        help.push(Span::raw("  "));
+        help.push(Span::styled(
+            "[editing filename]",
+            Style::default().fg(Color::Magenta),
+        ));
+    }
+
+    let help = match &picker.confirm_overwrite {
+        Some(path) => Line::from(vec![
+            Span::styled(
+                format!("{} exists. Overwrite? ", path.display()),
+                Style::default().fg(Color::Yellow),
+            ),
+            Span::styled("y", Style::default().fg(Color::DarkGray)),
+            Span::raw(" yes  "),
+            Span::styled("any other key", Style::default().fg(Color::DarkGray)),
+            Span::raw(" cancel"),
+        ]),
+        None => Line::from(help),
+    };
+
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(popup);
+    let mut state = ListState::default();
+    if !picker.entries.is_empty() {
+        state.select(Some(
+            picker.selected.min(picker.entries.len().saturating_sub(1)),
+        ));
+    }
+
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(title)
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .highlight_style(
+                Style::default()
+                    .fg(Color::Magenta)
+                    .add_modifier(Modifier::BOLD),
+            )
+            .highlight_symbol(">> "),
+        chunks[0],
+        &mut state,
+    );
+    frame.render_widget(Paragraph::new(help), chunks[1]);
+}
@@ -0,0 +1,139 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::json_tree::{JsonTree, PAGE, RowKind, TreeRow};
+
+/// Renders `tree` inside `block`, with the selected node's path (and its schema
+/// description, when the tree has one) under the rows. Only the visible rows are drawn.
+pub fn render(tree: &JsonTree, block: Block, frame: &mut Frame, area: Rect) {
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let footer_height = if tree.has_schema() { 3 } else { 1 };
+    let chunks =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(footer_height)]).split(inner);
+
+    let rows = tree.rows();
+    let height = chunks[0].height.max(1) as usize;
+    let offset = (tree.selected + 1).saturating_sub(height);
+    let items: Vec<ListItem> = rows
+        .iter()
+        .skip(offset)
+        .take(height)
+        .map(|row| ListItem::new(row_line(tree, row)))
+        .collect();
+    let mut state = ListState::default();
+    state.select(Some(tree.selected - offset));
+    frame.render_stateful_widget(
+        List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        chunks[0],
+        &mut state,
+    );
+
+    let Some(current) = tree.selected_row() else {
+        return;
+    };
+    let path = if current.path.is_empty() {
+        "."
+    } else {
+        current.path.as_str()
+    };
+    let mut path_line = vec![Span::styled(
+        path.to_string(),
+        Style::default().fg(Color::Cyan),
+    )];
+    if let Some(notice) = &tree.notice {
+        path_line.push(Span::styled(
+            format!("  {}", notice),
+            Style::default().fg(Color::Green),
+        ));
+    }
+    let mut lines = vec![Line::from(path_line)];
+    if let Some(desc) = &current.description {
+        lines.push(Line::from(Span::styled(
+            desc.replace('\n', " "),
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+    frame.render_widget(Paragraph::new(lines).wrap(Wrap { trim: false }), chunks[1]);
+}
+
+fn row_line(tree: &JsonTree, row: &TreeRow) -> Line<'static> {
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut spans = vec![Span::raw("  ".repeat(row.depth))];
+    if let RowKind::More(hidden) = row.kind {
+        spans.push(Span::styled(
+            format!("  … {} more (Enter shows {})", hidden, hidden.min(PAGE)),
+            dim.add_modifier(Modifier::ITALIC),
+        ));
+        return Line::from(spans);
+    }
+
+    let marker = match row.kind {
+        RowKind::Object(n) | RowKind::Array(n) if n > 0 => {
+            if row.open {
+                "▾ "
+            } else {
+                "▸ "
+            }
+        }
+        _ => "  ",
+    };
+    spans.push(Span::styled(marker, dim));
+    let matched = tree.is_match(&row.pointer);
+    let label_style = if matched {
+        Style::default()
+            .bg(Color::Magenta)
+            .fg(Color::White)
+            .add_modifier(Modifier::BOLD)
+    } else if row.label.starts_with('[') {
+        dim
+    } else {
+        Style::default().fg(Color::Cyan)
+    };
+    if !row.label.is_empty() {
+        spans.push(Span::styled(row.label.clone(), label_style));
+        spans.push(Span::raw(": "));
+    }
+
+    match row.kind {
+        RowKind::Object(n) => spans.push(Span::styled(
+            if row.open || n == 0 {
+                format!("{{{}}}", n)
+            } else {
+                format!("{{{}}} …", n)
+            },
+            Style::default().fg(Color::Yellow),
+        )),
+        RowKind::Array(n) => spans.push(Span::styled(
+            if row.open || n == 0 {
+                format!("[{}]", n)
+            } else {
+                format!("[{}] …", n)
+            },
+            Style::default().fg(Color::Yellow),
+        )),
+        _ => {
+            let text = match &row.short {
+                Some(short) if tree.annotations => short.clone(),
+                _ => row.text.clone(),
+            };
+            let style = if matched && row.label.is_empty() {
+                label_style
+            } else {
+                Style::default()
+            };
+            spans.push(Span::styled(text, style));
+            if tree.annotations
+                && let Some(annotation) = &row.annotation
+            {
+                spans.push(Span::styled(format!("  {}", annotation), dim));
+            }
+        }
+    }
+    Line::from(spans)
+}
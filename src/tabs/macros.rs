@@ -0,0 +1,189 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Flex, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::app::{App, MacroRun};
+use crate::storage;
+
+pub fn render(app: &App, frame: &mut Frame, area: Rect) {
+    let width = area.width.saturating_sub(8).min(100);
+    let height = area.height.saturating_sub(4).min(30);
+    let popup = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+
+    frame.render_widget(Clear, popup);
+
+    match &app.macros.run {
+        Some(run) => render_run(run, frame, popup),
+        None => render_palette(app, frame, popup),
+    }
+}
+
+fn render_palette(app: &App, frame: &mut Frame, area: Rect) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Macros")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let chunks = Layout::vertical([
+        Constraint::Min(1),
+        Constraint::Length(1),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let entries = app.macros.filtered();
+    if entries.is_empty() {
+        let dir = storage::macro_dir()
+            .map(|d| d.display().to_string())
+            .unwrap_or_else(|| "the config directory".to_string());
+        let text = if app.macros.entries.is_empty() {
+            format!("  No macros yet; add *.macro files to {}", dir)
+        } else {
+            "  No macro matches".to_string()
+        };
+        frame.render_widget(Paragraph::new(Span::styled(text, dim)), chunks[0]);
+    } else {
+        let items: Vec<ListItem> = entries
+            .iter()
+            .map(|entry| {
+                let mut spans = vec![Span::styled(
+                    entry.name.clone(),
+                    Style::default().fg(Color::Cyan),
+                )];
+                match &entry.description {
+                    Ok(d) if !d.is_empty() => spans.push(Span::styled(format!("  {}", d), dim)),
+                    Ok(_) => {}
+                    Err(e) => spans.push(Span::styled(
+                        format!("  {}", e),
+                        Style::default().fg(Color::Red),
+                    )),
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(app.macros.selected.min(items.len() - 1)));
+        frame.render_stateful_widget(
+            List::new(items)
+                .highlight_style(
+                    Style::default()
+                        .fg(Color::Magenta)
+                        .add_modifier(Modifier::BOLD),
+                )
+                .highlight_symbol(">> "),
+            chunks[0],
+            &mut state,
+        );
+    }
+
+    if let Some(status) = &app.macros.status {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                status.clone(),
+                Style::default().fg(Color::Red),
+            )),
+            chunks[1],
+        );
+    }
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::raw(app.macros.query.as_str()),
+            Span::styled("_", Style::default().fg(Color::Magenta)),
+        ])),
+        chunks[2],
+    );
+}
+
+fn render_run(run: &MacroRun, frame: &mut Frame, area: Rect) {
+    let failed = run.reports.iter().find(|r| !r.ok);
+    let (status, color) = if let Some(confirm) = &run.confirm {
+        let prompt = if confirm.class.needs_typed_confirmation() {
+            format!(
+                "This macro {}. Type {} and press Enter to run it: {}_",
+                confirm.class.label(),
+                run.name,
+                confirm.typed
+            )
+        } else {
+            format!("This macro {}. Run it? (y/n)", confirm.class.label())
+        };
+        (prompt, Color::Yellow)
+    } else if let Some(report) = failed {
+        (format!("failed at line {}", report.line), Color::Red)
+    } else if run.done {
+        ("passed".to_string(), Color::Green)
+    } else {
+        (
+            format!(
+                "running step {} of {}…",
+                run.reports.len() + 1,
+                run.steps.len()
+            ),
+            Color::Cyan,
+        )
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Macro: {}", run.name))
+        .border_style(Style::default().fg(color));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(inner);
+    frame.render_widget(
+        Paragraph::new(Span::styled(status, Style::default().fg(color))),
+        chunks[0],
+    );
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = Vec::new();
+    for (i, step) in run.steps.iter().enumerate() {
+        match run.reports.get(i) {
+            Some(report) => {
+                let (mark, mark_color) = if report.ok {
+                    ("✓ ", Color::Green)
+                } else {
+                    ("✗ ", Color::Red)
+                };
+                lines.push(Line::from(vec![
+                    Span::styled(mark, Style::default().fg(mark_color)),
+                    Span::raw(step.clone()),
+                    Span::styled(format!("  {} ms", report.duration_ms), dim),
+                ]));
+                let detail_style = if report.ok {
+                    dim
+                } else {
+                    Style::default().fg(Color::Red)
+                };
+                lines.push(Line::from(Span::styled(
+                    format!("    {}", report.detail),
+                    detail_style,
+                )));
+            }
+            None if i == run.reports.len() && run.pending.is_none() && !run.done => {
+                lines.push(Line::from(vec![
+                    Span::styled("… ", Style::default().fg(Color::Cyan)),
+                    Span::styled(step.clone(), Style::default().fg(Color::Cyan)),
+                ]));
+            }
+            None => lines.push(Line::from(Span::styled(format!("  {}", step), dim))),
+        }
+    }
+
+    let height = chunks[1].height;
+    let bottom = (lines.len() as u16).saturating_sub(height);
+    let scroll = bottom.saturating_sub(run.scroll_back);
+    frame.render_widget(Paragraph::new(lines).scroll((scroll, 0)), chunks[1]);
+}
@@ -1,12 +1,18 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Layout, Rect},
+    layout::{Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
 };
 
-use crate::app::{BrowserPane, InputMode, MethodBrowser};
+use crate::app::{BrowserPane, CallConfirm, InputMode, MethodBrowser};
+use crate::arg_form::{ArgForm, FieldKind, RowKind};
+use crate::call_history::args_text;
+use crate::method_class::{MethodClass, classify};
+use crate::result_view;
+use crate::tabs::json_tree;
+use crate::wallet_schema::{Availability, RpcMethod};
 
 pub fn render(
     browser: &MethodBrowser,
@@ -20,6 +26,236 @@ pub fn render(
 
     render_method_list(browser, frame, cols[0], focused, input_mode, wallet_name);
     render_detail(browser, frame, cols[1], focused, input_mode, wallet_name);
+    if let Some(form) = &browser.form {
+        render_arg_form(browser, form, frame, cols[1], input_mode);
+    }
+    if let Some(confirm) = &browser.confirm {
+        render_confirm(browser, confirm, frame, area);
+    }
+}
+
+fn render_confirm(browser: &MethodBrowser, confirm: &CallConfirm, frame: &mut Frame, area: Rect) {
+    let method = &browser.methods[browser.selected];
+    let typed = confirm.class.needs_typed_confirmation();
+    let color = if typed { Color::Red } else { Color::Yellow };
+    let width = area.width.saturating_sub(8).min(72);
+    let height = if typed { 8 } else { 7 };
+
+    let popup = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+
+    frame.render_widget(Clear, popup);
+
+    let args = match &browser.call_params {
+        Some(params) => args_text(params),
+        None => browser.arg_input.trim().to_string(),
+    };
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines = vec![
+        Line::from(vec![
+            Span::styled(
+                method.name.as_str(),
+                Style::default().fg(color).add_modifier(Modifier::BOLD),
+            ),
+            Span::raw(format!(" {}.", confirm.class.label())),
+        ]),
+        Line::from(vec![
+            Span::styled("Args: ", dim),
+            Span::raw(if args.is_empty() {
+                "(none)".to_string()
+            } else {
+                args
+            }),
+        ]),
+        Line::from(""),
+    ];
+    if typed {
+        lines.push(Line::from(format!(
+            "Type {} and press Enter to call it.",
+            method.name
+        )));
+        let style = if confirm.typed == method.name {
+            Style::default().fg(Color::Green)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::styled("> ", Style::default().fg(Color::Cyan)),
+            Span::styled(confirm.typed.as_str(), style),
+            Span::styled("_", Style::default().fg(Color::Magenta)),
+        ]));
+    } else {
+        lines.push(Line::from("Call it?"));
+    }
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Confirm call")
+        .border_style(Style::default().fg(color));
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .wrap(Wrap { trim: false }),
+        popup,
+    );
+}
+
+fn render_arg_form(
+    browser: &MethodBrowser,
+    form: &ArgForm,
+    frame: &mut Frame,
+    area: Rect,
+    input_mode: InputMode,
+) {
+    frame.render_widget(Clear, area);
+    let method = &browser.methods[browser.selected];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Arguments: {}", method.name))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let chunks = Layout::vertical([
+        Constraint::Min(3),
+        Constraint::Length(4),
+        Constraint::Length(3),
+    ])
+    .split(inner);
+
+    let editing = input_mode == InputMode::ArgForm;
+    let rows = form.rows();
+    let selected = form.selected.min(rows.len().saturating_sub(1));
+    let dim = Style::default().fg(Color::DarkGray);
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let is_selected = i == selected;
+        let marker = if is_selected { "> " } else { "  " };
+        let indent = "  ".repeat(row.depth);
+        let Some(field) = form.field(&row.path) else {
+            continue;
+        };
+        if row.kind != RowKind::Field {
+            let label = if row.kind == RowKind::AddItem {
+                "+ add item"
+            } else {
+                "+ add entry"
+            };
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            lines.push(Line::from(vec![
+                Span::raw(format!("{}{}", marker, indent)),
+                Span::styled(label, style),
+            ]));
+            continue;
+        }
+
+        let name_style = if field.included {
+            Style::default().fg(Color::Cyan)
+        } else {
+            dim
+        };
+        let name = if field.custom_key
+            && field.name.is_empty()
+            && !(editing && form.editing_key && is_selected)
+        {
+            "<key>".to_string()
+        } else {
+            field.name.clone()
+        };
+        let mut spans = vec![
+            Span::raw(format!("{}{}", marker, indent)),
+            Span::styled(name, name_style),
+        ];
+        if editing && is_selected && form.editing_key {
+            spans.push(Span::styled("_", Style::default().fg(Color::Magenta)));
+        }
+        let mut meta = field.schema.type_label();
+        if field.required {
+            meta.push_str(", required");
+        }
+        if !field.aliases.is_empty() {
+            meta.push_str(&format!(", alias {}", field.aliases.join("/")));
+        }
+        spans.push(Span::styled(format!(" ({})  ", meta), dim));
+
+        let value_style = if is_selected {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        let value = match field.kind() {
+            _ if !field.included => "omitted (node default)".to_string(),
+            FieldKind::Boolean => field.text.clone(),
+            FieldKind::Scalar => field.text.clone(),
+            FieldKind::Object => format!(
+                "{{{} set}}",
+                field.children.iter().filter(|c| c.included).count()
+            ),
+            FieldKind::Array => format!("[{} items]", field.children.len()),
+        };
+        spans.push(Span::styled(
+            value,
+            if field.included { value_style } else { dim },
+        ));
+        if editing && is_selected && !form.editing_key {
+            spans.push(Span::styled("_", Style::default().fg(Color::Magenta)));
+        }
+        if let Some(err) = field.error() {
+            spans.push(Span::styled(
+                format!("  ! {}", err),
+                Style::default().fg(Color::Red),
+            ));
+        }
+        lines.push(Line::from(spans));
+    }
+    let height = chunks[0].height as usize;
+    let top = (selected + 1).saturating_sub(height) as u16;
+    frame.render_widget(Paragraph::new(lines).scroll((top, 0)), chunks[0]);
+
+    let description = rows
+        .get(selected)
+        .filter(|r| r.kind == RowKind::Field)
+        .and_then(|r| form.field(&r.path))
+        .map(|f| f.description.clone())
+        .unwrap_or_default();
+    frame.render_widget(
+        Paragraph::new(description)
+            .style(dim)
+            .wrap(Wrap { trim: true })
+            .block(Block::default().borders(Borders::TOP)),
+        chunks[1],
+    );
+
+    let status = match (&form.error, form.build()) {
+        (Some(err), _) => Line::from(Span::styled(err.clone(), Style::default().fg(Color::Red))),
+        (None, Ok(params)) => Line::from(vec![
+            Span::styled("Request: ", dim),
+            Span::raw(format!("{} {}", method.name, params)),
+        ]),
+        (None, Err(e)) => Line::from(Span::styled(e, Style::default().fg(Color::Yellow))),
+    };
+    let status = if browser.calling {
+        Line::from(Span::styled("Calling...", Style::default().fg(Color::Cyan)))
+    } else {
+        status
+    };
+    frame.render_widget(
+        Paragraph::new(status)
+            .wrap(Wrap { trim: false })
+            .block(Block::default().borders(Borders::TOP)),
+        chunks[2],
+    );
 }
 
 fn pane_border_style(browser: &MethodBrowser, focused: bool, pane: BrowserPane) -> Style {
@@ -30,8 +266,8 @@ fn pane_border_style(browser: &MethodBrowser, focused: bool, pane: BrowserPane)
     }
 }
 
-fn render_method_list(
-    browser: &MethodBrowser,
+fn render_method_list<'a>(
+    browser: &'a MethodBrowser,
     frame: &mut Frame,
     area: Rect,
     focused: bool,
@@ -39,12 +275,27 @@ fn render_method_list(
     wallet_name: &str,
 ) {
     let is_filtered = input_mode == InputMode::MethodSearch;
+    let item = |m: &'a RpcMethod| {
+        let mut spans = vec![Span::raw(m.name.as_str())];
+        if m.availability == Availability::HelpOnly {
+            spans.push(Span::styled(" +", Style::default().fg(Color::Green)));
+        }
+        if browser.starred.contains(&m.name) {
+            spans.push(Span::styled(" ★", Style::default().fg(Color::Yellow)));
+        }
+        let item = ListItem::new(Line::from(spans));
+        if m.availability == Availability::Missing {
+            item.style(Style::default().fg(Color::DarkGray))
+        } else {
+            item
+        }
+    };
 
     let (items, selected_in_list): (Vec<ListItem>, Option<usize>) = if is_filtered {
         let items: Vec<ListItem> = browser
             .filtered_indices
             .iter()
-            .map(|&i| ListItem::new(browser.methods[i].name.as_str()))
+            .map(|&i| item(&browser.methods[i]))
             .collect();
         let sel = if items.is_empty() {
             None
@@ -53,19 +304,18 @@ fn render_method_list(
         };
         (items, sel)
     } else {
-        let items: Vec<ListItem> = browser
-            .methods
-            .iter()
-            .map(|m| ListItem::new(m.name.as_str()))
-            .collect();
+        let items: Vec<ListItem> = browser.methods.iter().map(item).collect();
         (items, Some(browser.selected))
     };
 
-    let title = if wallet_name.is_empty() {
+    let mut title = if wallet_name.is_empty() {
         "Methods".to_string()
     } else {
         format!("Methods [{}]", wallet_name)
     };
+    if let Some(note) = &browser.node_note {
+        title = format!("{} · {}", title, note);
+    }
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -118,7 +368,7 @@ fn render_detail(
     wallet_name: &str,
 ) {
     let is_searching = input_mode == InputMode::DetailSearch;
-    let has_matches = !browser.detail_matches.is_empty();
+    let has_matches = browser.match_count() > 0;
 
     let (detail_area, search_area) = if is_searching || has_matches {
         let rows = Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(area);
@@ -139,6 +389,41 @@ fn render_detail(
     }
 
     let method = &browser.methods[browser.selected];
+    if browser.showing_result() {
+        render_result(browser, frame, inner, input_mode == InputMode::DetailFilter);
+    } else {
+        render_help(browser, method, frame, inner, input_mode, wallet_name);
+    }
+
+    if let Some(search_area) = search_area {
+        if is_searching {
+            let search_line = Line::from(vec![
+                Span::styled("/ ", Style::default().fg(Color::Cyan)),
+                Span::raw(&browser.detail_search),
+                Span::styled("_", Style::default().fg(Color::Magenta)),
+            ]);
+            frame.render_widget(Paragraph::new(search_line), search_area);
+        } else if has_matches {
+            let info = format!(
+                "[{}/{}] {}",
+                browser.result.as_ref().map_or(0, |t| t.match_index) + 1,
+                browser.match_count(),
+                browser.detail_search
+            );
+            let search_line = Line::from(Span::styled(info, Style::default().fg(Color::Cyan)));
+            frame.render_widget(Paragraph::new(search_line), search_area);
+        }
+    }
+}
+
+fn render_help(
+    browser: &MethodBrowser,
+    method: &RpcMethod,
+    frame: &mut Frame,
+    area: Rect,
+    input_mode: InputMode,
+    wallet_name: &str,
+) {
     let mut lines: Vec<Line> = Vec::new();
 
     if !wallet_name.is_empty() {
@@ -160,6 +445,29 @@ fn render_detail(
             .fg(Color::Magenta)
             .add_modifier(Modifier::BOLD),
     )));
+    let class = classify(&method.name);
+    if class != MethodClass::ReadOnly {
+        let color = if class.needs_typed_confirmation() {
+            Color::Red
+        } else {
+            Color::Yellow
+        };
+        lines.push(Line::from(Span::styled(
+            format!("This call {}", class.label()),
+            Style::default().fg(color),
+        )));
+    }
+    match method.availability {
+        Availability::Missing => lines.push(Line::from(Span::styled(
+            "Not available on the connected node",
+            Style::default().fg(Color::Yellow),
+        ))),
+        Availability::HelpOnly => lines.push(Line::from(Span::styled(
+            "Not in the bundled spec; documented from the node's help",
+            Style::default().fg(Color::Green),
+        ))),
+        _ => {}
+    }
     lines.push(Line::from(""));
 
     for desc_line in method.description.lines() {
@@ -192,6 +500,35 @@ fn render_detail(
         }
     }
 
+    if let Some(schema) = &method.result {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Returns:",
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        for entry in result_view::outline(schema) {
+            let indent = "  ".repeat(entry.depth + 1);
+            let mut spans = vec![Span::raw(indent.clone())];
+            if !entry.name.is_empty() {
+                spans.push(Span::styled(
+                    format!("{} ", entry.name),
+                    Style::default().fg(Color::Cyan),
+                ));
+            }
+            spans.push(Span::styled(
+                format!("({})", entry.type_label),
+                Style::default().fg(Color::DarkGray),
+            ));
+            if let Some(desc) = entry.description.as_deref().and_then(|d| d.lines().next()) {
+                spans.push(Span::styled(
+                    format!(" {}", desc),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            lines.push(Line::from(spans));
+        }
+    }
+
     if browser.editing_args || !browser.arg_input.is_empty() {
         lines.push(Line::from(""));
         let style = if input_mode == InputMode::ArgInput {
@@ -219,98 +556,71 @@ fn render_detail(
         )));
     }
 
-    if let Some(result) = &browser.result {
+    if let Some(err) = &browser.error {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
-            "Result:",
-            Style::default()
-                .fg(Color::Green)
-                .add_modifier(Modifier::BOLD),
+            format!("Error: {}", err),
+            Style::default().fg(Color::Red),
         )));
-
-        let search_query = if has_matches {
-            Some(browser.detail_search.to_lowercase())
-        } else {
-            None
-        };
-
-        for (i, rl) in result.lines().enumerate() {
-            let is_match_line = browser.detail_matches.iter().any(|&m| m as usize == i);
-            if is_match_line {
-                if let Some(ref query) = search_query {
-                    lines.push(highlight_line(rl, query));
-                } else {
-                    lines.push(Line::from(rl.to_string()));
-                }
-            } else {
-                lines.push(Line::from(rl.to_string()));
-            }
-        }
     }
 
-    if let Some(err) = &browser.error {
+    if browser.result.is_some() {
         lines.push(Line::from(""));
         lines.push(Line::from(Span::styled(
-            format!("Error: {}", err),
-            Style::default().fg(Color::Red),
+            "Result hidden: ? shows it again",
+            Style::default().fg(Color::DarkGray),
         )));
     }
 
-    let scroll_offset = browser.result_scroll;
-
     let paragraph = Paragraph::new(lines)
         .wrap(Wrap { trim: false })
-        .scroll((scroll_offset, 0));
-    frame.render_widget(paragraph, inner);
-
-    if let Some(search_area) = search_area {
-        if is_searching {
-            let search_line = Line::from(vec![
-                Span::styled("/ ", Style::default().fg(Color::Cyan)),
-                Span::raw(&browser.detail_search),
-                Span::styled("_", Style::default().fg(Color::Magenta)),
-            ]);
-            frame.render_widget(Paragraph::new(search_line), search_area);
-        } else if has_matches {
-            let info = format!(
-                "[{}/{}] {}",
-                browser.detail_match_index + 1,
-                browser.detail_matches.len(),
-                browser.detail_search
-            );
-            let search_line = Line::from(Span::styled(info, Style::default().fg(Color::Cyan)));
-            frame.render_widget(Paragraph::new(search_line), search_area);
-        }
-    }
+        .scroll((browser.detail_scroll, 0));
+    frame.render_widget(paragraph, area);
 }
 
-fn highlight_line<'a>(line: &str, query: &str) -> Line<'a> {
-    if !line.is_ascii() || !query.is_ascii() {
-        return Line::from(line.to_string());
-    }
-
-    let lower = line.to_lowercase();
-    let mut spans = Vec::new();
-    let mut pos = 0;
+/// The last result as a collapsible tree; the bottom rows describe the node under the
+/// cursor from the method's result schema. The header shows the filter, if any.
+fn render_result(browser: &MethodBrowser, frame: &mut Frame, area: Rect, filtering: bool) {
+    let chunks = Layout::vertical([Constraint::Length(1), Constraint::Min(1)]).split(area);
 
-    while let Some(start) = lower[pos..].find(query) {
-        let start = pos + start;
-        if start > pos {
-            spans.push(Span::raw(line[pos..start].to_string()));
-        }
-        spans.push(Span::styled(
-            line[start..start + query.len()].to_string(),
+    let mut header = vec![if browser.calling {
+        Span::styled("Calling...", Style::default().fg(Color::Cyan))
+    } else {
+        Span::styled(
+            "Result:",
             Style::default()
-                .bg(Color::Magenta)
-                .fg(Color::White)
+                .fg(Color::Green)
                 .add_modifier(Modifier::BOLD),
+        )
+    }];
+    if let Some(note) = &browser.result_note {
+        header.push(Span::styled(
+            format!(" ({})", note),
+            Style::default().fg(Color::DarkGray),
         ));
-        pos = start + query.len();
     }
-
-    if pos < line.len() {
-        spans.push(Span::raw(line[pos..].to_string()));
+    if filtering || !browser.filter.is_empty() {
+        header.push(Span::styled(" | ", Style::default().fg(Color::Cyan)));
+        header.push(Span::raw(browser.filter.clone()));
+        if filtering {
+            header.push(Span::styled("_", Style::default().fg(Color::Magenta)));
+        }
+        if let Some(err) = &browser.filter_error {
+            header.push(Span::styled(
+                format!("  {}", err),
+                Style::default().fg(Color::Red),
+            ));
+        } else if let Some(n) = browser.filter_outputs {
+            let label = if n == 1 { "output" } else { "outputs" };
+            header.push(Span::styled(
+                format!("  {} {}", n, label),
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
     }
+    frame.render_widget(Paragraph::new(Line::from(header)), chunks[0]);
 
-    Line::from(spans)
+    if let Some(tree) = &browser.result {
+        json_tree::render(tree, Block::default(), frame, chunks[1]);
+    }
 }
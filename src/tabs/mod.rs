@@ -1,4 +1,8 @@
+pub mod call_history;
 pub mod dashboard;
+pub mod file_picker;
+pub mod json_tree;
+pub mod macros;
 pub mod method_browser;
 pub mod peers;
 pub mod psbt;
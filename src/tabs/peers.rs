@@ -1,22 +1,49 @@
+use std::collections::BTreeMap;
+
 use ratatui::{
     Frame,
     layout::{Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
-    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table, TableState},
+    text::{Line, Span},
+    widgets::{
+        Block, Borders, Cell, Clear, List, ListItem, ListState, Paragraph, Row, Sparkline, Table,
+        TableState,
+    },
 };
+use serde_json::Value;
 
-use crate::app::{App, InputMode};
+use crate::app::{ADDRMAN_NETWORKS, ADDRMAN_SERVICES, App, InputMode};
 use crate::format::*;
+use crate::peer_history::PeerEventKind;
 use crate::peers_query;
+use crate::rpc_types::PeerInfo;
+use crate::tabs::json_tree;
+
+/// Most rows the event log takes; it shrinks to its entries and to a third of the tab.
+const EVENT_LOG_HEIGHT: u16 = 10;
 
 pub fn render(app: &App, frame: &mut Frame, area: Rect) {
     let show_query_line = app.input_mode == InputMode::PeersQuery
         || !peers_query::is_empty(&app.peers_query)
-        || app.peers_query_error.is_some();
+        || app.peers_query_error.is_some()
+        || app.peers_export_status.is_some();
+    // Hidden until there is something to show, unless focused.
+    let log_height = if app.peers_events.is_empty() && !app.peers_events_focused {
+        0
+    } else {
+        (app.peers_events.len() as u16 + 2)
+            .clamp(3, EVENT_LOG_HEIGHT)
+            .min(area.height / 3)
+    };
+    let outer = Layout::vertical([Constraint::Min(0), Constraint::Length(log_height)]).split(area);
+    if log_height > 0 {
+        render_event_log(app, frame, outer[1]);
+    }
+
     let chunks = if show_query_line {
-        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(area)
+        Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).split(outer[0])
     } else {
-        Layout::vertical([Constraint::Min(0)]).split(area)
+        Layout::vertical([Constraint::Min(0)]).split(outer[0])
     };
     let table_area = chunks[0];
 
@@ -25,12 +52,19 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
     let Some(peers) = &app.peers else {
         frame.render_widget(Paragraph::new("Loading...").block(block), table_area);
         render_query_line(app, frame, chunks.get(1).copied());
+        render_addrman_popup(app, frame, area);
+        render_export_picker(app, frame, area);
         return;
     };
 
     if peers.is_empty() {
-        frame.render_widget(Paragraph::new("No peers connected").block(block), table_area);
+        frame.render_widget(
+            Paragraph::new("No peers connected").block(block),
+            table_area,
+        );
         render_query_line(app, frame, chunks.get(1).copied());
+        render_addrman_popup(app, frame, area);
+        render_export_picker(app, frame, area);
         return;
     }
 
@@ -40,6 +74,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
             table_area,
         );
         render_query_line(app, frame, chunks.get(1).copied());
+        render_addrman_popup(app, frame, area);
+        render_export_picker(app, frame, area);
         return;
     }
 
@@ -58,6 +94,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
         "Ping",
         "Recv",
         "Sent",
+        "Rx/s",
+        "Tx/s",
         "Height",
         "V2",
     ])
@@ -73,7 +111,11 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
         .filter_map(|&i| peers.get(i))
         .map(|p| {
             let dir = if p.inbound { "in" } else { "out" };
-            let dir_color = if p.inbound { Color::Yellow } else { Color::Green };
+            let dir_color = if p.inbound {
+                Color::Yellow
+            } else {
+                Color::Green
+            };
             let ping = p
                 .pingtime
                 .map(|t| format!("{:.0}ms", t * 1000.0))
@@ -93,6 +135,15 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
             } else {
                 "—".into()
             };
+            let history = app.peer_history.get(p.id);
+            let recv_rate = history
+                .and_then(|h| h.recv_rate())
+                .map(fmt_rate)
+                .unwrap_or_else(|| "—".into());
+            let send_rate = history
+                .and_then(|h| h.send_rate())
+                .map(fmt_rate)
+                .unwrap_or_else(|| "—".into());
             let peer_identity = if app.peers_show_user_agent {
                 if p.subver.is_empty() {
                     "—".to_string()
@@ -112,6 +163,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
                 Cell::from(ping),
                 Cell::from(fmt_bytes(p.bytesrecv)),
                 Cell::from(fmt_bytes(p.bytessent)),
+                Cell::from(recv_rate),
+                Cell::from(send_rate),
                 Cell::from(height),
                 Cell::from(v2).style(Style::default().fg(v2_color)),
             ])
@@ -127,6 +180,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
         ratatui::layout::Constraint::Length(8),
         ratatui::layout::Constraint::Length(9),
         ratatui::layout::Constraint::Length(9),
+        ratatui::layout::Constraint::Length(10),
+        ratatui::layout::Constraint::Length(10),
         ratatui::layout::Constraint::Length(8),
         ratatui::layout::Constraint::Length(3),
     ];
@@ -144,6 +199,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
     render_query_line(app, frame, chunks.get(1).copied());
     render_peer_popup(app, frame, area);
     render_query_help_popup(app, frame, area);
+    render_addrman_popup(app, frame, area);
+    render_export_picker(app, frame, area);
 }
 
 fn abbreviate_conn_type(ct: &str) -> &str {
@@ -159,7 +216,16 @@ fn abbreviate_conn_type(ct: &str) -> &str {
 }
 
 fn render_peer_popup(app: &App, frame: &mut Frame, area: Rect) {
-    let Some(peer_json) = &app.peers_popup else {
+    let Some(peer_id) = app.peers_popup else {
+        return;
+    };
+    let history = app.peer_history.get(peer_id);
+    let Some(peer) = app
+        .peers
+        .as_ref()
+        .and_then(|peers| peers.iter().find(|p| p.id == peer_id))
+        .or_else(|| history.map(|h| &h.last))
+    else {
         return;
     };
 
@@ -172,17 +238,653 @@ fn render_peer_popup(app: &App, frame: &mut Frame, area: Rect) {
 
     frame.render_widget(Clear, popup);
 
+    let connected = history.is_none_or(|h| h.connected);
+    let title = if connected {
+        format!("Peer {} Details (Esc to close)", peer_id)
+    } else {
+        format!("Peer {} Details — disconnected (Esc to close)", peer_id)
+    };
     let block = Block::default()
         .borders(Borders::ALL)
-        .title("Peer Details (Esc to close)")
+        .title(title)
         .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(2),
+        Constraint::Length(4),
+        Constraint::Length(4),
+        Constraint::Length(4),
+        Constraint::Min(0),
+    ])
+    .split(inner);
+
+    let recv_rates = history.map(|h| h.recv_rates()).unwrap_or_default();
+    let send_rates = history.map(|h| h.send_rates()).unwrap_or_default();
+    let ping_ms = history.map(|h| h.ping_ms()).unwrap_or_default();
+    let rate_or_dash = |r: Option<f64>| r.map(fmt_rate).unwrap_or_else(|| "—".into());
 
+    let dim = Style::default().fg(Color::DarkGray);
+    let summary = vec![
+        Line::from(vec![
+            Span::styled("Recv ", dim),
+            Span::raw(format!(
+                "{} ({})   ",
+                rate_or_dash(history.and_then(|h| h.recv_rate())),
+                fmt_bytes(peer.bytesrecv)
+            )),
+            Span::styled("Sent ", dim),
+            Span::raw(format!(
+                "{} ({})   ",
+                rate_or_dash(history.and_then(|h| h.send_rate())),
+                fmt_bytes(peer.bytessent)
+            )),
+            Span::styled("Ping ", dim),
+            Span::raw(
+                peer.pingtime
+                    .map(|t| format!("{:.0}ms", t * 1000.0))
+                    .unwrap_or_else(|| "—".into()),
+            ),
+        ]),
+        Line::from(Span::styled(
+            format!(
+                "History: {} samples over {}",
+                history.map(|h| h.samples.len()).unwrap_or(0),
+                fmt_duration(history.map(|h| h.tracked_for().as_secs()).unwrap_or(0))
+            ),
+            dim,
+        )),
+    ];
+    frame.render_widget(Paragraph::new(summary), chunks[0]);
+
+    render_history_sparkline(
+        frame,
+        chunks[1],
+        format!("Recv rate (max {})", fmt_rate(max_of(&recv_rates) as f64)),
+        &recv_rates,
+        Color::Green,
+    );
+    render_history_sparkline(
+        frame,
+        chunks[2],
+        format!("Send rate (max {})", fmt_rate(max_of(&send_rates) as f64)),
+        &send_rates,
+        Color::Yellow,
+    );
+    render_history_sparkline(
+        frame,
+        chunks[3],
+        format!("Ping (max {}ms)", max_of(&ping_ms)),
+        &ping_ms,
+        Color::Magenta,
+    );
+
+    if let Some(tree) = &app.peers_popup_tree {
+        let block = Block::default()
+            .borders(Borders::TOP)
+            .title("getpeerinfo (r: structured)");
+        json_tree::render(tree, block, frame, chunks[4]);
+        return;
+    }
     frame.render_widget(
-        Paragraph::new(peer_json.clone())
-            .block(block)
+        Paragraph::new(peer_detail_lines(app, peer))
+            .block(
+                Block::default()
+                    .borders(Borders::TOP)
+                    .title("Details (r: raw JSON)"),
+            )
             .scroll((app.peers_popup_scroll, 0)),
-        popup,
+        chunks[4],
+    );
+}
+
+fn peer_detail_lines(app: &App, peer: &PeerInfo) -> Vec<Line<'static>> {
+    let extra_str = |key: &str| {
+        peer.extra
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+    let extra_u64 = |key: &str| peer.extra.get(key).and_then(Value::as_u64);
+    let extra_i64 = |key: &str| peer.extra.get(key).and_then(Value::as_i64);
+    let extra_bool = |key: &str| peer.extra.get(key).and_then(Value::as_bool);
+    let extra_list = |key: &str| {
+        peer.extra
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|v| {
+                        v.as_str()
+                            .map(str::to_string)
+                            .unwrap_or_else(|| v.to_string())
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let yes_no = |b: Option<bool>| match b {
+        Some(true) => "yes".to_string(),
+        Some(false) => "no".to_string(),
+        None => "—".to_string(),
+    };
+    let or_dash = |s: Option<String>| s.filter(|s| !s.is_empty()).unwrap_or_else(|| "—".into());
+    let unix_ago = |t: Option<u64>| match t {
+        Some(0) | None => "never".to_string(),
+        Some(t) => fmt_relative_time(t),
+    };
+
+    let mut lines = Vec::new();
+
+    section(&mut lines, "Identity");
+    lines.push(kv("Address", peer.addr.clone()));
+    lines.push(kv("Local bind", or_dash(extra_str("addrbind"))));
+    lines.push(kv("Our addr", or_dash(extra_str("addrlocal"))));
+    lines.push(kv("Network", or_dash(Some(peer.network.clone()))));
+    if let Some(asn) = extra_u64("mapped_as") {
+        lines.push(kv("Mapped AS", format!("AS{}", asn)));
+    }
+    lines.push(kv("User agent", or_dash(Some(peer.subver.clone()))));
+    lines.push(kv("Version", peer.version.to_string()));
+    let services = extra_list("servicesnames");
+    lines.push(kv(
+        "Services",
+        format!(
+            "{} ({})",
+            if services.is_empty() {
+                "none".to_string()
+            } else {
+                services.join(", ")
+            },
+            or_dash(extra_str("services"))
+        ),
+    ));
+    let permissions = extra_list("permissions");
+    lines.push(kv(
+        "Permissions",
+        if permissions.is_empty() {
+            "none".to_string()
+        } else {
+            permissions.join(", ")
+        },
+    ));
+
+    section(&mut lines, "Connection");
+    lines.push(kv("Type", or_dash(Some(peer.connection_type.clone()))));
+    lines.push(kv(
+        "Direction",
+        if peer.inbound { "inbound" } else { "outbound" },
+    ));
+    let transport = or_dash(Some(peer.transport_protocol_type.clone()));
+    lines.push(kv("Transport", transport));
+    if let Some(session_id) = extra_str("session_id").filter(|s| !s.is_empty()) {
+        lines.push(kv("Session ID", session_id));
+    }
+    lines.push(kv(
+        "Connected",
+        if peer.conntime > 0 {
+            format!(
+                "{} (since {} UTC)",
+                fmt_relative_time(peer.conntime),
+                fmt_unix_time(peer.conntime)
+            )
+        } else {
+            "—".into()
+        },
+    ));
+    lines.push(kv("Last send", unix_ago(extra_u64("last_send"))));
+    lines.push(kv("Last recv", unix_ago(extra_u64("last_recv"))));
+    lines.push(kv(
+        "Ping / min",
+        format!(
+            "{} / {}",
+            peer.pingtime
+                .map(|t| format!("{:.0}ms", t * 1000.0))
+                .unwrap_or_else(|| "—".into()),
+            peer.extra
+                .get("minping")
+                .and_then(Value::as_f64)
+                .map(|t| format!("{:.0}ms", t * 1000.0))
+                .unwrap_or_else(|| "—".into())
+        ),
+    ));
+    if let Some(offset) = extra_i64("timeoffset") {
+        lines.push(kv("Time offset", format!("{}s", offset)));
+    }
+
+    section(&mut lines, "Sync");
+    let (our_headers, our_blocks) = app
+        .blockchain
+        .as_ref()
+        .map(|b| (b.headers as i64, b.blocks as i64))
+        .unwrap_or((-1, -1));
+    lines.push(kv(
+        "Starting height",
+        or_dash(extra_i64("startingheight").map(|h| h.to_string())),
+    ));
+    if let Some(presynced) = extra_i64("presynced_headers").filter(|h| *h >= 0) {
+        lines.push(kv("Presynced hdrs", fmt_number(presynced as u64)));
+    }
+    lines.push(sync_line("Headers", peer.synced_headers, our_headers));
+    lines.push(sync_line("Blocks", peer.synced_blocks, our_blocks));
+    let inflight = extra_list("inflight");
+    lines.push(kv(
+        "In flight",
+        if inflight.is_empty() {
+            "none".to_string()
+        } else {
+            inflight.join(", ")
+        },
+    ));
+    lines.push(kv("Last block", unix_ago(extra_u64("last_block"))));
+    lines.push(kv(
+        "HB compact",
+        format!(
+            "to {} / from {}",
+            yes_no(extra_bool("bip152_hb_to")),
+            yes_no(extra_bool("bip152_hb_from"))
+        ),
+    ));
+
+    section(&mut lines, "Relay");
+    lines.push(kv("Relays txs", yes_no(extra_bool("relaytxes"))));
+    lines.push(kv(
+        "Fee filter",
+        peer.extra
+            .get("minfeefilter")
+            .and_then(Value::as_f64)
+            .map(fmt_sat_per_vb)
+            .unwrap_or_else(|| "—".into()),
+    ));
+    lines.push(kv("Last tx", unix_ago(extra_u64("last_transaction"))));
+    lines.push(kv("Addr relay", yes_no(extra_bool("addr_relay_enabled"))));
+    lines.push(kv(
+        "Addrs processed",
+        or_dash(extra_u64("addr_processed").map(fmt_number)),
+    ));
+    lines.push(kv(
+        "Addrs limited",
+        or_dash(extra_u64("addr_rate_limited").map(fmt_number)),
+    ));
+
+    section(&mut lines, "Messages");
+    lines.extend(message_table(peer));
+    lines
+}
+
+fn section(lines: &mut Vec<Line<'static>>, title: &str) {
+    if !lines.is_empty() {
+        lines.push(Line::from(""));
+    }
+    lines.push(Line::from(Span::styled(
+        title.to_string(),
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    )));
+}
+
+fn kv(key: &str, value: impl Into<String>) -> Line<'static> {
+    Line::from(vec![
+        Span::styled(
+            format!("  {:<16}", key),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::raw(Into::<String>::into(value)),
+    ])
+}
+
+fn sync_line(label: &str, theirs: i64, ours: i64) -> Line<'static> {
+    if theirs < 0 {
+        return kv(label, "—");
+    }
+    let (note, color) = if ours < 0 {
+        (String::new(), Color::White)
+    } else if theirs >= ours {
+        ("  (at our tip)".to_string(), Color::Green)
+    } else {
+        (
+            format!("  ({} behind)", fmt_number((ours - theirs) as u64)),
+            Color::Yellow,
+        )
+    };
+    Line::from(vec![
+        Span::styled(
+            format!("  {:<16}", label),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::raw(fmt_number(theirs as u64)),
+        Span::styled(note, Style::default().fg(color)),
+    ])
+}
+
+fn message_table(peer: &PeerInfo) -> Vec<Line<'static>> {
+    let per_msg = |key: &str| {
+        peer.extra
+            .get(key)
+            .and_then(Value::as_object)
+            .map(|m| {
+                m.iter()
+                    .map(|(k, v)| (k.clone(), v.as_u64().unwrap_or(0)))
+                    .collect::<BTreeMap<_, _>>()
+            })
+            .unwrap_or_default()
+    };
+    let sent = per_msg("bytessent_per_msg");
+    let recv = per_msg("bytesrecv_per_msg");
+
+    let mut rows: Vec<(String, u64, u64)> = sent
+        .keys()
+        .chain(recv.keys())
+        .collect::<std::collections::BTreeSet<_>>()
+        .into_iter()
+        .map(|msg| {
+            (
+                msg.clone(),
+                sent.get(msg).copied().unwrap_or(0),
+                recv.get(msg).copied().unwrap_or(0),
+            )
+        })
+        .collect();
+    rows.sort_by(|a, b| (b.1 + b.2).cmp(&(a.1 + a.2)).then_with(|| a.0.cmp(&b.0)));
+
+    if rows.is_empty() {
+        return vec![Line::from(Span::styled(
+            "  no per-message counters",
+            Style::default().fg(Color::DarkGray),
+        ))];
+    }
+
+    let mut lines = vec![Line::from(Span::styled(
+        format!(
+            "  {:<16}{:>12}{:>12}{:>12}",
+            "Message", "Sent", "Recv", "Total"
+        ),
+        Style::default()
+            .fg(Color::DarkGray)
+            .add_modifier(Modifier::BOLD),
+    ))];
+    for (msg, s, r) in rows {
+        lines.push(Line::from(format!(
+            "  {:<16}{:>12}{:>12}{:>12}",
+            msg,
+            fmt_bytes(s),
+            fmt_bytes(r),
+            fmt_bytes(s + r)
+        )));
+    }
+    lines
+}
+
+fn render_history_sparkline(
+    frame: &mut Frame,
+    area: Rect,
+    title: String,
+    data: &[u64],
+    color: Color,
+) {
+    // Keep the newest samples when the history is wider than the popup.
+    let width = area.width.saturating_sub(2) as usize;
+    let start = data.len().saturating_sub(width);
+    frame.render_widget(
+        Sparkline::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .data(&data[start..])
+            .style(Style::default().fg(color)),
+        area,
+    );
+}
+
+fn max_of(data: &[u64]) -> u64 {
+    data.iter().copied().max().unwrap_or(0)
+}
+
+fn render_event_log(app: &App, frame: &mut Frame, area: Rect) {
+    let border = if app.peers_events_focused {
+        Color::Cyan
+    } else {
+        Color::DarkGray
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("Peer Events ({})", app.peers_events.len()))
+        .border_style(Style::default().fg(border));
+
+    if app.peers_events.is_empty() {
+        frame.render_widget(
+            Paragraph::new("No connects or disconnects seen yet")
+                .style(Style::default().fg(Color::DarkGray))
+                .block(block),
+            area,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = app
+        .peers_events
+        .iter()
+        .rev()
+        .map(|e| {
+            let (marker, color) = match e.kind {
+                PeerEventKind::Connected => ("+ conn", Color::Green),
+                PeerEventKind::Disconnected => ("- disc", Color::Red),
+            };
+            let mut spans = vec![
+                Span::styled(
+                    format!("{} UTC ", fmt_unix_time(e.time)),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Span::styled(format!("{:<7}", marker), Style::default().fg(color)),
+                Span::raw(format!(
+                    "{:>6}  {:<7} {:<4} {:<3} {}",
+                    e.id,
+                    abbreviate_conn_type(&e.connection_type),
+                    e.network,
+                    if e.inbound { "in" } else { "out" },
+                    e.addr
+                )),
+            ];
+            if e.kind == PeerEventKind::Disconnected {
+                spans.push(Span::styled(
+                    format!(
+                        "  after {}, recv {} sent {}",
+                        fmt_duration(e.duration),
+                        fmt_bytes(e.bytesrecv),
+                        fmt_bytes(e.bytessent)
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut list = List::new(items).block(block);
+    let mut state = ListState::default();
+    if app.peers_events_focused {
+        list = list.highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        state.select(Some(app.peers_events_scroll));
+    } else {
+        *state.offset_mut() = app.peers_events_scroll;
+    }
+    frame.render_stateful_widget(list, area, &mut state);
+}
+
+fn render_addrman_popup(app: &App, frame: &mut Frame, area: Rect) {
+    let view = &app.addrman;
+    if !view.open {
+        return;
+    }
+
+    let popup = Layout::vertical([Constraint::Length(area.height.saturating_sub(4))])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8))])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+
+    frame.render_widget(Clear, popup);
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Address Manager (Esc to close)")
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+
+    let networks = app
+        .network
+        .as_ref()
+        .map(|n| n.networks.as_slice())
+        .unwrap_or_default();
+    let counts_rows = view.info.as_ref().map(|i| i.len()).unwrap_or(1).max(1) as u16;
+    let chunks = Layout::vertical([
+        Constraint::Length(counts_rows + 2),
+        Constraint::Length(1),
+        Constraint::Min(0),
+    ])
+    .split(inner);
+
+    let header = Row::new([
+        "Network",
+        "New",
+        "Tried",
+        "Total",
+        "Reachable",
+        "Limited",
+        "Proxy",
+    ])
+    .style(
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    );
+    let count_rows: Vec<Row> = match (&view.info, &view.info_error) {
+        (Some(info), _) => info
+            .iter()
+            .map(|(name, counts)| {
+                let entry = networks.iter().find(|n| n.name == *name);
+                let (reachable, reach_color) = match entry {
+                    Some(n) if n.reachable => ("yes", Color::Green),
+                    Some(_) => ("no", Color::Red),
+                    None => ("", Color::White),
+                };
+                let tried_color = if counts.tried == 0 && entry.is_some() {
+                    Color::Red
+                } else {
+                    Color::White
+                };
+                Row::new(vec![
+                    Cell::from(name.clone()),
+                    Cell::from(fmt_number(counts.new)),
+                    Cell::from(fmt_number(counts.tried)).style(Style::default().fg(tried_color)),
+                    Cell::from(fmt_number(counts.total)),
+                    Cell::from(reachable).style(Style::default().fg(reach_color)),
+                    Cell::from(
+                        entry
+                            .map(|n| if n.limited { "yes" } else { "no" })
+                            .unwrap_or(""),
+                    ),
+                    Cell::from(
+                        entry
+                            .map(|n| n.proxy.clone())
+                            .filter(|p| !p.is_empty())
+                            .unwrap_or_else(|| "—".into()),
+                    ),
+                ])
+            })
+            .collect(),
+        (None, Some(err)) => vec![
+            Row::new(vec![Cell::from(format!("getaddrmaninfo: {}", err))])
+                .style(Style::default().fg(Color::Red)),
+        ],
+        (None, None) => vec![Row::new(vec![Cell::from("Loading...")])],
+    };
+    let widths = [
+        Constraint::Length(14),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(10),
+        Constraint::Length(8),
+        Constraint::Min(10),
+    ];
+    frame.render_widget(
+        Table::new(count_rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::BOTTOM))
+            .column_spacing(1),
+        chunks[0],
     );
+
+    let dim = Style::default().fg(Color::DarkGray);
+    let hl = Style::default().fg(Color::Cyan);
+    let filter_line = Line::from(vec![
+        Span::styled("network ", dim),
+        Span::styled(ADDRMAN_NETWORKS[view.network_filter], hl),
+        Span::styled("  services ", dim),
+        Span::styled(ADDRMAN_SERVICES[view.service_filter], hl),
+        Span::styled(
+            format!(
+                "  showing {} of {} sampled",
+                view.visible.len(),
+                view.addresses.len()
+            ),
+            dim,
+        ),
+        Span::styled(
+            if view.loading { "  loading..." } else { "" },
+            Style::default().fg(Color::Magenta),
+        ),
+    ]);
+    frame.render_widget(Paragraph::new(filter_line), chunks[1]);
+
+    if let Some(err) = &view.error {
+        frame.render_widget(
+            Paragraph::new(format!("getnodeaddresses: {}", err))
+                .style(Style::default().fg(Color::Red)),
+            chunks[2],
+        );
+        return;
+    }
+
+    let header = Row::new(["Address", "Port", "Network", "Last Seen", "Services"]).style(
+        Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+    );
+    let rows: Vec<Row> = view
+        .visible
+        .iter()
+        .filter_map(|&i| view.addresses.get(i))
+        .map(|a| {
+            Row::new(vec![
+                Cell::from(a.address.clone()),
+                Cell::from(a.port.to_string()),
+                Cell::from(a.network.clone()),
+                Cell::from(fmt_relative_time(a.time)),
+                Cell::from(service_flag_names(a.services).join(" ")),
+            ])
+        })
+        .collect();
+    let widths = [
+        Constraint::Min(24),
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Length(12),
+        Constraint::Min(20),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .column_spacing(1)
+        .row_highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+    let mut state = TableState::default();
+    if !view.visible.is_empty() {
+        state.select(Some(view.selected));
+    }
+    frame.render_stateful_widget(table, chunks[2], &mut state);
 }
 
 fn render_query_line(app: &App, frame: &mut Frame, area: Option<Rect>) {
@@ -193,14 +895,42 @@ fn render_query_line(app: &App, frame: &mut Frame, area: Option<Rect>) {
         format!(":{}", app.peers_query_input)
     } else if let Some(err) = &app.peers_query_error {
         format!("query error: {}", err)
+    } else if let Some(status) = &app.peers_export_status {
+        match status {
+            Ok(msg) => msg.clone(),
+            Err(err) => format!("export error: {}", err),
+        }
     } else if !peers_query::is_empty(&app.peers_query) {
         format!("query: {}", peers_query::summary(&app.peers_query))
     } else {
-        "query: none  (press : for where/sort/clear)".to_string()
+        "query: none  (press : for where/sort/columns/clear)".to_string()
     };
     frame.render_widget(Paragraph::new(text), area);
 }
 
+fn render_export_picker(app: &App, frame: &mut Frame, area: Rect) {
+    if !app.peers_export_picker.open {
+        return;
+    }
+    let hl = Style::default().fg(Color::DarkGray);
+    let help = vec![
+        Span::styled("f", hl),
+        Span::raw(format!(
+            " format: {}  ",
+            app.peers_export_format.extension()
+        )),
+        Span::raw(format!("[{} peers]", app.peers_visible_indices.len())),
+    ];
+    crate::tabs::file_picker::render(
+        &app.peers_export_picker,
+        "peers",
+        help,
+        app.input_mode == InputMode::SaveName,
+        frame,
+        area,
+    );
+}
+
 fn render_query_help_popup(app: &App, frame: &mut Frame, area: Rect) {
     if !app.peers_query_help_open {
         return;
@@ -221,7 +951,8 @@ fn render_query_help_popup(app: &App, frame: &mut Frame, area: Rect) {
         "Commands:",
         "  where <field> <op> <value> [and ...]",
         "  sort <field> [asc|desc]",
-        "  clear | clear where | clear sort",
+        "  columns <field>[,<field>...]   (fields written by export, x)",
+        "  clear | clear where | clear sort | clear columns",
         "",
         "Operators:",
         "  ==  !=  >  >=  <  <=  ~=",
@@ -230,11 +961,13 @@ fn render_query_help_popup(app: &App, frame: &mut Frame, area: Rect) {
         "  - Nested fields use dot notation (e.g. bytessent_per_msg.addrv2)",
         "  - Tab completes commands/fields/operators/values",
         "  - Press Tab repeatedly to cycle completion candidates",
+        "  - columns also accepts recv_rate and send_rate (bytes/s)",
         "",
         "Examples:",
         "  where version == 70016 and subver ~= \"Satoshi\"",
         "  where inbound == false and network == \"ipv4\"",
         "  sort bytessent_per_msg.addrv2 desc",
+        "  columns id,addr,subver,pingtime,recv_rate",
         "  clear",
         "",
         "Keys:",
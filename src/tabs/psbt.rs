@@ -1,162 +1,1046 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Flex, Layout, Rect},
+    layout::{Alignment, Constraint, Flex, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
 };
 
-use crate::app::{App, InputMode, PsbtFileMode};
+use crate::app::{App, BroadcastCheck, InputMode, PsbtRpcAction, PsbtView};
+use crate::file_picker::FileMode;
+use crate::format::fmt_btc;
+use crate::psbt;
+use crate::psbt_create::{CHANGE_TYPES, FormRow};
+use crate::psbt_process::{ProcessMode, ProcessRow, SIGHASH_TYPES};
+use crate::psbt_qr;
+use crate::psbt_summary::{Ownership, PsbtSummary};
+use crate::tabs::json_tree;
 
 pub fn render(app: &App, frame: &mut Frame, area: Rect) {
-    let chunks = Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).split(area);
+    let chunks =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).split(area);
     render_psbt_panel(app, frame, chunks[0]);
     render_output_panel(app, frame, chunks[1]);
-    if app.psbt.picker_open {
-        render_picker(app, frame, area);
+    if app.psbt.create.open {
+        render_create_form(app, frame, area);
+    }
+    if app.psbt.process.open {
+        render_process_form(app, frame, area);
+    }
+    if app.psbt.qr.open {
+        render_qr_view(app, frame, area);
+    }
+    if let Some(check) = &app.psbt.broadcast {
+        render_broadcast_confirm(app, check, frame, area);
+    }
+    if app.psbt.signers_open {
+        render_signers(app, frame, area);
+    }
+    if app.input_mode == InputMode::PsbtEditor {
+        render_editor(app, frame, area);
+    }
+    if app.psbt.picker.open {
+        let help = if app.psbt.picker.mode == FileMode::Save {
+            vec![
+                Span::styled("f", Style::default().fg(Color::DarkGray)),
+                Span::raw(format!(" encoding: {}", app.psbt.save_encoding.label())),
+            ]
+        } else {
+            Vec::new()
+        };
+        crate::tabs::file_picker::render(
+            &app.psbt.picker,
+            "PSBT",
+            help,
+            app.input_mode == InputMode::SaveName,
+            frame,
+            area,
+        );
     }
 }
 
 fn render_psbt_panel(app: &App, frame: &mut Frame, area: Rect) {
-    let lines = if app.psbt.psbt.trim().is_empty() {
+    let area = if app.psbt.psbts.is_empty() {
+        area
+    } else {
+        let height = app.psbt.psbts.len().min(6) as u16 + 2;
+        let chunks = Layout::vertical([Constraint::Length(height), Constraint::Min(0)]).split(area);
+        render_psbt_list(app, frame, chunks[0]);
+        chunks[1]
+    };
+
+    if app.psbt.view == PsbtView::Fields && !app.psbt.tree().is_empty() {
+        render_field_tree(app, frame, area);
+        return;
+    }
+    if app.psbt.view == PsbtView::Summary
+        && let Some(current) = app.psbt.current()
+        && current.parsed.is_ok()
+    {
+        render_summary(app, current.summary.as_ref(), frame, area);
+        return;
+    }
+
+    let text = app.psbt.current_text().trim();
+    let mut lines = if text.is_empty() {
         vec![Line::from(Span::styled(
-            "No PSBT loaded. Press 'l' to load from file.",
+            "No PSBT loaded. Press 'l' to load from file or 'n' to create one.",
             Style::default().fg(Color::DarkGray),
         ))]
     } else {
         vec![
             Line::from(Span::styled(
-                format!("Length: {} chars", app.psbt.psbt.trim().len()),
+                format!("Length: {} chars", text.len()),
                 Style::default().fg(Color::DarkGray),
             )),
             Line::from(""),
-            Line::from(app.psbt.psbt.trim().to_string()),
+            Line::from(text.to_string()),
         ]
     };
+    if let Some(Err(err)) = app.psbt.current().map(|p| &p.parsed) {
+        lines.insert(
+            0,
+            Line::from(Span::styled(
+                format!("Parse error: {}", err),
+                Style::default().fg(Color::Red),
+            )),
+        );
+    }
 
     frame.render_widget(
         Paragraph::new(lines)
-            .block(Block::default().borders(Borders::ALL).title("PSBT").border_style(Style::default().fg(Color::Cyan)))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("PSBT")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
             .scroll((app.psbt.scroll, 0)),
         area,
     );
 }
 
-fn render_output_panel(app: &App, frame: &mut Frame, area: Rect) {
-    let mut lines: Vec<Line<'static>> = vec![
-        Line::from(vec![
-            Span::styled("Actions: ", Style::default().fg(Color::DarkGray)),
-            Span::raw("d=decode a=analyze p=walletprocess f=finalize u=utxoupdate"),
-        ]),
-        Line::from(""),
-    ];
+fn render_psbt_list(app: &App, frame: &mut Frame, area: Rect) {
+    let items: Vec<ListItem> = app
+        .psbt
+        .psbts
+        .iter()
+        .enumerate()
+        .map(|(i, loaded)| {
+            let marked = app.psbt.marked.contains(&i);
+            let (summary, summary_style) = match &loaded.parsed {
+                Ok(p) => (
+                    format!(
+                        "v{}, {} in / {} out",
+                        p.version,
+                        p.inputs.len(),
+                        p.outputs.len()
+                    ),
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Err(_) => ("parse error".to_string(), Style::default().fg(Color::Red)),
+            };
+            let name_style = if marked {
+                Style::default().fg(Color::Yellow)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(if marked { "* " } else { "  " }),
+                Span::styled(loaded.name.clone(), name_style),
+                Span::raw("  "),
+                Span::styled(summary, summary_style),
+            ]))
+        })
+        .collect();
 
-    if let Some(err) = &app.psbt.error {
+    let mut state = ListState::default();
+    state.select(Some(app.psbt.active));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("PSBTs ({})", app.psbt.psbts.len()))
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .highlight_style(
+                Style::default()
+                    .add_modifier(Modifier::BOLD)
+                    .fg(Color::Cyan),
+            ),
+        area,
+        &mut state,
+    );
+}
+
+fn render_summary(
+    app: &App,
+    summary: Option<&Result<PsbtSummary, String>>,
+    frame: &mut Frame,
+    area: Rect,
+) {
+    let dim = Style::default().fg(Color::DarkGray);
+    let btc = |sat: u64| fmt_btc(sat as f64 / 100_000_000.0);
+    let mut lines: Vec<Line> = Vec::new();
+    match summary {
+        None => lines.push(Line::from(Span::styled(
+            "Loading summary (decodepsbt, analyzepsbt, wallet lookups)...",
+            Style::default().fg(Color::Magenta),
+        ))),
+        Some(Err(e)) => {
+            lines.push(Line::from(Span::styled(
+                format!("Summary unavailable: {}", e),
+                Style::default().fg(Color::Red),
+            )));
+            lines.push(Line::from(Span::styled(
+                "r retries, v shows the offline field view",
+                dim,
+            )));
+        }
+        Some(Ok(s)) => {
+            if s.warnings.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    "No warnings",
+                    Style::default().fg(Color::Green),
+                )));
+            }
+            for warning in &s.warnings {
+                lines.push(Line::from(Span::styled(
+                    format!("! {}", warning),
+                    Style::default().fg(Color::Yellow),
+                )));
+            }
+            lines.push(Line::from(""));
+
+            let total_in = s
+                .total_in_sat
+                .map(btc)
+                .unwrap_or_else(|| "unknown".to_string());
+            let fee = s.fee_sat.map(btc).unwrap_or_else(|| "unknown".to_string());
+            let rate = match (s.fee_rate, s.estimate) {
+                (Some(rate), Some(est)) => format!("{:.2} sat/vB (estimate {:.2})", rate, est),
+                (Some(rate), None) => format!("{:.2} sat/vB", rate),
+                (None, _) => "unknown".to_string(),
+            };
+            for (label, value) in [
+                ("Total in", total_in),
+                ("Total out", btc(s.total_out_sat)),
+                ("Fee", fee),
+                ("Fee rate", rate),
+                (
+                    "Est. vsize",
+                    s.vsize
+                        .map(|v| format!("{} vB", v))
+                        .unwrap_or_else(|| "unknown".to_string()),
+                ),
+                (
+                    "Next step",
+                    s.next.clone().unwrap_or_else(|| "unknown".to_string()),
+                ),
+            ] {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("{:<12}", label), dim),
+                    Span::raw(value),
+                ]));
+            }
+
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Inputs ({})", s.inputs.len()),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for input in &s.inputs {
+                lines.push(Line::from(vec![
+                    Span::raw(format!(
+                        "  {:>20}  ",
+                        input.amount_sat.map(btc).unwrap_or_else(|| "?".to_string())
+                    )),
+                    Span::styled(input.outpoint.clone(), dim),
+                ]));
+            }
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                format!("Outputs ({})", s.outputs.len()),
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD),
+            )));
+            for output in &s.outputs {
+                let color = match output.ownership {
+                    Ownership::External => Color::White,
+                    Ownership::Mine | Ownership::Change => Color::Green,
+                    Ownership::Unknown => Color::DarkGray,
+                };
+                lines.push(Line::from(vec![
+                    Span::raw(format!("  {:>20}  ", btc(output.amount_sat))),
+                    Span::styled(
+                        format!("{:<9}", output.ownership.label()),
+                        Style::default().fg(color),
+                    ),
+                    Span::raw(
+                        output
+                            .address
+                            .clone()
+                            .unwrap_or_else(|| "(no address)".to_string()),
+                    ),
+                ]));
+            }
+        }
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("PSBT summary")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .scroll((app.psbt.scroll, 0)),
+        area,
+    );
+}
+
+fn render_field_tree(app: &App, frame: &mut Frame, area: Rect) {
+    let rows = psbt::flatten(app.psbt.tree(), &app.psbt.tree_expanded);
+    let items: Vec<ListItem> = rows
+        .iter()
+        .map(|row| {
+            let marker = match (row.node.children.is_empty(), row.expanded) {
+                (true, _) => "  ",
+                (false, true) => "▾ ",
+                (false, false) => "▸ ",
+            };
+            let label_style = if row.node.issue.is_some() {
+                Style::default().fg(Color::Red)
+            } else if row.depth == 0 {
+                Style::default()
+                    .fg(Color::Cyan)
+                    .add_modifier(Modifier::BOLD)
+            } else if row.node.children.is_empty() {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(Color::Yellow)
+            };
+            let mut spans = vec![
+                Span::raw("  ".repeat(row.depth)),
+                Span::raw(marker),
+                Span::styled(row.node.label.clone(), label_style),
+            ];
+            if !row.node.value.is_empty() {
+                if !row.node.label.is_empty() {
+                    spans.push(Span::raw("  "));
+                }
+                spans.push(Span::raw(row.node.value.clone()));
+            }
+            if let Some(issue) = &row.node.issue {
+                spans.push(Span::styled(
+                    format!("  ⚠ {}", issue),
+                    Style::default().fg(Color::Red),
+                ));
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+
+    let mut state = ListState::default();
+    state.select(Some(
+        app.psbt.tree_selected.min(rows.len().saturating_sub(1)),
+    ));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title("PSBT fields")
+                    .border_style(Style::default().fg(Color::Cyan)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        area,
+        &mut state,
+    );
+}
+
+fn render_create_form(app: &App, frame: &mut Frame, area: Rect) {
+    let form = &app.psbt.create;
+    let rows = form.rows();
+    let popup = Layout::vertical([Constraint::Length((rows.len() as u16 + 8).min(area.height))])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8).min(100))])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+    frame.render_widget(Clear, popup);
+
+    let editing = app.input_mode == InputMode::PsbtForm;
+    let selected = form.selected.min(rows.len() - 1);
+    let label_style = Style::default().fg(Color::DarkGray);
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let (label, value) = match *row {
+            FormRow::Address(n) => (
+                format!("Output {} address", n),
+                form.outputs[n].address.clone(),
+            ),
+            FormRow::Amount(n) => (
+                format!("Output {} amount (BTC)", n),
+                if form.outputs[n].subtract_fee {
+                    format!("{}  [subtract fee]", form.outputs[n].amount)
+                } else {
+                    form.outputs[n].amount.clone()
+                },
+            ),
+            FormRow::Inputs => ("Inputs (txid:vout, ...)".into(), form.inputs.clone()),
+            FormRow::AddInputs => (
+                "Add inputs if needed".into(),
+                yes_no(form.add_inputs).into(),
+            ),
+            FormRow::FeeRate => (
+                "Fee rate (sat/vB)".into(),
+                if form.fee_rate.is_empty() && !(editing && i == selected) {
+                    "wallet estimate".into()
+                } else {
+                    form.fee_rate.clone()
+                },
+            ),
+            FormRow::ChangeType => ("Change type".into(), CHANGE_TYPES[form.change_type].into()),
+            FormRow::Replaceable => ("Signal RBF".into(), yes_no(form.replaceable).into()),
+            FormRow::Locktime => (
+                "Locktime".into(),
+                if form.locktime.is_empty() && !(editing && i == selected) {
+                    "0".into()
+                } else {
+                    form.locktime.clone()
+                },
+            ),
+            FormRow::Submit => (String::new(), String::new()),
+        };
+
+        let is_selected = i == selected;
+        let marker = if is_selected { ">> " } else { "   " };
+        if *row == FormRow::Submit {
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(" Create PSBT ", style),
+            ]));
+            continue;
+        }
+        let cursor = if editing && is_selected { "_" } else { "" };
+        let value_style = if is_selected {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::raw(marker),
+            Span::styled(format!("{:<24}", label), label_style),
+            Span::styled(format!("{}{}", value, cursor), value_style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    if let Some(err) = &form.error {
         lines.push(Line::from(Span::styled(
             err.clone(),
             Style::default().fg(Color::Red),
         )));
-    } else if let Some(out) = &app.psbt.output {
-        for line in out.lines() {
-            lines.push(Line::from(line.to_string()));
-        }
-    } else {
+    } else if matches!(app.psbt.running_action, Some(PsbtRpcAction::Create)) {
         lines.push(Line::from(Span::styled(
-            "No action output yet.",
-            Style::default().fg(Color::DarkGray),
+            "Running walletcreatefundedpsbt...",
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
         )));
     }
 
-    if let Some(action) = app.psbt.running_action {
-        lines.push(Line::from(""));
+    let wallet = if app.wallet.wallet_name.is_empty() {
+        "default wallet".to_string()
+    } else {
+        format!("wallet: {}", app.wallet.wallet_name)
+    };
+    frame.render_widget(
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!("Create PSBT (walletcreatefundedpsbt, {})", wallet))
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup,
+    );
+}
+
+fn render_process_form(app: &App, frame: &mut Frame, area: Rect) {
+    let form = &app.psbt.process;
+    let rows = form.rows();
+    let popup = Layout::vertical([Constraint::Length((rows.len() as u16 + 8).min(area.height))])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8).min(100))])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+    frame.render_widget(Clear, popup);
+
+    let editing = app.input_mode == InputMode::PsbtForm;
+    let selected = form.selected.min(rows.len() - 1);
+    let label_style = Style::default().fg(Color::DarkGray);
+    let mut lines: Vec<Line> = Vec::new();
+    for (i, row) in rows.iter().enumerate() {
+        let (label, value) = match *row {
+            ProcessRow::Mode => (
+                "Process with".into(),
+                match form.mode {
+                    ProcessMode::Wallet => "wallet (walletprocesspsbt)".into(),
+                    ProcessMode::Descriptors => "descriptors (descriptorprocesspsbt)".into(),
+                },
+            ),
+            ProcessRow::Descriptor(n) => {
+                let d = &form.descriptors[n];
+                (
+                    if n == 0 {
+                        "Saved descriptors".into()
+                    } else {
+                        String::new()
+                    },
+                    format!("[{}] {}", if d.selected { "x" } else { " " }, d.descriptor),
+                )
+            }
+            ProcessRow::NewDescriptor => (
+                "New descriptor".into(),
+                if form.new_descriptor.is_empty() && !(editing && i == selected) {
+                    "none".into()
+                } else {
+                    form.new_descriptor.clone()
+                },
+            ),
+            ProcessRow::Sign => ("Sign".into(), yes_no(form.sign).into()),
+            ProcessRow::Sighash => ("Sighash type".into(), SIGHASH_TYPES[form.sighash].into()),
+            ProcessRow::Bip32Derivs => (
+                "Include bip32 derivations".into(),
+                yes_no(form.bip32derivs).into(),
+            ),
+            ProcessRow::Finalize => ("Finalize if complete".into(), yes_no(form.finalize).into()),
+            ProcessRow::Submit => (String::new(), String::new()),
+        };
+
+        let is_selected = i == selected;
+        let marker = if is_selected { ">> " } else { "   " };
+        if *row == ProcessRow::Submit {
+            let style = if is_selected {
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Green)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(Color::Green)
+            };
+            lines.push(Line::from(""));
+            lines.push(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(" Process PSBT ", style),
+            ]));
+            continue;
+        }
+        let cursor = if editing && is_selected { "_" } else { "" };
+        let value_style = if is_selected {
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+        };
+        lines.push(Line::from(vec![
+            Span::raw(marker),
+            Span::styled(format!("{:<27}", label), label_style),
+            Span::styled(format!("{}{}", value, cursor), value_style),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    if let Some(err) = &form.error {
+        lines.push(Line::from(Span::styled(
+            err.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    } else if let Some(action @ (PsbtRpcAction::WalletProcess | PsbtRpcAction::DescriptorProcess)) =
+        app.psbt.running_action
+    {
         lines.push(Line::from(Span::styled(
             format!("Running {}...", action_label(action)),
-            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        )));
+    } else if form.mode == ProcessMode::Descriptors
+        && crate::psbt_process::has_private_key(&form.new_descriptor)
+    {
+        lines.push(Line::from(Span::styled(
+            "New descriptor has private keys; it is used for this call only and not saved",
+            Style::default().fg(Color::Yellow),
         )));
     }
 
+    let title = match form.mode {
+        ProcessMode::Wallet if app.wallet.wallet_name.is_empty() => {
+            "Process PSBT (default wallet)".to_string()
+        }
+        ProcessMode::Wallet => format!("Process PSBT (wallet: {})", app.wallet.wallet_name),
+        ProcessMode::Descriptors => "Process PSBT (no wallet)".to_string(),
+    };
     frame.render_widget(
-        Paragraph::new(lines)
-            .block(Block::default().borders(Borders::ALL).title("Output").border_style(Style::default().fg(Color::Green)))
-            .scroll((app.psbt.scroll, 0)),
-        area,
+        Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(title)
+                .border_style(Style::default().fg(Color::Cyan)),
+        ),
+        popup,
     );
 }
 
-fn render_picker(app: &App, frame: &mut Frame, area: Rect) {
-    let popup = Layout::vertical([Constraint::Length(area.height.saturating_sub(6))])
+fn render_editor(app: &App, frame: &mut Frame, area: Rect) {
+    let editor = &app.psbt.editor;
+    let popup = Layout::vertical([Constraint::Percentage(70)])
         .flex(Flex::Center)
         .split(area);
-    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8))])
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8).min(100))])
         .flex(Flex::Center)
         .split(popup[0])[0];
-
     frame.render_widget(Clear, popup);
 
-    let title = match app.psbt.picker_mode {
-        PsbtFileMode::Load => format!("Load PSBT: {}", app.psbt.picker_dir.display()),
-        PsbtFileMode::Save => format!("Save PSBT: {} (file: {})", app.psbt.picker_dir.display(), app.psbt.save_name),
+    let title = if editor.replace {
+        format!(
+            "Edit PSBT: {}",
+            app.psbt.current().map(|p| p.name.as_str()).unwrap_or("")
+        )
+    } else {
+        "Paste PSBT (base64 or hex)".to_string()
     };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
 
-    let items: Vec<ListItem> = app
-        .psbt
-        .picker_entries
+    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
+    let width = chunks[0].width as usize;
+    let height = chunks[0].height as usize;
+    let (rows, (cursor_row, cursor_col)) = editor.text.wrapped(width);
+    let top = (cursor_row + 1).saturating_sub(height);
+    let lines: Vec<Line> = rows
         .iter()
-        .map(|entry| {
-            let prefix = if entry.is_dir { "d " } else { "f " };
-            let style = if entry.is_dir {
-                Style::default().fg(Color::Cyan)
-            } else {
-                Style::default()
-            };
-            ListItem::new(format!("{}{}", prefix, entry.name)).style(style)
+        .enumerate()
+        .skip(top)
+        .take(height)
+        .map(|(i, row)| {
+            if i != cursor_row {
+                return Line::from(row.clone());
+            }
+            let chars: Vec<char> = row.chars().collect();
+            let before: String = chars[..cursor_col.min(chars.len())].iter().collect();
+            let at: String = chars
+                .get(cursor_col)
+                .map_or(" ".to_string(), |c| c.to_string());
+            let after: String = chars.iter().skip(cursor_col + 1).collect();
+            Line::from(vec![
+                Span::raw(before),
+                Span::styled(at, Style::default().add_modifier(Modifier::REVERSED)),
+                Span::raw(after),
+            ])
         })
         .collect();
+    frame.render_widget(Paragraph::new(lines), chunks[0]);
 
-    let mut help = vec![
-        Span::styled("j/k", Style::default().fg(Color::DarkGray)),
-        Span::raw(" move  "),
-        Span::styled("Enter", Style::default().fg(Color::DarkGray)),
-        Span::raw(" open/select  "),
-        Span::styled("Esc", Style::default().fg(Color::DarkGray)),
-        Span::raw(" close"),
-    ];
-    if app.psbt.picker_mode == PsbtFileMode::Save {
-        help.push(Span::raw("  "));
-        help.push(Span::styled("w", Style::default().fg(Color::DarkGray)));
-        help.push(Span::raw(" write here  "));
-        help.push(Span::styled("e", Style::default().fg(Color::DarkGray)));
-        help.push(Span::raw(" edit filename"));
+    let status = match &editor.validation {
+        None => Line::from(Span::styled(
+            "Paste with your terminal (bracketed paste) or type; whitespace is ignored",
+            Style::default().fg(Color::DarkGray),
+        )),
+        Some(Ok(msg)) => Line::from(Span::styled(msg.clone(), Style::default().fg(Color::Green))),
+        Some(Err(e)) => Line::from(Span::styled(e.clone(), Style::default().fg(Color::Red))),
+    };
+    frame.render_widget(Paragraph::new(status), chunks[1]);
+}
+
+fn yes_no(b: bool) -> &'static str {
+    if b { "yes" } else { "no" }
+}
+
+fn render_qr_view(app: &App, frame: &mut Frame, area: Rect) {
+    let qr = &app.psbt.qr;
+    frame.render_widget(Clear, area);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("PSBT QR — {}", qr.format.label()))
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let chunks = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Min(0),
+        Constraint::Length(1),
+    ])
+    .split(inner);
+    let hl = Style::default().fg(Color::DarkGray);
+    let status = format!(
+        "frame {}/{}  {} fps{}  {} B/frame",
+        qr.frame + 1,
+        qr.frames.len(),
+        qr.fps,
+        if qr.paused { " (paused)" } else { "" },
+        qr.max_fragment(),
+    );
+    frame.render_widget(
+        Paragraph::new(status).alignment(Alignment::Center),
+        chunks[0],
+    );
+    frame.render_widget(
+        Paragraph::new(Line::from(vec![
+            Span::styled("f", hl),
+            Span::raw(" UR/BBQr  "),
+            Span::styled("[/]", hl),
+            Span::raw(" density  "),
+            Span::styled("+/-", hl),
+            Span::raw(" fps  "),
+            Span::styled("Space", hl),
+            Span::raw(" pause  "),
+            Span::styled("h/l", hl),
+            Span::raw(" step  "),
+            Span::styled("Esc", hl),
+            Span::raw(" close"),
+        ]))
+        .alignment(Alignment::Center),
+        chunks[2],
+    );
+
+    let body = chunks[1];
+    let rendered = match (&qr.error, qr.current()) {
+        (Some(err), _) => Err(err.clone()),
+        (None, Some(text)) => psbt_qr::render(text),
+        (None, None) => Err("nothing to show".to_string()),
+    };
+    let lines = match rendered {
+        Ok(lines) => lines,
+        Err(err) => {
+            frame.render_widget(
+                Paragraph::new(Span::styled(err, Style::default().fg(Color::Red)))
+                    .alignment(Alignment::Center),
+                body,
+            );
+            return;
+        }
+    };
+
+    let width = lines.first().map(|l| l.chars().count()).unwrap_or(0) as u16;
+    let height = lines.len() as u16;
+    if width > body.width || height > body.height {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                format!(
+                    "QR needs {}x{} cells but only {}x{} are available; lower the density with [ or enlarge the terminal",
+                    width, height, body.width, body.height
+                ),
+                Style::default().fg(Color::Yellow),
+            ))
+            .alignment(Alignment::Center),
+            body,
+        );
+        return;
     }
-    if app.input_mode == InputMode::PsbtSaveName {
-        help.push(Span::raw("  "));
-        help.push(Span::styled("[editing filename]", Style::default().fg(Color::Magenta)));
+
+    let qr_area = Layout::vertical([Constraint::Length(height)])
+        .flex(Flex::Center)
+        .split(body)[0];
+    let qr_area = Layout::horizontal([Constraint::Length(width)])
+        .flex(Flex::Center)
+        .split(qr_area)[0];
+    frame.render_widget(
+        Paragraph::new(lines.into_iter().map(Line::from).collect::<Vec<_>>())
+            .style(Style::default().fg(Color::White).bg(Color::Black)),
+        qr_area,
+    );
+}
+
+fn render_signers(app: &App, frame: &mut Frame, area: Rect) {
+    let signers = &app.psbt.signers;
+    let popup = Layout::vertical([Constraint::Length(
+        (signers.len() as u16 + 6).clamp(8, area.height),
+    )])
+    .flex(Flex::Center)
+    .split(area);
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8).min(70))])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+    frame.render_widget(Clear, popup);
+
+    let via = match &app.psbt.config.signer {
+        Some(command) => format!("via {}", command),
+        None => format!(
+            "via walletprocesspsbt on wallet '{}'",
+            app.wallet.wallet_name
+        ),
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(format!("External signers ({})", via))
+        .border_style(Style::default().fg(Color::Yellow));
+    let inner = block.inner(popup);
+    frame.render_widget(block, popup);
+    let chunks = Layout::vertical([Constraint::Min(0), Constraint::Length(2)]).split(inner);
+
+    if signers.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "No signers found. Start bitcoind with -signer=<hwi> or pass --signer <cmd>.",
+                Style::default().fg(Color::DarkGray),
+            )),
+            chunks[0],
+        );
+    } else {
+        let items: Vec<ListItem> = signers
+            .iter()
+            .map(|s| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(s.fingerprint.clone(), Style::default().fg(Color::Cyan)),
+                    Span::raw("  "),
+                    Span::raw(s.name.clone()),
+                ]))
+            })
+            .collect();
+        let mut state = ListState::default();
+        state.select(Some(app.psbt.signer_selected.min(signers.len() - 1)));
+        frame.render_stateful_widget(
+            List::new(items).highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            chunks[0],
+            &mut state,
+        );
     }
 
-    let chunks = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(popup);
-    let mut state = ListState::default();
-    if !app.psbt.picker_entries.is_empty() {
-        state.select(Some(
-            app.psbt
-                .picker_selected
-                .min(app.psbt.picker_entries.len().saturating_sub(1)),
-        ));
+    let hl = Style::default().fg(Color::DarkGray);
+    // Without --signer the node picks the device from its own -signer, not this list.
+    let sign = if app.psbt.config.signer.is_some() {
+        " sign active PSBT  "
+    } else {
+        " sign active PSBT with the wallet's own signer  "
+    };
+    let mut footer = vec![Line::from(vec![
+        Span::styled("Enter", hl),
+        Span::raw(sign),
+        Span::styled("Esc", hl),
+        Span::raw(" close"),
+    ])];
+    if let Some(action) = app.psbt.running_action {
+        footer.push(Line::from(Span::styled(
+            format!("Running {}... confirm on the device", action_label(action)),
+            Style::default().fg(Color::Magenta),
+        )));
+    } else if let Some(err) = &app.psbt.error {
+        footer.push(Line::from(Span::styled(
+            err.clone(),
+            Style::default().fg(Color::Red),
+        )));
     }
+    frame.render_widget(Paragraph::new(footer), chunks[1]);
+}
 
-    frame.render_stateful_widget(
-        List::new(items)
-            .block(
+fn render_broadcast_confirm(app: &App, check: &BroadcastCheck, frame: &mut Frame, area: Rect) {
+    let popup = Layout::vertical([Constraint::Length(12.min(area.height))])
+        .flex(Flex::Center)
+        .split(area);
+    let popup = Layout::horizontal([Constraint::Length(area.width.saturating_sub(8).min(90))])
+        .flex(Flex::Center)
+        .split(popup[0])[0];
+    frame.render_widget(Clear, popup);
+
+    let label = Style::default().fg(Color::DarkGray);
+    let row = |name: &str, value: String| {
+        Line::from(vec![
+            Span::styled(format!("{:<20}", name), label),
+            Span::raw(value),
+        ])
+    };
+    let (verdict, verdict_style) = if check.allowed {
+        (
+            "accepted by mempool policy".to_string(),
+            Style::default().fg(Color::Green),
+        )
+    } else {
+        (
+            format!(
+                "rejected: {}",
+                check.reject_reason.as_deref().unwrap_or("unknown reason")
+            ),
+            Style::default().fg(Color::Red),
+        )
+    };
+    let mut lines = vec![
+        row("txid", check.txid.clone()),
+        Line::from(vec![
+            Span::styled(format!("{:<20}", "testmempoolaccept"), label),
+            Span::styled(verdict, verdict_style),
+        ]),
+        row(
+            "vsize",
+            check
+                .vsize
+                .map(|v| format!("{} vB", v))
+                .unwrap_or_else(|| "-".into()),
+        ),
+        row(
+            "fee",
+            check
+                .fee
+                .map(|f| format!("{:.8} BTC ({} sat)", f, (f * 1e8).round() as i64))
+                .unwrap_or_else(|| "-".into()),
+        ),
+        row(
+            "effective fee rate",
+            check
+                .effective_feerate
+                .map(|r| format!("{:.2} sat/vB", r * 1e5))
+                .unwrap_or_else(|| "-".into()),
+        ),
+        row(
+            "max fee rate",
+            format!("{} sat/vB", app.psbt.config.max_fee_rate),
+        ),
+        Line::from(""),
+    ];
+    lines.push(if app.psbt.running_action.is_some() {
+        Line::from(Span::styled(
+            "Broadcasting...",
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        ))
+    } else if check.allowed {
+        Line::from(vec![
+            Span::styled("y", Style::default().fg(Color::Yellow)),
+            Span::raw(" broadcast with sendrawtransaction  "),
+            Span::styled("n/Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" cancel"),
+        ])
+    } else {
+        Line::from(vec![
+            Span::styled("Esc", Style::default().fg(Color::Yellow)),
+            Span::raw(" close"),
+        ])
+    });
+    if let Some(err) = &app.psbt.error {
+        lines.push(Line::from(Span::styled(
+            err.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    frame.render_widget(
+        Paragraph::new(lines).block(
             Block::default()
                 .borders(Borders::ALL)
-                .title(title)
-                .border_style(Style::default().fg(Color::Cyan)),
-            )
-            .highlight_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
-            .highlight_symbol(">> "),
+                .title("Broadcast transaction")
+                .border_style(Style::default().fg(Color::Yellow)),
+        ),
+        popup,
+    );
+}
+
+/// Action hints, messages and the last result, which `o` focuses as a tree.
+fn render_output_panel(app: &App, frame: &mut Frame, area: Rect) {
+    let tree_hint = if app.psbt.output_tree.is_some() {
+        " o=result"
+    } else {
+        ""
+    };
+    let mut lines: Vec<Line<'static>> = vec![
+        Line::from(vec![
+            Span::styled("Actions: ", Style::default().fg(Color::DarkGray)),
+            Span::raw(format!(
+                "n=create i=paste E=edit y=copy d=decode a=analyze p=process f=finalize u=utxoupdate e=signers b=broadcast q=qr c=combine J=join D=diff v=summary/fields/raw{}",
+                tree_hint
+            )),
+        ]),
+        Line::from(""),
+    ];
+
+    if let Some(err) = &app.psbt.error {
+        lines.push(Line::from(Span::styled(
+            err.clone(),
+            Style::default().fg(Color::Red),
+        )));
+    } else if let Some(out) = &app.psbt.output {
+        for line in out.lines() {
+            let color = match line.get(..2) {
+                Some("+ ") => Some(Color::Green),
+                Some("- ") | Some("! ") => Some(Color::Red),
+                Some("~ ") => Some(Color::Yellow),
+                _ => None,
+            };
+            lines.push(match color {
+                Some(color) => {
+                    Line::from(Span::styled(line.to_string(), Style::default().fg(color)))
+                }
+                None => Line::from(line.to_string()),
+            });
+        }
+    } else if app.psbt.output_tree.is_none() {
+        lines.push(Line::from(Span::styled(
+            "No action output yet.",
+            Style::default().fg(Color::DarkGray),
+        )));
+    }
+
+    if let Some(action) = app.psbt.running_action {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Running {}...", action_label(action)),
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        )));
+    }
+
+    let border = if app.psbt.output_focus {
+        Color::Cyan
+    } else {
+        Color::Green
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Output")
+        .border_style(Style::default().fg(border));
+    let Some(tree) = &app.psbt.output_tree else {
+        frame.render_widget(
+            Paragraph::new(lines)
+                .block(block)
+                .scroll((app.psbt.scroll, 0)),
+            area,
+        );
+        return;
+    };
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+    let chunks =
+        Layout::vertical([Constraint::Length(lines.len() as u16), Constraint::Min(1)]).split(inner);
+    frame.render_widget(
+        Paragraph::new(lines).scroll((app.psbt.scroll, 0)),
         chunks[0],
-        &mut state,
     );
-    frame.render_widget(Paragraph::new(Line::from(help)), chunks[1]);
+    json_tree::render(tree, Block::default(), frame, chunks[1]);
 }
 
 fn action_label(action: crate::app::PsbtRpcAction) -> &'static str {
@@ -164,7 +1048,16 @@ fn action_label(action: crate::app::PsbtRpcAction) -> &'static str {
         crate::app::PsbtRpcAction::Decode => "decodepsbt",
         crate::app::PsbtRpcAction::Analyze => "analyzepsbt",
         crate::app::PsbtRpcAction::WalletProcess => "walletprocesspsbt",
+        crate::app::PsbtRpcAction::DescriptorProcess => "descriptorprocesspsbt",
         crate::app::PsbtRpcAction::Finalize => "finalizepsbt",
         crate::app::PsbtRpcAction::UtxoUpdate => "utxoupdatepsbt",
+        crate::app::PsbtRpcAction::Create => "walletcreatefundedpsbt",
+        crate::app::PsbtRpcAction::Combine => "combinepsbt",
+        crate::app::PsbtRpcAction::Join => "joinpsbts",
+        crate::app::PsbtRpcAction::TestAccept => "finalizepsbt + testmempoolaccept",
+        crate::app::PsbtRpcAction::Broadcast => "sendrawtransaction",
+        crate::app::PsbtRpcAction::EnumerateSigners => "enumeratesigners",
+        crate::app::PsbtRpcAction::ExternalSign => "signer signtx",
+        crate::app::PsbtRpcAction::WalletSign => "walletprocesspsbt",
     }
 }
@@ -12,4 +12,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
         app.input_mode,
         "",
     );
+
+    if app.history.open {
+        super::call_history::render(app, frame, area);
+    }
 }
@@ -8,6 +8,8 @@ use ratatui::{
 
 use crate::app::{App, InputMode, SearchResult};
 use crate::format::*;
+use crate::json_tree::JsonTree;
+use crate::tabs::json_tree;
 
 pub fn render(app: &App, frame: &mut Frame, area: Rect) {
     let tx = &app.transactions;
@@ -39,7 +41,13 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
             chunks[1],
         );
     } else if let Some(result) = &tx.result {
-        render_result(result, tx.result_scroll, frame, chunks[1]);
+        render_result(
+            result,
+            tx.decoded.as_ref(),
+            tx.result_scroll,
+            frame,
+            chunks[1],
+        );
     } else {
         let block = Block::default()
             .borders(Borders::ALL)
@@ -87,13 +95,16 @@ fn render_search_input(app: &App, frame: &mut Frame, area: Rect) {
     );
 }
 
-fn render_result(result: &SearchResult, scroll: u16, frame: &mut Frame, area: Rect) {
+/// The summary, with the decoded transaction as a tree below it when there is one.
+fn render_result(
+    result: &SearchResult,
+    decoded: Option<&JsonTree>,
+    scroll: u16,
+    frame: &mut Frame,
+    area: Rect,
+) {
     let lines = match result {
-        SearchResult::Mempool {
-            txid,
-            entry,
-            decoded,
-        } => {
+        SearchResult::Mempool { txid, entry, .. } => {
             let base_fee = entry.fees.base.as_f64().unwrap_or(0.0);
             let fee_rate = if entry.vsize > 0 {
                 let fee_sats = base_fee * 100_000_000.0;
@@ -101,7 +112,7 @@ fn render_result(result: &SearchResult, scroll: u16, frame: &mut Frame, area: Re
             } else {
                 "—".into()
             };
-            let mut lines = vec![
+            vec![
                 kv(
                     "Status",
                     "MEMPOOL",
@@ -125,11 +136,9 @@ fn render_result(result: &SearchResult, scroll: u16, frame: &mut Frame, area: Re
                     Style::default(),
                 ),
                 kv("Age", fmt_relative_time(entry.time), Style::default()),
-            ];
-            append_decoded(&mut lines, decoded);
-            lines
+            ]
         }
-        SearchResult::Confirmed { txid, tx, decoded } => {
+        SearchResult::Confirmed { txid, tx, .. } => {
             let mut lines = vec![
                 kv(
                     "Status",
@@ -154,7 +163,6 @@ fn render_result(result: &SearchResult, scroll: u16, frame: &mut Frame, area: Re
             if let Some(bt) = tx.blocktime {
                 lines.push(kv("Block Age", fmt_relative_time(bt), Style::default()));
             }
-            append_decoded(&mut lines, decoded);
             lines
         }
     };
@@ -164,7 +172,21 @@ fn render_result(result: &SearchResult, scroll: u16, frame: &mut Frame, area: Re
         .title("Result")
         .border_style(Style::default().fg(Color::Cyan));
 
-    frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+    let Some(tree) = decoded else {
+        frame.render_widget(Paragraph::new(lines).block(block).scroll((scroll, 0)), area);
+        return;
+    };
+    let chunks = Layout::vertical([
+        Constraint::Length(lines.len() as u16 + 2),
+        Constraint::Min(0),
+    ])
+    .split(area);
+    frame.render_widget(Paragraph::new(lines).block(block), chunks[0]);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title("Decoded")
+        .border_style(Style::default().fg(Color::Cyan));
+    json_tree::render(tree, block, frame, chunks[1]);
 }
 
 fn kv(key: &str, value: impl Into<String>, value_style: Style) -> Line<'static> {
@@ -173,18 +195,3 @@ fn kv(key: &str, value: impl Into<String>, value_style: Style) -> Line<'static>
         Span::styled(Into::<String>::into(value), value_style),
     ])
 }
-
-fn append_decoded(lines: &mut Vec<Line<'static>>, decoded: &Option<String>) {
-    if let Some(decoded) = decoded {
-        lines.push(Line::from(""));
-        lines.push(Line::from(Span::styled(
-            "Decoded",
-            Style::default()
-                .fg(Color::Cyan)
-                .add_modifier(Modifier::BOLD),
-        )));
-        for line in decoded.lines() {
-            lines.push(Line::from(Span::raw(line.to_string())));
-        }
-    }
-}
@@ -19,6 +19,8 @@ pub fn render(app: &App, frame: &mut Frame, area: Rect) {
 
     if app.input_mode == InputMode::WalletPicker {
         render_wallet_picker(app, frame, area);
+    } else if app.history.open {
+        super::call_history::render(app, frame, area);
     }
 }
 
@@ -62,12 +64,18 @@ fn render_wallet_picker(app: &App, frame: &mut Frame, area: Rect) {
 
     let list = List::new(items)
         .block(block)
-        .highlight_style(Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD))
+        .highlight_style(
+            Style::default()
+                .fg(Color::Magenta)
+                .add_modifier(Modifier::BOLD),
+        )
         .highlight_symbol(">> ");
 
     let mut state = ListState::default();
     if !wallets.is_empty() {
-        state.select(Some(app.wallet.picker_index.min(wallets.len().saturating_sub(1))));
+        state.select(Some(
+            app.wallet.picker_index.min(wallets.len().saturating_sub(1)),
+        ));
     }
     frame.render_stateful_widget(list, popup, &mut state);
 }
@@ -7,6 +7,7 @@ use ratatui::{
 };
 
 use crate::app::App;
+use crate::tabs::json_tree;
 
 pub fn render(app: &App, frame: &mut Frame, area: Rect) {
     let zmq = &app.zmq;
@@ -104,14 +105,6 @@ fn render_block_popup(app: &App, frame: &mut Frame, area: Rect) {
 
     frame.render_widget(Clear, popup);
 
-    let text = if zmq.block_popup_loading {
-        "Loading block details...".to_string()
-    } else if let Some(err) = &zmq.block_popup_error {
-        err.clone()
-    } else {
-        zmq.block_popup.clone().unwrap_or_default()
-    };
-
     let border = if zmq.block_popup_error.is_some() {
         Color::Red
     } else {
@@ -122,10 +115,14 @@ fn render_block_popup(app: &App, frame: &mut Frame, area: Rect) {
         .title("Block Details (Esc to close)")
         .border_style(Style::default().fg(border));
 
-    frame.render_widget(
-        Paragraph::new(text)
-            .block(block)
-            .scroll((zmq.block_popup_scroll, 0)),
-        popup,
-    );
+    if let Some(tree) = &zmq.block_popup {
+        json_tree::render(tree, block, frame, popup);
+        return;
+    }
+    let text = if zmq.block_popup_loading {
+        "Loading block details...".to_string()
+    } else {
+        zmq.block_popup_error.clone().unwrap_or_default()
+    };
+    frame.render_widget(Paragraph::new(text).block(block), popup);
 }
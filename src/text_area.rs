@@ -0,0 +1,152 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// A small multi-line text editor. The cursor column counts characters, not bytes.
+#[derive(Default)]
+pub struct TextArea {
+    pub lines: Vec<String>,
+    pub row: usize,
+    pub col: usize,
+}
+
+impl TextArea {
+    pub fn new(text: &str) -> Self {
+        let mut area = TextArea::default();
+        area.set_text(text);
+        area
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.lines = text.lines().map(str::to_string).collect();
+        if self.lines.is_empty() {
+            self.lines.push(String::new());
+        }
+        self.row = self.lines.len() - 1;
+        self.col = self.line_len(self.row);
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    fn line_len(&self, row: usize) -> usize {
+        self.lines.get(row).map_or(0, |l| l.chars().count())
+    }
+
+    fn byte_index(&self, row: usize, col: usize) -> usize {
+        let line = &self.lines[row];
+        line.char_indices().nth(col).map_or(line.len(), |(i, _)| i)
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        if c == '\n' {
+            let at = self.byte_index(self.row, self.col);
+            let rest = self.lines[self.row].split_off(at);
+            self.row += 1;
+            self.lines.insert(self.row, rest);
+            self.col = 0;
+        } else {
+            let at = self.byte_index(self.row, self.col);
+            self.lines[self.row].insert(at, c);
+            self.col += 1;
+        }
+    }
+
+    /// Inserts pasted text at the cursor; CRLF line endings are normalized.
+    pub fn insert_str(&mut self, text: &str) {
+        for c in text.replace("\r\n", "\n").replace('\r', "\n").chars() {
+            if c == '\n' || !c.is_control() {
+                self.insert_char(c);
+            }
+        }
+    }
+
+    pub fn backspace(&mut self) {
+        if self.col > 0 {
+            let at = self.byte_index(self.row, self.col - 1);
+            self.lines[self.row].remove(at);
+            self.col -= 1;
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.row -= 1;
+            self.col = self.line_len(self.row);
+            self.lines[self.row].push_str(&line);
+        }
+    }
+
+    pub fn delete(&mut self) {
+        if self.col < self.line_len(self.row) {
+            let at = self.byte_index(self.row, self.col);
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        }
+    }
+
+    /// Applies an editing or cursor key. Returns false for keys it doesn't handle.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.set_text("");
+            }
+            KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.insert_char(c)
+            }
+            KeyCode::Enter => self.insert_char('\n'),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.line_len(self.row);
+            }
+            KeyCode::Right if self.col < self.line_len(self.row) => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = 0;
+            }
+            KeyCode::Up if self.row > 0 => {
+                self.row -= 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Down if self.row + 1 < self.lines.len() => {
+                self.row += 1;
+                self.col = self.col.min(self.line_len(self.row));
+            }
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(self.row),
+            KeyCode::Left | KeyCode::Right | KeyCode::Up | KeyCode::Down => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Hard-wraps the text to `width` columns and returns the visual lines plus the
+    /// cursor's visual (row, column).
+    pub fn wrapped(&self, width: usize) -> (Vec<String>, (usize, usize)) {
+        let width = width.max(1);
+        let mut out = Vec::new();
+        let mut cursor = (0, 0);
+        for (row, line) in self.lines.iter().enumerate() {
+            let chars: Vec<char> = line.chars().collect();
+            if row == self.row {
+                cursor = (out.len() + self.col / width, self.col % width);
+            }
+            if chars.is_empty() {
+                out.push(String::new());
+            }
+            for chunk in chars.chunks(width) {
+                out.push(chunk.iter().collect());
+            }
+            // A cursor at the end of a line that fills the width wraps onto a new row.
+            if row == self.row
+                && !chars.is_empty()
+                && self.col == chars.len()
+                && self.col.is_multiple_of(width)
+            {
+                out.push(String::new());
+            }
+        }
+        (out, cursor)
+    }
+}
@@ -6,7 +6,7 @@ use ratatui::{
     widgets::{Paragraph, Tabs},
 };
 
-use crate::app::{App, BrowserPane, Focus, InputMode, Tab};
+use crate::app::{App, BrowserPane, Focus, InputMode, PsbtView, Tab};
 
 pub fn render(app: &App, frame: &mut Frame) {
     let chunks = Layout::vertical([
@@ -18,9 +18,31 @@ pub fn render(app: &App, frame: &mut Frame) {
 
     render_tab_bar(app, frame, chunks[0]);
     render_content(app, frame, chunks[1]);
+    if app.macros.open {
+        crate::tabs::macros::render(app, frame, chunks[1]);
+    }
+    if app.result_export.picker.open {
+        render_export_picker(app, frame, chunks[1]);
+    }
     render_footer(app, frame, chunks[2]);
 }
 
+fn render_export_picker(app: &App, frame: &mut Frame, area: Rect) {
+    let size = app
+        .result_export
+        .pending
+        .as_ref()
+        .map_or(0, |export| export.text.len());
+    crate::tabs::file_picker::render(
+        &app.result_export.picker,
+        "result",
+        vec![Span::raw(format!("[{} bytes]", size))],
+        app.input_mode == InputMode::SaveName,
+        frame,
+        area,
+    );
+}
+
 fn render_tab_bar(app: &App, frame: &mut Frame, area: Rect) {
     let titles: Vec<Line> = Tab::ALL.iter().map(|t| Line::from(t.title())).collect();
     let selected = Tab::ALL.iter().position(|t| *t == app.tab).unwrap_or(0);
@@ -38,7 +60,22 @@ fn render_tab_bar(app: &App, frame: &mut Frame, area: Rect) {
         .highlight_style(highlight)
         .divider("│");
 
-    frame.render_widget(tabs, area);
+    if app.read_only {
+        let cols = Layout::horizontal([Constraint::Min(0), Constraint::Length(11)]).split(area);
+        frame.render_widget(tabs, cols[0]);
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                " READ-ONLY ",
+                Style::default()
+                    .fg(Color::Black)
+                    .bg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            cols[1],
+        );
+    } else {
+        frame.render_widget(tabs, area);
+    }
 }
 
 fn render_content(app: &App, frame: &mut Frame, area: Rect) {
@@ -57,6 +94,37 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
     let hl = Style::default().fg(Color::Magenta);
 
     let left_spans = match app.input_mode {
+        InputMode::Normal if app.macros.open => match &app.macros.run {
+            Some(run) if run.confirm.is_some() => vec![
+                Span::styled("Enter", hl),
+                Span::raw(" run  "),
+                Span::styled("Esc", hl),
+                Span::raw(" cancel"),
+            ],
+            Some(_) if app.macros.running() => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" scroll  "),
+                Span::styled("Esc", hl),
+                Span::raw(" stop"),
+            ],
+            Some(_) => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" scroll  "),
+                Span::styled("r", hl),
+                Span::raw(" run again  "),
+                Span::styled("Esc", hl),
+                Span::raw(" back"),
+            ],
+            None => vec![
+                Span::raw("type to filter  "),
+                Span::styled("↑/↓", hl),
+                Span::raw(" select  "),
+                Span::styled("Enter", hl),
+                Span::raw(" run  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
+        },
         InputMode::Normal => match app.focus {
             Focus::TabBar => vec![
                 Span::styled("D", hl),
@@ -74,6 +142,8 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                 Span::raw("ransactions "),
                 Span::styled("Z", hl),
                 Span::raw("MQ  "),
+                Span::styled("m", hl),
+                Span::raw(" macros  "),
                 Span::styled("q", hl),
                 Span::raw(" quit"),
             ],
@@ -84,6 +154,55 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                     &app.rpc
                 };
                 match browser.pane {
+                    _ if browser
+                        .confirm
+                        .as_ref()
+                        .is_some_and(|c| c.class.needs_typed_confirmation()) =>
+                    {
+                        vec![
+                            Span::raw("type the method name  "),
+                            Span::styled("Enter", hl),
+                            Span::raw(" call  "),
+                            Span::styled("Esc", hl),
+                            Span::raw(" cancel"),
+                        ]
+                    }
+                    _ if browser.confirm.is_some() => vec![
+                        Span::styled("y/Enter", hl),
+                        Span::raw(" call  "),
+                        Span::styled("n/Esc", hl),
+                        Span::raw(" cancel"),
+                    ],
+                    _ if app.history.open => vec![
+                        Span::styled("j/k", hl),
+                        Span::raw(" select  "),
+                        Span::styled("Enter", hl),
+                        Span::raw(" re-run  "),
+                        Span::styled("e", hl),
+                        Span::raw(" edit  "),
+                        Span::styled("v", hl),
+                        Span::raw(" view result  "),
+                        Span::styled("*", hl),
+                        Span::raw(" star  "),
+                        Span::styled("Esc", hl),
+                        Span::raw(" close"),
+                    ],
+                    _ if browser.form.is_some() => vec![
+                        Span::styled("j/k", hl),
+                        Span::raw(" field  "),
+                        Span::styled("Enter", hl),
+                        Span::raw(" edit/toggle/add  "),
+                        Span::styled("Space", hl),
+                        Span::raw(" include/omit  "),
+                        Span::styled("x", hl),
+                        Span::raw(" remove  "),
+                        Span::styled("r", hl),
+                        Span::raw(" rename key  "),
+                        Span::styled("c/C-s", hl),
+                        Span::raw(" call  "),
+                        Span::styled("Esc", hl),
+                        Span::raw(" close"),
+                    ],
                     BrowserPane::Methods => {
                         let mut spans = vec![
                             Span::styled("j/k", hl),
@@ -95,6 +214,10 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                             spans.push(Span::styled("w", hl));
                             spans.push(Span::raw(" wallet  "));
                         }
+                        spans.push(Span::styled("H", hl));
+                        spans.push(Span::raw(" history  "));
+                        spans.push(Span::styled("R", hl));
+                        spans.push(Span::raw(" recheck node  "));
                         spans.push(Span::styled("Tab", hl));
                         spans.push(Span::raw(" pane  "));
                         spans.push(Span::styled("Esc", hl));
@@ -104,7 +227,9 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                     BrowserPane::Detail => {
                         let mut spans = vec![
                             Span::styled("Enter", hl),
-                            Span::raw(" call  "),
+                            Span::raw(" call/form  "),
+                            Span::styled("e", hl),
+                            Span::raw(" raw args  "),
                             Span::styled("j/k", hl),
                             Span::raw(" scroll  "),
                             Span::styled("C-u/d", hl),
@@ -112,7 +237,29 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                             Span::styled("/", hl),
                             Span::raw(" search  "),
                         ];
-                        if !browser.detail_matches.is_empty() {
+                        if browser.result.is_some() {
+                            spans.push(Span::styled("?", hl));
+                            spans.push(Span::raw(if browser.show_help {
+                                " result  "
+                            } else {
+                                " help  "
+                            }));
+                        }
+                        if browser.raw_result.is_some() {
+                            spans.push(Span::styled("|", hl));
+                            spans.push(Span::raw(" filter  "));
+                        }
+                        if browser.showing_result() {
+                            spans.push(Span::styled("Space", hl));
+                            spans.push(Span::raw(" fold  "));
+                            spans.push(Span::styled("y/Y/C", hl));
+                            spans.push(Span::raw(" copy value/path/all  "));
+                            spans.push(Span::styled("s/S", hl));
+                            spans.push(Span::raw(" save value/all  "));
+                            spans.push(Span::styled("a", hl));
+                            spans.push(Span::raw(" annotations  "));
+                        }
+                        if browser.match_count() > 0 {
                             spans.push(Span::styled("n/N", hl));
                             spans.push(Span::raw(" next/prev  "));
                         }
@@ -124,6 +271,22 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                     }
                 }
             }
+            Focus::Content if app.tab == Tab::Zmq && app.zmq.block_popup.is_some() => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" move  "),
+                Span::styled("Space", hl),
+                Span::raw(" fold  "),
+                Span::styled("h/l", hl),
+                Span::raw(" out/in  "),
+                Span::styled("y/Y/C", hl),
+                Span::raw(" copy value/path/all  "),
+                Span::styled("s/S", hl),
+                Span::raw(" save value/all  "),
+                Span::styled("a", hl),
+                Span::raw(" annotations  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
             Focus::Content if app.tab == Tab::Zmq => vec![
                 Span::styled("j/k", hl),
                 Span::raw(" select  "),
@@ -136,6 +299,26 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                 Span::styled("Esc", hl),
                 Span::raw(" back"),
             ],
+            Focus::Content
+                if app.tab == Tab::Transactions && app.transactions.decoded.is_some() =>
+            {
+                vec![
+                    Span::styled("/", hl),
+                    Span::raw(" search  "),
+                    Span::styled("j/k", hl),
+                    Span::raw(" move  "),
+                    Span::styled("Space", hl),
+                    Span::raw(" fold  "),
+                    Span::styled("y/Y/C", hl),
+                    Span::raw(" copy value/path/all  "),
+                    Span::styled("s/S", hl),
+                    Span::raw(" save value/all  "),
+                    Span::styled("a", hl),
+                    Span::raw(" annotations  "),
+                    Span::styled("Esc", hl),
+                    Span::raw(" back"),
+                ]
+            }
             Focus::Content if app.tab == Tab::Transactions => vec![
                 Span::styled("/", hl),
                 Span::raw(" search  "),
@@ -146,21 +329,179 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                 Span::styled("Esc", hl),
                 Span::raw(" back"),
             ],
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.output_focus => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" move  "),
+                Span::styled("Space", hl),
+                Span::raw(" fold  "),
+                Span::styled("y/Y/C", hl),
+                Span::raw(" copy value/path/all  "),
+                Span::styled("s/S", hl),
+                Span::raw(" save value/all  "),
+                Span::styled("a", hl),
+                Span::raw(" annotations  "),
+                Span::styled("Esc/o", hl),
+                Span::raw(" back"),
+            ],
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.qr.open => vec![
+                Span::styled("f", hl),
+                Span::raw(" UR/BBQr  "),
+                Span::styled("[/]", hl),
+                Span::raw(" density  "),
+                Span::styled("+/-", hl),
+                Span::raw(" fps  "),
+                Span::styled("Space", hl),
+                Span::raw(" pause  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.signers_open => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" select  "),
+                Span::styled("Enter", hl),
+                Span::raw(" sign  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.broadcast.is_some() => vec![
+                Span::styled("y", hl),
+                Span::raw(" broadcast  "),
+                Span::styled("n/Esc", hl),
+                Span::raw(" cancel"),
+            ],
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.create.open => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" field  "),
+                Span::styled("Enter", hl),
+                Span::raw(" edit/toggle/create  "),
+                Span::styled("o/x", hl),
+                Span::raw(" add/remove output  "),
+                Span::styled("f", hl),
+                Span::raw(" subtract fee  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.process.open => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" field  "),
+                Span::styled("Space", hl),
+                Span::raw(" toggle  "),
+                Span::styled("Enter", hl),
+                Span::raw(" edit/toggle/process  "),
+                Span::styled("x", hl),
+                Span::raw(" forget descriptor  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
+            Focus::Content
+                if app.tab == Tab::Psbt
+                    && app.psbt.view == PsbtView::Fields
+                    && !app.psbt.tree().is_empty() =>
+            {
+                vec![
+                    Span::styled("j/k", hl),
+                    Span::raw(" select  "),
+                    Span::styled("Enter/→", hl),
+                    Span::raw(" expand  "),
+                    Span::styled("h/←", hl),
+                    Span::raw(" collapse/parent  "),
+                    Span::styled("v", hl),
+                    Span::raw(" raw  "),
+                    Span::styled("[/]", hl),
+                    Span::raw(" psbt  "),
+                    Span::styled("m", hl),
+                    Span::raw(" mark  "),
+                    Span::styled("c/J/D", hl),
+                    Span::raw(" combine/join/diff  "),
+                    Span::styled("l/s", hl),
+                    Span::raw(" load/save  "),
+                    Span::styled("d/a/p/f/u", hl),
+                    Span::raw(" rpc  "),
+                    Span::styled("b", hl),
+                    Span::raw(" broadcast  "),
+                    Span::styled("q", hl),
+                    Span::raw(" qr  "),
+                    Span::styled("Esc", hl),
+                    Span::raw(" back"),
+                ]
+            }
+            Focus::Content if app.tab == Tab::Psbt && app.psbt.view == PsbtView::Summary => vec![
+                Span::styled("r", hl),
+                Span::raw(" refresh  "),
+                Span::styled("l/s", hl),
+                Span::raw(" load/save  "),
+                Span::styled("d/a/p/f/u", hl),
+                Span::raw(" rpc  "),
+                Span::styled("b", hl),
+                Span::raw(" broadcast  "),
+                Span::styled("q", hl),
+                Span::raw(" qr  "),
+                Span::styled("[/]", hl),
+                Span::raw(" psbt  "),
+                Span::styled("m/x", hl),
+                Span::raw(" mark/close  "),
+                Span::styled("c/J/D", hl),
+                Span::raw(" combine/join/diff  "),
+                Span::styled("v", hl),
+                Span::raw(" fields  "),
+                Span::styled("j/k", hl),
+                Span::raw(" scroll  "),
+                Span::styled("Esc", hl),
+                Span::raw(" back"),
+            ],
             Focus::Content if app.tab == Tab::Psbt => vec![
                 Span::styled("l/s", hl),
                 Span::raw(" load/save  "),
                 Span::styled("d/a/p/f/u", hl),
                 Span::raw(" decode/analyze/process/finalize/update  "),
+                Span::styled("b", hl),
+                Span::raw(" broadcast  "),
+                Span::styled("q", hl),
+                Span::raw(" qr  "),
+                Span::styled("[/]", hl),
+                Span::raw(" psbt  "),
+                Span::styled("m/x", hl),
+                Span::raw(" mark/close  "),
+                Span::styled("c/J/D", hl),
+                Span::raw(" combine/join/diff  "),
+                Span::styled("v", hl),
+                Span::raw(if app.psbt.view == PsbtView::Raw {
+                    " summary  "
+                } else {
+                    " raw  "
+                }),
                 Span::styled("j/k", hl),
                 Span::raw(" scroll  "),
                 Span::styled("Esc", hl),
                 Span::raw(" back"),
             ],
+            Focus::Content if app.tab == Tab::Peers && app.peers_popup_tree.is_some() => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" move  "),
+                Span::styled("Space", hl),
+                Span::raw(" fold  "),
+                Span::styled("y/Y/C", hl),
+                Span::raw(" copy value/path/all  "),
+                Span::styled("s/S", hl),
+                Span::raw(" save value/all  "),
+                Span::styled("a", hl),
+                Span::raw(" annotations  "),
+                Span::styled("r", hl),
+                Span::raw(" structured  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
             Focus::Content if app.tab == Tab::Peers && app.peers_popup.is_some() => vec![
                 Span::styled("j/k", hl),
                 Span::raw(" scroll  "),
                 Span::styled("C-u/d", hl),
                 Span::raw(" page  "),
+                Span::styled("r", hl),
+                Span::raw(if app.peers_popup_raw {
+                    " structured  "
+                } else {
+                    " raw JSON  "
+                }),
                 Span::styled("Esc", hl),
                 Span::raw(" close"),
             ],
@@ -172,11 +513,39 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
                 Span::styled("Esc", hl),
                 Span::raw(" close"),
             ],
+            Focus::Content if app.tab == Tab::Peers && app.addrman.open => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" select  "),
+                Span::styled("n", hl),
+                Span::raw(" network  "),
+                Span::styled("s", hl),
+                Span::raw(" services  "),
+                Span::styled("r", hl),
+                Span::raw(" refresh  "),
+                Span::styled("Esc", hl),
+                Span::raw(" close"),
+            ],
+            Focus::Content if app.tab == Tab::Peers && app.peers_events_focused => vec![
+                Span::styled("j/k", hl),
+                Span::raw(" scroll  "),
+                Span::styled("C-u/d", hl),
+                Span::raw(" page  "),
+                Span::styled("g/G", hl),
+                Span::raw(" newest/oldest  "),
+                Span::styled("e/Esc", hl),
+                Span::raw(" back to peers"),
+            ],
             Focus::Content if app.tab == Tab::Peers => vec![
                 Span::styled("j/k", hl),
                 Span::raw(" select  "),
                 Span::styled("Enter", hl),
                 Span::raw(" details  "),
+                Span::styled("e", hl),
+                Span::raw(" events  "),
+                Span::styled("a", hl),
+                Span::raw(" addrman  "),
+                Span::styled("x", hl),
+                Span::raw(" export  "),
                 Span::styled(":", hl),
                 Span::raw(" query  "),
                 Span::styled("?", hl),
@@ -197,11 +566,18 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
             Span::raw(" cancel"),
         ],
         InputMode::ArgInput => vec![
+            Span::raw("JSON values or name=value pairs  "),
             Span::styled("Enter", hl),
             Span::raw(" send  "),
             Span::styled("Esc", hl),
             Span::raw(" cancel"),
         ],
+        InputMode::ArgForm => vec![
+            Span::styled("Enter/Esc", hl),
+            Span::raw(" done  "),
+            Span::styled("Tab", hl),
+            Span::raw(" next field"),
+        ],
         InputMode::WalletPicker => vec![
             Span::styled("j/k", hl),
             Span::raw(" select  "),
@@ -210,7 +586,7 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
             Span::styled("Esc", hl),
             Span::raw(" cancel"),
         ],
-        InputMode::PsbtSaveName => vec![
+        InputMode::SaveName => vec![
             Span::styled("Enter", hl),
             Span::raw(" accept  "),
             Span::styled("Esc", hl),
@@ -228,6 +604,33 @@ fn render_footer(app: &App, frame: &mut Frame, area: Rect) {
             Span::styled("Esc", hl),
             Span::raw(" cancel"),
         ],
+        InputMode::DetailFilter => vec![
+            Span::raw("jq: paths .[] select() map length keys has not == < and or  "),
+            Span::styled("Enter", hl),
+            Span::raw(" keep  "),
+            Span::styled("C-u", hl),
+            Span::raw(" clear  "),
+            Span::styled("Esc", hl),
+            Span::raw(" remove filter"),
+        ],
+        InputMode::PsbtForm => vec![
+            Span::styled("Enter/Esc", hl),
+            Span::raw(" done  "),
+            Span::styled("Tab", hl),
+            Span::raw(" next field"),
+        ],
+        InputMode::PsbtEditor => vec![
+            Span::styled("C-s", hl),
+            Span::raw(if app.psbt.editor.replace {
+                " update PSBT  "
+            } else {
+                " load PSBT  "
+            }),
+            Span::styled("C-u", hl),
+            Span::raw(" clear  "),
+            Span::styled("Esc", hl),
+            Span::raw(" cancel"),
+        ],
         InputMode::PeersQuery => vec![
             Span::styled("Tab", hl),
             Span::raw(" complete  "),
@@ -1,4 +1,10 @@
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
 use serde::Deserialize;
+use serde_json::Value;
+
+const SPEC: &str = include_str!("../openrpc.json");
 
 #[derive(Deserialize)]
 struct OpenRpc {
@@ -10,28 +16,102 @@ struct RawMethod {
     name: String,
     description: Option<String>,
     params: Vec<RawParam>,
+    result: Option<RawResult>,
     #[serde(rename = "x-bitcoin-category")]
     category: Option<String>,
 }
 
+#[derive(Deserialize)]
+struct RawResult {
+    schema: Option<Schema>,
+}
+
 #[derive(Deserialize)]
 struct RawParam {
     name: String,
     description: Option<String>,
     required: Option<bool>,
-    schema: Option<RawSchema>,
+    schema: Option<Schema>,
+    #[serde(rename = "x-bitcoin-aliases", default)]
+    aliases: Vec<String>,
+    #[serde(rename = "x-bitcoin-type-str")]
+    type_str: Option<Vec<String>>,
 }
 
-#[derive(Deserialize)]
-struct RawSchema {
+/// The subset of JSON Schema that openrpc.json uses for params and results.
+#[derive(Clone, Default, Deserialize)]
+pub struct Schema {
     #[serde(rename = "type")]
-    schema_type: Option<String>,
+    pub schema_type: Option<String>,
+    pub description: Option<String>,
+    pub pattern: Option<String>,
+    pub items: Option<Box<Schema>>,
+    #[serde(default)]
+    pub properties: BTreeMap<String, Schema>,
+    #[serde(default)]
+    pub required: Vec<String>,
+    /// `true`, `false` or a schema for the values of free-form keys.
+    #[serde(rename = "additionalProperties")]
+    pub additional_properties: Option<Value>,
+    #[serde(rename = "oneOf", default)]
+    pub one_of: Vec<Schema>,
+    /// Core's own type description where the JSON type is too narrow, e.g.
+    /// `["", "string or numeric"]`.
+    #[serde(rename = "x-bitcoin-type-str")]
+    pub type_str: Option<Vec<String>>,
+    /// Unit hint for results, e.g. `unix-time`.
+    #[serde(rename = "x-bitcoin-unit")]
+    pub unit: Option<String>,
+}
+
+impl Schema {
+    /// Short type name for display, e.g. `string`, `number|array`, `string or numeric`.
+    pub fn type_label(&self) -> String {
+        if let Some(label) = self.type_str.as_ref().and_then(|t| t.get(1)) {
+            return label.clone();
+        }
+        match &self.schema_type {
+            Some(t) => t.clone(),
+            None if !self.one_of.is_empty() => self
+                .one_of
+                .iter()
+                .map(|s| s.type_label())
+                .collect::<Vec<_>>()
+                .join("|"),
+            None => "any".to_string(),
+        }
+    }
+
+    /// Schema for values under free-form keys, if the object allows them.
+    pub fn additional_schema(&self) -> Option<Schema> {
+        match &self.additional_properties {
+            Some(Value::Bool(true)) => Some(Schema::default()),
+            Some(v @ Value::Object(_)) => serde_json::from_value(v.clone()).ok(),
+            _ => None,
+        }
+    }
 }
 
 pub struct RpcMethod {
     pub name: String,
     pub description: String,
     pub params: Vec<RpcParam>,
+    /// Schema of the result, used for the Returns help and result annotations.
+    pub result: Option<Schema>,
+    pub availability: Availability,
+}
+
+/// Whether the connected node has a method, once its `help` has been read.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Availability {
+    /// Not checked against the node yet.
+    #[default]
+    Unknown,
+    Available,
+    /// In the bundled spec but not on the node.
+    Missing,
+    /// On the node but not in the bundled spec; documented from its help text.
+    HelpOnly,
 }
 
 pub struct RpcParam {
@@ -39,6 +119,9 @@ pub struct RpcParam {
     pub description: String,
     pub required: bool,
     pub schema_type: String,
+    pub schema: Schema,
+    /// Other names Core accepts for this param in named calls.
+    pub aliases: Vec<String>,
 }
 
 pub fn load_wallet_methods() -> Vec<RpcMethod> {
@@ -48,13 +131,40 @@ pub fn load_wallet_methods() -> Vec<RpcMethod> {
         .collect()
 }
 
+/// The wallet's PSBT methods, which the PSBT tab runs instead of the Wallet tab.
+pub fn load_wallet_psbt_methods() -> Vec<RpcMethod> {
+    load_methods(|cat| matches!(cat, Some("wallet")))
+        .into_iter()
+        .filter(|m| m.name.to_lowercase().contains("psbt"))
+        .collect()
+}
+
 pub fn load_non_wallet_methods() -> Vec<RpcMethod> {
     load_methods(|cat| !matches!(cat, Some("wallet")))
 }
 
+/// The bundled spec's category of a method, e.g. `wallet` or `blockchain`.
+pub fn method_category(name: &str) -> Option<&'static str> {
+    static CATEGORIES: OnceLock<BTreeMap<String, String>> = OnceLock::new();
+    CATEGORIES
+        .get_or_init(|| {
+            let spec: OpenRpc = serde_json::from_str(SPEC).expect("invalid openrpc.json");
+            spec.methods
+                .into_iter()
+                .filter_map(|m| Some((m.name, m.category?)))
+                .collect()
+        })
+        .get(name)
+        .map(String::as_str)
+}
+
+/// Names of every method in the bundled spec.
+pub fn bundled_method_names() -> Vec<String> {
+    load_methods(|_| true).into_iter().map(|m| m.name).collect()
+}
+
 fn load_methods(filter: impl Fn(Option<&str>) -> bool) -> Vec<RpcMethod> {
-    let json = include_str!("../openrpc.json");
-    let spec: OpenRpc = serde_json::from_str(json).expect("invalid openrpc.json");
+    let spec: OpenRpc = serde_json::from_str(SPEC).expect("invalid openrpc.json");
 
     let mut methods: Vec<RpcMethod> = spec
         .methods
@@ -66,16 +176,23 @@ fn load_methods(filter: impl Fn(Option<&str>) -> bool) -> Vec<RpcMethod> {
             params: m
                 .params
                 .into_iter()
-                .map(|p| RpcParam {
-                    name: p.name,
-                    description: p.description.unwrap_or_default(),
-                    required: p.required.unwrap_or(false),
-                    schema_type: p
-                        .schema
-                        .and_then(|s| s.schema_type)
-                        .unwrap_or_else(|| "any".into()),
+                .map(|p| {
+                    let mut schema = p.schema.unwrap_or_default();
+                    if schema.type_str.is_none() {
+                        schema.type_str = p.type_str;
+                    }
+                    RpcParam {
+                        name: p.name,
+                        description: p.description.unwrap_or_default(),
+                        required: p.required.unwrap_or(false),
+                        schema_type: schema.type_label(),
+                        schema,
+                        aliases: p.aliases,
+                    }
                 })
                 .collect(),
+            result: m.result.and_then(|r| r.schema),
+            availability: Availability::Unknown,
         })
         .collect();
 